@@ -1,68 +1,93 @@
-use crate::init_parse;
-use crate::wrap_message;
+use hldemo::NetMsgData;
+use rayon::prelude::*;
 
-use super::*;
+use crate::netmsg_doer::parse_netmsg_immutable;
+use crate::{init_parse, wrap_message};
 
-use demosuperimpose_goldsrc::netmsg_doer::parse_netmsg_immutable;
-use rayon::prelude::*;
+use super::pipeline::{DemoPass, PassContext};
+use super::*;
 
 /// Adds entry index and frame_index on screen.
+///
+/// Only adds messages so frames don't depend on each other, they are done in parallel
+/// after the delta descriptions and user messages of LOADING are known.
 pub fn add_debug(demo: &mut Demo) {
     let (delta_decoders, custom_messages) = init_parse!(demo);
 
-    for (entry_idx, entry) in demo.directory.entries.iter_mut().skip(1).enumerate() {
+    for (entry_idx, entry) in demo.directory.entries.iter_mut().enumerate().skip(1) {
         entry
             .frames
             .par_iter_mut()
             .enumerate()
             .for_each(|(frame_idx, frame)| {
-                match &mut frame.data {
-                    FrameData::NetMsg((_, data)) => {
-                        let (_, mut messages) =
-                            parse_netmsg_immutable(data.msg, &delta_decoders, &custom_messages)
-                                .unwrap();
-
-                        let message = format!(
-                            "{} {} \n {} {}\0",
-                            entry_idx,
-                            frame_idx,
-                            data.info.ref_params.viewangles[0],
-                            data.info.ref_params.viewangles[1]
-                        );
-                        let message = message.as_bytes();
-
-                        let text = TeTextMessage {
-                            channel: 4,
-                            // (0, 0) is top left
-                            x: 0.48f32.coord_conversion(),
-                            y: 0.50f32.coord_conversion(),
-                            effect: 0,
-                            text_color: &[255, 255, 255, 0],
-                            effect_color: &[255, 255, 255, 0],
-                            fade_in_time: 25,
-                            fade_out_time: 76,
-                            hold_time: 60,
-                            effect_time: None,
-                            message,
-                        };
-
-                        let temp_entity = SvcTempEntity {
-                            entity_type: 29,
-                            entity: TempEntityEntity::TeTextMessage(text),
-                        };
-
-                        messages.push(wrap_message!(SvcTempEntity, temp_entity));
-
-                        let write = write_netmsg(messages, &delta_decoders, &custom_messages);
-
-                        data.msg = write.leak();
-                    }
-                    _ => (),
+                if let FrameData::NetMsg((_, data)) = &mut frame.data {
+                    let (_, mut messages) =
+                        parse_netmsg_immutable(data.msg, &delta_decoders, &custom_messages)
+                            .unwrap();
+
+                    messages.push(debug_message(entry_idx, frame_idx, data));
+
+                    let write = write_netmsg(messages, &delta_decoders, &custom_messages);
+                    data.msg = write.leak();
                 }
             });
     }
 }
 
+/// [`add_debug`] as a pass, for when other passes run too.
+pub struct AddDebug;
+
+impl<'a> DemoPass<'a> for AddDebug {
+    fn messages(
+        &mut self,
+        ctx: &PassContext<'_, 'a>,
+        data: &mut NetMsgData<'a>,
+        messages: &mut Vec<Message<'a>>,
+    ) -> Result<bool, String> {
+        // Nothing to see in LOADING.
+        if ctx.entry_idx == 0 {
+            return Ok(false);
+        }
+
+        messages.push(debug_message(ctx.entry_idx, ctx.frame_idx, data));
+
+        Ok(true)
+    }
+}
+
+fn debug_message<'a>(entry_idx: usize, frame_idx: usize, data: &NetMsgData) -> Message<'a> {
+    let message = format!(
+        "{} {} \n {} {}\0",
+        entry_idx,
+        frame_idx,
+        data.info.ref_params.viewangles[0],
+        data.info.ref_params.viewangles[1]
+    );
+    let message = message.leak().as_bytes();
+
+    let text = TeTextMessage {
+        channel: 4,
+        // (0, 0) is top left
+        x: 0.48f32.coord_conversion(),
+        y: 0.50f32.coord_conversion(),
+        effect: 0,
+        text_color: &[255, 255, 255, 0],
+        effect_color: &[255, 255, 255, 0],
+        fade_in_time: 25,
+        fade_out_time: 76,
+        hold_time: 60,
+        effect_time: None,
+        message,
+    };
+
+    let temp_entity = SvcTempEntity {
+        entity_type: 29,
+        entity: TempEntityEntity::TeTextMessage(text),
+    };
+
+    wrap_message!(SvcTempEntity, temp_entity)
+}
+
 trait CoordConversion {
     fn coord_conversion(&self) -> i16;
 }
@@ -0,0 +1,225 @@
+use std::io::Write;
+
+use bitvec::prelude::*;
+
+use crate::netmsg_doer::{
+    parse_single_netmsg,
+    utils::{record_bit_annotations, BitAnnotation},
+};
+
+use super::dump::DumpOptions;
+use super::*;
+
+/// One message with where its bits went.
+pub struct AnnotatedMessage<'a> {
+    /// Message name, or the error if it couldn't be parsed.
+    pub name: String,
+    /// Starts with the message type byte. On error it is everything left in the netmsg.
+    pub bytes: &'a [u8],
+    /// Reads in order, offsets from the start of `bytes`.
+    pub fields: Vec<AnnotatedField>,
+    pub is_error: bool,
+}
+
+pub struct AnnotatedField {
+    pub name: String,
+    pub bit_offset: usize,
+    pub bit_len: usize,
+}
+
+/// Parses a netmsg one message at a time and records every read.
+///
+/// Parsing stops at the first message that errors, which is the one to look at.
+pub fn annotate_netmsg<'a>(
+    msg: &'a [u8],
+    delta_decoders: &mut DeltaDecoderTable,
+    custom_messages: &mut HashMap<u8, SvcNewUserMsg<'a>>,
+) -> Vec<AnnotatedMessage<'a>> {
+    let mut res = vec![];
+    let mut i = msg;
+
+    while !i.is_empty() {
+        let (parsed, annotations) =
+            record_bit_annotations(|| parse_single_netmsg(i, delta_decoders, custom_messages));
+
+        let mut fields = vec![AnnotatedField {
+            name: "type".to_owned(),
+            bit_offset: 0,
+            bit_len: 8,
+        }];
+        fields.extend(
+            annotations
+                .iter()
+                .filter_map(|annotation| to_field(annotation, i)),
+        );
+
+        match parsed {
+            Ok((rest, message)) => {
+                res.push(AnnotatedMessage {
+                    name: message_name(&message),
+                    bytes: &i[..i.len() - rest.len()],
+                    fields,
+                    is_error: false,
+                });
+
+                i = rest;
+            }
+            Err(err) => {
+                res.push(AnnotatedMessage {
+                    name: format!("type {} failed: {:?}", i[0], err),
+                    bytes: i,
+                    fields,
+                    is_error: true,
+                });
+                break;
+            }
+        }
+    }
+
+    res
+}
+
+/// Lines a read up with the message. Reads outside of it are from something else.
+fn to_field(annotation: &BitAnnotation, message: &[u8]) -> Option<AnnotatedField> {
+    let message_start = message.as_ptr() as usize;
+    let message_end = message_start + message.len();
+
+    if annotation.start < message_start || annotation.start > message_end {
+        return None;
+    }
+
+    Some(AnnotatedField {
+        name: if annotation.name.is_empty() {
+            "?".to_owned()
+        } else {
+            annotation.name.clone()
+        },
+        bit_offset: (annotation.start - message_start) * 8 + annotation.bit_offset,
+        bit_len: annotation.bit_len,
+    })
+}
+
+impl<'a> AnnotatedMessage<'a> {
+    /// Every field with its offset, the bytes it sits in, its bits and its value.
+    pub fn render(&self, out: &mut impl Write) {
+        let bits = BitVec::<u8, Lsb0>::from_slice(self.bytes);
+
+        writeln!(out, "{} ({} bytes)", self.name, self.bytes.len()).unwrap();
+
+        for field in &self.fields {
+            if field.bit_offset >= bits.len() {
+                continue;
+            }
+
+            let end = (field.bit_offset + field.bit_len).min(bits.len());
+            let field_bits = &bits[field.bit_offset..end];
+
+            let first_byte = field.bit_offset / 8;
+            let last_byte = (end.max(1) - 1) / 8;
+            let hex: Vec<String> = self.bytes[first_byte..=last_byte.min(self.bytes.len() - 1)]
+                .iter()
+                .take(8)
+                .map(|byte| format!("{:02x}", byte))
+                .collect();
+            let hex = if last_byte - first_byte >= 8 {
+                format!("{} ..", hex.join(" "))
+            } else {
+                hex.join(" ")
+            };
+
+            // Least significant bit first, same as it is read.
+            let mut bit_string: String = field_bits
+                .iter()
+                .take(32)
+                .map(|bit| if *bit { '1' } else { '0' })
+                .collect();
+            if field_bits.len() > 32 {
+                bit_string.push_str("..");
+            }
+
+            let value = if !field_bits.is_empty() && field_bits.len() <= 32 {
+                format!("= {}", field_bits.load_le::<u32>())
+            } else {
+                String::new()
+            };
+
+            writeln!(
+                out,
+                "  {:>6}.{} {:>4}b  {:<26} {:<28} {} {}",
+                field.bit_offset / 8,
+                field.bit_offset % 8,
+                field.bit_len,
+                hex,
+                field.name,
+                bit_string,
+                value
+            )
+            .unwrap();
+        }
+
+        // Bits nobody read, usually padding or where it went wrong.
+        let last_read = self
+            .fields
+            .iter()
+            .map(|field| field.bit_offset + field.bit_len)
+            .max()
+            .unwrap_or(0);
+        let unread_from = (last_read + 7) / 8;
+        if unread_from < self.bytes.len() && self.is_error {
+            writeln!(out, "  unread:").unwrap();
+            for (row, chunk) in self.bytes[unread_from..].chunks(16).enumerate() {
+                let hex: Vec<String> = chunk.iter().map(|byte| format!("{:02x}", byte)).collect();
+                writeln!(out, "  {:>6}  {}", unread_from + row * 16, hex.join(" ")).unwrap();
+            }
+        }
+    }
+}
+
+/// Annotated hexdump of every netmsg, for [`super::dump::dump`] in bits mode.
+///
+/// Message name and time filters apply, entity filters don't.
+pub fn dump_bits(demo: &Demo, options: &DumpOptions, out: &mut impl Write) {
+    let mut delta_decoders = get_initial_delta();
+    let mut custom_messages = HashMap::<u8, SvcNewUserMsg>::new();
+
+    for (entry_idx, entry) in demo.directory.entries.iter().enumerate() {
+        for (frame_idx, frame) in entry.frames.iter().enumerate() {
+            let data = match &frame.data {
+                FrameData::NetMsg((_, data)) => data,
+                _ => continue,
+            };
+
+            let messages = annotate_netmsg(data.msg, &mut delta_decoders, &mut custom_messages);
+            let has_error = messages.iter().any(|message| message.is_error);
+
+            if !options.is_in_time(frame.time) && !has_error {
+                continue;
+            }
+
+            let messages: Vec<&AnnotatedMessage> = messages
+                .iter()
+                .filter(|message| message.is_error || options.is_listed_message(&message.name))
+                .collect();
+
+            if messages.is_empty() {
+                continue;
+            }
+
+            writeln!(
+                out,
+                "entry {} frame {} time {:.3}",
+                entry_idx, frame_idx, frame.time
+            )
+            .unwrap();
+
+            for message in messages {
+                message.render(out);
+            }
+
+            // Nothing after this can be trusted.
+            if has_error {
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use std::path::Path;
+
+use super::*;
+
+/// Sequences are offset by multiples of this so `delta_sequence` of `SvcDeltaPacketEntities`,
+/// which is only 8 bits, still points at the right frame without rewriting netmsg.
+const SEQUENCE_WINDOW: i32 = 256;
+
+/// Gap between demos when the last one has no frametime, 100 fps.
+const DEFAULT_FRAMETIME: f32 = 0.01;
+
+/// [`concat`] but opens the demos.
+pub fn concat_files<'a>(paths: &[impl AsRef<Path>]) -> Result<Demo<'a>, String> {
+    let demos = paths
+        .iter()
+        .map(open_demo_file)
+        .collect::<Result<Vec<Demo>, String>>()?;
+
+    concat(demos)
+}
+
+/// Appends every next demo to the first one.
+///
+/// A demo on the same map as the one before shares its playback entry, its LOADING frames play
+/// in there so the engine reconnects on its `SvcServerInfo` and playback goes on. A demo on
+/// another map keeps its LOADING entry as an entry of its own, so multi map runs chain.
+/// Frame times, client times, frame numbers and sequences continue from the previous demo,
+/// one frame after its last.
+///
+/// Only the first header is kept.
+pub fn concat<'a>(demos: Vec<Demo<'a>>) -> Result<Demo<'a>, String> {
+    let mut demos = demos.into_iter();
+    let mut res = demos
+        .next()
+        .ok_or_else(|| "concat needs at least one demo".to_owned())?;
+
+    // Header of the demo that was appended last.
+    let mut last_map = (res.header.map_name, res.header.map_crc);
+
+    for demo in demos {
+        let (last_time, last_frame, last_sequence, last_client_time, frametime) = demo_end(&res);
+        let first_client_time = first_client_time(&demo);
+
+        let time_offset = last_time + frametime;
+        // Client time is the server clock, same as frame time it goes on from the last one.
+        let client_time_offset = last_client_time + frametime - first_client_time;
+        let frame_offset = last_frame + 1;
+        let sequence_offset = (last_sequence / SEQUENCE_WINDOW + 1) * SEQUENCE_WINDOW;
+
+        let map = (demo.header.map_name, demo.header.map_crc);
+        let is_same_map = map == last_map;
+        last_map = map;
+
+        for (entry_idx, mut entry) in demo.directory.entries.into_iter().enumerate() {
+            let mut frames = take_frames(&mut entry);
+
+            for frame in &mut frames {
+                frame.time += time_offset;
+                frame.frame += frame_offset;
+
+                if let FrameData::NetMsg((_, data)) = &mut frame.data {
+                    data.info.ref_params.time += client_time_offset;
+
+                    data.incoming_sequence += sequence_offset;
+                    data.incoming_acknowledged += sequence_offset;
+                    data.outgoing_sequence += sequence_offset;
+                    data.last_reliable_sequence += sequence_offset;
+                }
+            }
+
+            let shared = if is_same_map {
+                res.directory.entries.iter_mut().skip(1).next_back()
+            } else {
+                None
+            };
+
+            match shared {
+                Some(shared) => {
+                    // Written back at the end by the writer.
+                    shared
+                        .frames
+                        .retain(|frame| !matches!(frame.data, FrameData::NextSection));
+                    // Playback already started.
+                    shared.frames.extend(
+                        frames
+                            .into_iter()
+                            .filter(|frame| !matches!(frame.data, FrameData::DemoStart)),
+                    );
+                    update_entry_end(shared);
+                }
+                None => {
+                    // 1 for Normal
+                    if entry_idx == 0 {
+                        entry.entry_type = 1;
+                    }
+
+                    entry.frames = frames;
+                    update_entry_end(&mut entry);
+                    res.directory.entries.push(entry);
+                }
+            }
+        }
+    }
+
+    Ok(res)
+}
+
+fn update_entry_end(entry: &mut DirectoryEntry) {
+    if let Some(last) = entry.frames.last() {
+        entry.frame_count = last.frame;
+        entry.track_time = last.time;
+    }
+}
+
+/// Last frame time, last frame number, biggest sequence, last client time and last frametime of
+/// the demo.
+fn demo_end(demo: &Demo) -> (f32, i32, i32, f32, f32) {
+    let (time, frame_number, sequence, client_time) = demo
+        .directory
+        .entries
+        .iter()
+        .flat_map(|entry| entry.frames.iter())
+        .fold(
+            (0., 0, 0, 0.),
+            |(time, frame_number, sequence, client_time), frame| {
+                let (frame_sequence, frame_client_time) = match &frame.data {
+                    FrameData::NetMsg((_, data)) => (
+                        data.incoming_sequence.max(data.outgoing_sequence),
+                        data.info.ref_params.time,
+                    ),
+                    _ => (0, 0.),
+                };
+
+                (
+                    frame.time.max(time),
+                    frame.frame.max(frame_number),
+                    frame_sequence.max(sequence),
+                    frame_client_time.max(client_time),
+                )
+            },
+        );
+
+    let frametime = demo
+        .directory
+        .entries
+        .iter()
+        .flat_map(|entry| entry.frames.iter())
+        .filter_map(|frame| match &frame.data {
+            FrameData::NetMsg((_, data)) => Some(data.info.ref_params.frametime),
+            _ => None,
+        })
+        .filter(|frametime| *frametime > 0.)
+        .last()
+        .unwrap_or(DEFAULT_FRAMETIME);
+
+    (time, frame_number, sequence, client_time, frametime)
+}
+
+/// Client time of the first net message, which is where the server clock was at.
+fn first_client_time(demo: &Demo) -> f32 {
+    demo.directory
+        .entries
+        .iter()
+        .flat_map(|entry| entry.frames.iter())
+        .find_map(|frame| match &frame.data {
+            FrameData::NetMsg((_, data)) => Some(data.info.ref_params.time),
+            _ => None,
+        })
+        .unwrap_or(0.)
+}
@@ -0,0 +1,379 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use super::json::decode_entity_field;
+use super::*;
+
+/// Differences between two demos, one line each.
+///
+/// Entries and frames are lined up by index. Messages of lined up netmsg frames are lined up by
+/// type so an added message doesn't make every message after it different. Entity updates are
+/// compared field by field and shown decoded. A netmsg that doesn't parse is a difference too.
+///
+/// Anything without `PartialEq` is compared by its `Debug` output.
+pub fn diff(left: &Demo, right: &Demo) -> Vec<String> {
+    let mut res: Vec<String> = vec![];
+
+    let mut left_parser = Parser::new();
+    let mut right_parser = Parser::new();
+
+    let left_entries = &left.directory.entries;
+    let right_entries = &right.directory.entries;
+
+    if left_entries.len() != right_entries.len() {
+        res.push(format!(
+            "entry count: {} -> {}",
+            left_entries.len(),
+            right_entries.len()
+        ));
+    }
+
+    for entry_idx in 0..left_entries.len().max(right_entries.len()) {
+        let (left_entry, right_entry) =
+            match (left_entries.get(entry_idx), right_entries.get(entry_idx)) {
+                (Some(left_entry), Some(right_entry)) => (left_entry, right_entry),
+                (Some(_), None) => {
+                    res.push(format!("entry {}: removed", entry_idx));
+                    continue;
+                }
+                (None, Some(_)) => {
+                    res.push(format!("entry {}: added", entry_idx));
+                    continue;
+                }
+                (None, None) => unreachable!(),
+            };
+
+        let prefix = format!("entry {}", entry_idx);
+        compare(
+            &mut res,
+            &prefix,
+            "entry_type",
+            left_entry.entry_type,
+            right_entry.entry_type,
+        );
+        compare(
+            &mut res,
+            &prefix,
+            "frame_count",
+            left_entry.frame_count,
+            right_entry.frame_count,
+        );
+        compare(
+            &mut res,
+            &prefix,
+            "track_time",
+            left_entry.track_time,
+            right_entry.track_time,
+        );
+
+        for frame_idx in 0..left_entry.frames.len().max(right_entry.frames.len()) {
+            let prefix = format!("entry {} frame {}", entry_idx, frame_idx);
+
+            let left_frame = left_entry.frames.get(frame_idx);
+            let right_frame = right_entry.frames.get(frame_idx);
+
+            // Parse anyway so both sides still know about delta descriptions later on.
+            let left_messages = left_frame.map(|frame| left_parser.parse(&frame.data));
+            let right_messages = right_frame.map(|frame| right_parser.parse(&frame.data));
+
+            let (left_frame, right_frame) = match (left_frame, right_frame) {
+                (Some(left_frame), Some(right_frame)) => (left_frame, right_frame),
+                (Some(frame), None) => {
+                    res.push(format!("{}: removed {}", prefix, frame_type(&frame.data)));
+                    continue;
+                }
+                (None, Some(frame)) => {
+                    res.push(format!("{}: added {}", prefix, frame_type(&frame.data)));
+                    continue;
+                }
+                (None, None) => unreachable!(),
+            };
+
+            compare(&mut res, &prefix, "time", left_frame.time, right_frame.time);
+            compare(
+                &mut res,
+                &prefix,
+                "frame",
+                left_frame.frame,
+                right_frame.frame,
+            );
+
+            if frame_type(&left_frame.data) != frame_type(&right_frame.data) {
+                res.push(format!(
+                    "{}: type {} -> {}",
+                    prefix,
+                    frame_type(&left_frame.data),
+                    frame_type(&right_frame.data)
+                ));
+                continue;
+            }
+
+            match (&left_frame.data, &right_frame.data) {
+                (FrameData::NetMsg((_, left_data)), FrameData::NetMsg((_, right_data))) => {
+                    compare_debug(&mut res, &prefix, "info", &left_data.info, &right_data.info);
+                    compare(
+                        &mut res,
+                        &prefix,
+                        "incoming_sequence",
+                        left_data.incoming_sequence,
+                        right_data.incoming_sequence,
+                    );
+                    compare(
+                        &mut res,
+                        &prefix,
+                        "incoming_acknowledged",
+                        left_data.incoming_acknowledged,
+                        right_data.incoming_acknowledged,
+                    );
+                    compare(
+                        &mut res,
+                        &prefix,
+                        "outgoing_sequence",
+                        left_data.outgoing_sequence,
+                        right_data.outgoing_sequence,
+                    );
+
+                    match (left_messages, right_messages) {
+                        (Some(Ok(left_messages)), Some(Ok(right_messages))) => diff_messages(
+                            &mut res,
+                            &prefix,
+                            (&left_messages, &left_parser.delta_decoders),
+                            (&right_messages, &right_parser.delta_decoders),
+                        ),
+                        (Some(Err(_)), Some(Err(_))) => {
+                            res.push(format!("{}: unparseable netmsg on both sides", prefix))
+                        }
+                        (Some(Err(_)), _) => {
+                            res.push(format!("{}: unparseable netmsg -> parsed", prefix))
+                        }
+                        _ => res.push(format!("{}: parsed netmsg -> unparseable", prefix)),
+                    }
+                }
+                (left_data, right_data) => {
+                    compare_debug(&mut res, &prefix, "data", left_data, right_data);
+                }
+            }
+        }
+    }
+
+    res
+}
+
+/// [`diff`] but opens the demos and prints.
+pub fn diff_files(left: &Path, right: &Path) -> Result<(), String> {
+    let left = open_demo_file(left)?;
+    let right = open_demo_file(right)?;
+
+    for line in diff(&left, &right) {
+        println!("{}", line);
+    }
+
+    Ok(())
+}
+
+/// Each demo needs its own delta decoders and user messages.
+struct Parser<'a> {
+    delta_decoders: DeltaDecoderTable,
+    custom_messages: HashMap<u8, SvcNewUserMsg<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new() -> Self {
+        Self {
+            delta_decoders: get_initial_delta(),
+            custom_messages: HashMap::new(),
+        }
+    }
+
+    /// Messages of a netmsg frame, nothing for other frames.
+    fn parse(&mut self, data: &FrameData<'a>) -> Result<Vec<Message<'a>>, ()> {
+        if let FrameData::NetMsg((_, data)) = data {
+            parse_netmsg(
+                data.msg,
+                &mut self.delta_decoders,
+                &mut self.custom_messages,
+            )
+            .map(|(_, messages)| messages)
+            .map_err(|_| ())
+        } else {
+            Ok(vec![])
+        }
+    }
+}
+
+/// Each side is its messages and the delta decoders they were parsed with.
+fn diff_messages(
+    res: &mut Vec<String>,
+    prefix: &str,
+    (left, left_decoders): (&[Message], &DeltaDecoderTable),
+    (right, right_decoders): (&[Message], &DeltaDecoderTable),
+) {
+    let left_names: Vec<String> = left.iter().map(message_name).collect();
+    let right_names: Vec<String> = right.iter().map(message_name).collect();
+
+    // Longest common subsequence of message names.
+    let mut lengths = vec![vec![0usize; right.len() + 1]; left.len() + 1];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            lengths[i][j] = if left_names[i] == right_names[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() || j < right.len() {
+        if i < left.len() && j < right.len() && left_names[i] == right_names[j] {
+            let prefix = format!("{} message {}", prefix, left_names[i]);
+
+            if !diff_entities(
+                res,
+                &prefix,
+                (&left[i], left_decoders),
+                (&right[j], right_decoders),
+            ) {
+                compare_debug(res, &prefix, "", &left[i], &right[j]);
+            }
+
+            i += 1;
+            j += 1;
+        } else if j < right.len() && (i == left.len() || lengths[i][j + 1] >= lengths[i + 1][j]) {
+            res.push(format!("{}: added message {}", prefix, right_names[j]));
+            j += 1;
+        } else {
+            res.push(format!("{}: removed message {}", prefix, left_names[i]));
+            i += 1;
+        }
+    }
+}
+
+/// Compares entity updates field by field. Returns false if these aren't entity updates.
+fn diff_entities(
+    res: &mut Vec<String>,
+    prefix: &str,
+    (left, left_decoders): (&Message, &DeltaDecoderTable),
+    (right, right_decoders): (&Message, &DeltaDecoderTable),
+) -> bool {
+    let (left, right) = match (entity_deltas(left), entity_deltas(right)) {
+        (Some(left), Some(right)) => (left, right),
+        _ => return false,
+    };
+
+    let indices: BTreeSet<u16> = left.keys().chain(right.keys()).cloned().collect();
+
+    for entity_index in indices {
+        let prefix = format!("{} entity {}", prefix, entity_index);
+
+        let (left, right) = match (left.get(&entity_index), right.get(&entity_index)) {
+            (Some(left), Some(right)) => (left, right),
+            (Some(_), None) => {
+                res.push(format!("{}: removed", prefix));
+                continue;
+            }
+            (None, Some(_)) => {
+                res.push(format!("{}: added", prefix));
+                continue;
+            }
+            (None, None) => unreachable!(),
+        };
+
+        let keys: BTreeSet<&String> = left.keys().chain(right.keys()).collect();
+
+        for key in keys {
+            let field = key.trim_end_matches('\0');
+            let left = left.get(key);
+            let right = right.get(key);
+
+            if left == right {
+                continue;
+            }
+
+            let left = field_text(key, left, left_decoders);
+            let right = field_text(key, right, right_decoders);
+            res.push(format!("{}: {} {} -> {}", prefix, field, left, right));
+        }
+    }
+
+    true
+}
+
+/// Entity index to its delta. Removed entities from delta updates have an empty delta.
+fn entity_deltas(message: &Message) -> Option<BTreeMap<u16, Delta>> {
+    match message {
+        Message::EngineMessage(EngineMessage::SvcPacketEntities(packet)) => Some(
+            packet
+                .entity_states
+                .iter()
+                .map(|entity| (entity.entity_index, entity.delta.clone()))
+                .collect(),
+        ),
+        Message::EngineMessage(EngineMessage::SvcDeltaPacketEntities(packet)) => Some(
+            packet
+                .entity_states
+                .iter()
+                .map(|entity| {
+                    let mut delta = entity.delta.clone().unwrap_or_default();
+                    if entity.remove_entity {
+                        delta.insert("remove\0".to_owned(), vec![1]);
+                    }
+
+                    (entity.entity_index, delta)
+                })
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+fn field_text(key: &str, bytes: Option<&Vec<u8>>, delta_decoders: &DeltaDecoderTable) -> String {
+    match bytes {
+        Some(bytes) => decode_entity_field(key, bytes, delta_decoders).to_string(),
+        None => "none".to_owned(),
+    }
+}
+
+fn frame_type(data: &FrameData) -> &'static str {
+    match data {
+        FrameData::DemoStart => "DemoStart",
+        FrameData::ConsoleCommand(_) => "ConsoleCommand",
+        FrameData::ClientData(_) => "ClientData",
+        FrameData::NextSection => "NextSection",
+        FrameData::Event(_) => "Event",
+        FrameData::WeaponAnim(_) => "WeaponAnim",
+        FrameData::Sound(_) => "Sound",
+        FrameData::DemoBuffer(_) => "DemoBuffer",
+        FrameData::NetMsg(_) => "NetMsg",
+    }
+}
+
+fn compare<T: PartialEq + std::fmt::Debug>(
+    res: &mut Vec<String>,
+    prefix: &str,
+    field: &str,
+    left: T,
+    right: T,
+) {
+    if left != right {
+        res.push(format!("{}: {} {:?} -> {:?}", prefix, field, left, right));
+    }
+}
+
+fn compare_debug<T: std::fmt::Debug>(
+    res: &mut Vec<String>,
+    prefix: &str,
+    field: &str,
+    left: &T,
+    right: &T,
+) {
+    let (left, right) = (format!("{:?}", left), format!("{:?}", right));
+
+    if left != right {
+        let separator = if field.is_empty() { "" } else { " " };
+        res.push(format!(
+            "{}:{}{} {} -> {}",
+            prefix, separator, field, left, right
+        ));
+    }
+}
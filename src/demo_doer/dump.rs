@@ -0,0 +1,254 @@
+use std::io::Write;
+
+use serde_json::{json, Value};
+
+use super::annotate::dump_bits;
+use super::json::{export_frame_data, message_to_value, JsonFrameData};
+use super::*;
+
+pub enum DumpFormat {
+    /// One line per frame and one indented line per message.
+    Text,
+    /// One JSON object per frame.
+    Ndjson,
+    /// Annotated hexdump of netmsg, see [`super::annotate`].
+    Bits,
+}
+
+pub struct DumpOptions {
+    format: DumpFormat,
+    messages: Vec<String>,
+    entities: Vec<u16>,
+    start: Option<f32>,
+    end: Option<f32>,
+}
+
+impl DumpOptions {
+    pub fn new() -> Self {
+        Self {
+            format: DumpFormat::Text,
+            messages: vec![],
+            entities: vec![],
+            start: None,
+            end: None,
+        }
+    }
+
+    pub fn ndjson(&mut self) -> &mut Self {
+        self.format = DumpFormat::Ndjson;
+        return self;
+    }
+
+    pub fn bits(&mut self) -> &mut Self {
+        self.format = DumpFormat::Bits;
+        return self;
+    }
+
+    /// Only messages with this name, like `SvcSound` or a user message name. Case insensitive.
+    pub fn add_message(&mut self, name: &str) -> &mut Self {
+        self.messages.push(name.to_lowercase());
+        return self;
+    }
+
+    /// Only entity messages and only the states of listed entities.
+    pub fn add_entity(&mut self, entity_index: u16) -> &mut Self {
+        self.entities.push(entity_index);
+        return self;
+    }
+
+    /// Only frames with time in between, in seconds.
+    pub fn time_range(&mut self, start: Option<f32>, end: Option<f32>) -> &mut Self {
+        self.start = start;
+        self.end = end;
+        return self;
+    }
+
+    pub fn get(&self) -> &Self {
+        return self;
+    }
+
+    fn has_message_filter(&self) -> bool {
+        !self.messages.is_empty() || !self.entities.is_empty()
+    }
+
+    pub(super) fn is_listed_message(&self, name: &str) -> bool {
+        self.messages.is_empty() || self.messages.contains(&name.to_lowercase())
+    }
+
+    pub(super) fn is_in_time(&self, time: f32) -> bool {
+        self.start.map_or(true, |start| time >= start) && self.end.map_or(true, |end| time <= end)
+    }
+}
+
+/// Writes every frame and every decoded message.
+///
+/// Delta fields are decoded to numbers and strings with the delta descriptions of the demo.
+pub fn dump(demo: &Demo, options: &DumpOptions, out: &mut impl Write) -> Result<(), String> {
+    if let DumpFormat::Bits = options.format {
+        dump_bits(demo, options, out);
+        return Ok(());
+    }
+
+    let mut delta_decoders = get_initial_delta();
+    let mut custom_messages = HashMap::<u8, SvcNewUserMsg>::new();
+
+    for (entry_idx, entry) in demo.directory.entries.iter().enumerate() {
+        for (frame_idx, frame) in entry.frames.iter().enumerate() {
+            let is_in_time = options.is_in_time(frame.time);
+
+            // Parsed no matter what so delta descriptions are always up to date.
+            let mut data = export_frame_data(
+                &frame.data,
+                &mut delta_decoders,
+                &mut custom_messages,
+                (entry_idx, frame_idx),
+                |message, delta_decoders| {
+                    if !is_in_time {
+                        return Ok(None);
+                    }
+
+                    let message = match filter_message(message, options) {
+                        Some(message) => message,
+                        None => return Ok(None),
+                    };
+                    let name = message_name(&message);
+                    let value = message_to_json(&message, delta_decoders)?;
+                    Ok(Some((name, value)))
+                },
+            )?;
+
+            if !is_in_time {
+                continue;
+            }
+
+            let messages: Vec<(String, Value)> = match &mut data {
+                JsonFrameData::NetMsg { messages, .. } => std::mem::take(messages),
+                _ => vec![],
+            };
+
+            if options.has_message_filter() && messages.is_empty() {
+                continue;
+            }
+
+            // {"NetMsg": {..}} or "DemoStart"
+            let data = serde_json::to_value(&data).map_err(|err| err.to_string())?;
+            let (frame_type, mut data) = match data {
+                Value::Object(object) if object.len() == 1 => object.into_iter().next().unwrap(),
+                Value::String(frame_type) => (frame_type, Value::Null),
+                value => ("Unknown".to_owned(), value),
+            };
+
+            // Messages are written on their own.
+            if let Value::Object(data) = &mut data {
+                data.remove("messages");
+            }
+
+            match options.format {
+                DumpFormat::Text => {
+                    write!(
+                        out,
+                        "entry {} frame {} time {:.3} number {} {}",
+                        entry_idx, frame_idx, frame.time, frame.frame, frame_type
+                    )
+                    .unwrap();
+                    if !data.is_null() {
+                        write!(out, " {}", data).unwrap();
+                    }
+                    writeln!(out).unwrap();
+
+                    for (name, message) in messages {
+                        writeln!(out, "    {} {}", name, message).unwrap();
+                    }
+                }
+                DumpFormat::Ndjson => {
+                    let messages: Vec<Value> = messages
+                        .into_iter()
+                        .map(|(name, message)| json!({ "name": name, "data": message }))
+                        .collect();
+
+                    let line = json!({
+                        "entry": entry_idx,
+                        "frame_idx": frame_idx,
+                        "time": frame.time,
+                        "frame": frame.frame,
+                        "type": frame_type,
+                        "data": data,
+                        "messages": messages,
+                    });
+
+                    writeln!(out, "{}", line).unwrap();
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// [`dump`] to stdout.
+pub fn dump_file(path: impl AsRef<Path>, options: &DumpOptions) -> Result<(), String> {
+    let demo = open_demo_file(path)?;
+    let stdout = std::io::stdout();
+
+    dump(&demo, options, &mut stdout.lock())
+}
+
+/// Applies message and entity filters. Entity messages keep only the listed entities.
+fn filter_message<'a>(mut message: Message<'a>, options: &DumpOptions) -> Option<Message<'a>> {
+    if !options.is_listed_message(&message_name(&message)) {
+        return None;
+    }
+
+    if options.entities.is_empty() {
+        return Some(message);
+    }
+
+    let is_listed = |entity_index: &u16| options.entities.contains(entity_index);
+
+    let remaining = match &mut message {
+        Message::EngineMessage(EngineMessage::SvcPacketEntities(packet)) => {
+            packet
+                .entity_states
+                .retain(|entity| is_listed(&entity.entity_index));
+            packet.entity_states.len()
+        }
+        Message::EngineMessage(EngineMessage::SvcDeltaPacketEntities(packet)) => {
+            packet
+                .entity_states
+                .retain(|entity| is_listed(&entity.entity_index));
+            packet.entity_states.len()
+        }
+        Message::EngineMessage(EngineMessage::SvcSpawnBaseline(baseline)) => {
+            baseline
+                .entities
+                .retain(|entity| is_listed(&entity.entity_index));
+            baseline.entities.len()
+        }
+        _ => 0,
+    };
+
+    if remaining == 0 {
+        None
+    } else {
+        Some(message)
+    }
+}
+
+/// Message as JSON without the enum wrapping and with deltas decoded.
+fn message_to_json(message: &Message, delta_decoders: &DeltaDecoderTable) -> Result<Value, String> {
+    let mut value = message_to_value(message, delta_decoders)?;
+
+    // {"EngineMessage": {"SvcTime": {..}}} or {"UserMessage": {..}}
+    let wrapping = match message {
+        Message::EngineMessage(_) => 2,
+        Message::UserMessage(_) => 1,
+    };
+    for _ in 0..wrapping {
+        value = match value {
+            Value::Object(object) if object.len() == 1 => object.into_iter().next().unwrap().1,
+            value => value,
+        };
+    }
+
+    Ok(value)
+}
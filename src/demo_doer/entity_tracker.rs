@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+
+use crate::nbit_num;
+
+use super::*;
+
+/// Keeps the absolute state of every entity so a full [`SvcPacketEntities`] can be made at any frame.
+///
+/// The `bool` is whether the entity is decoded with `custom_entity_state_t`.
+pub struct EntityTracker {
+    baselines: BTreeMap<u16, (bool, Delta)>,
+    entities: BTreeMap<u16, (bool, Delta)>,
+}
+
+impl EntityTracker {
+    pub fn new() -> Self {
+        Self {
+            baselines: BTreeMap::new(),
+            entities: BTreeMap::new(),
+        }
+    }
+
+    /// Feeds parsed messages of one netmsg frame, in order.
+    pub fn update(&mut self, messages: &[Message]) {
+        for message in messages {
+            match message {
+                Message::EngineMessage(EngineMessage::SvcSpawnBaseline(baseline)) => {
+                    for entity in &baseline.entities {
+                        self.baselines.insert(
+                            entity.entity_index,
+                            (entity.type_.to_u8() & 1 == 0, entity.delta.clone()),
+                        );
+                    }
+                }
+                Message::EngineMessage(EngineMessage::SvcPacketEntities(packet)) => {
+                    // Full update, so anything not in here is gone.
+                    self.entities.clear();
+
+                    for entity in &packet.entity_states {
+                        let (_, mut delta) = self
+                            .baselines
+                            .get(&entity.entity_index)
+                            .cloned()
+                            .unwrap_or_default();
+                        delta.extend(entity.delta.clone());
+
+                        self.entities
+                            .insert(entity.entity_index, (entity.has_custom_delta, delta));
+                    }
+                }
+                Message::EngineMessage(EngineMessage::SvcDeltaPacketEntities(packet)) => {
+                    for entity in &packet.entity_states {
+                        if entity.remove_entity {
+                            self.entities.remove(&entity.entity_index);
+                            continue;
+                        }
+
+                        let baselines = &self.baselines;
+                        let state = self.entities.entry(entity.entity_index).or_insert_with(|| {
+                            baselines
+                                .get(&entity.entity_index)
+                                .cloned()
+                                .unwrap_or_default()
+                        });
+
+                        if let Some(has_custom_delta) = entity.has_custom_delta {
+                            state.0 = has_custom_delta;
+                        }
+
+                        if let Some(delta) = &entity.delta {
+                            state.1.extend(delta.clone());
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Feeds every netmsg of the LOADING entry, for passes that cut into playback but still need
+    /// its delta decoders and baselines.
+    pub fn read_loading<'a>(
+        &mut self,
+        entry: &DirectoryEntry<'a>,
+        delta_decoders: &mut DeltaDecoderTable,
+        custom_messages: &mut HashMap<u8, SvcNewUserMsg<'a>>,
+    ) -> Result<(), String> {
+        for (frame_idx, frame) in entry.frames.iter().enumerate() {
+            if let FrameData::NetMsg((_, data)) = &frame.data {
+                let messages =
+                    parse_frame_netmsg(data.msg, delta_decoders, custom_messages, 0, frame_idx)?;
+                self.update(&messages);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Swaps the first `SvcDeltaPacketEntities` in `messages` for a full update, for when the
+    /// delta chain is cut. Returns whether there was one to swap.
+    pub fn make_full_update(&self, messages: &mut [Message]) -> bool {
+        let delta = messages.iter_mut().find(|message| {
+            matches!(
+                message,
+                Message::EngineMessage(EngineMessage::SvcDeltaPacketEntities(_))
+            )
+        });
+
+        match delta {
+            Some(message) => {
+                *message = Message::EngineMessage(EngineMessage::SvcPacketEntities(
+                    self.packet_entities(),
+                ));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Current absolute state of an entity.
+    pub fn get(&self, entity_index: u16) -> Option<&Delta> {
+        self.entities.get(&entity_index).map(|(_, delta)| delta)
+    }
+
+    pub fn entity_indices(&self) -> Vec<u16> {
+        self.entities.keys().cloned().collect()
+    }
+
+    /// Every tracked entity as one full update.
+    ///
+    /// Deltas in `SvcPacketEntities` are against the baseline so sending the whole state is fine.
+    pub fn packet_entities(&self) -> SvcPacketEntities {
+        let mut entity_states: Vec<EntityState> = vec![];
+        let mut last_index = 0u16;
+
+        for (&entity_index, (has_custom_delta, delta)) in &self.entities {
+            let difference = entity_index - last_index;
+            let increment_entity_number = difference == 1;
+
+            // Same rule as ghost insertion, difference is only 6 bits.
+            let (is_absolute_entity_index, absolute_entity_index, entity_index_difference) =
+                if increment_entity_number {
+                    (None, None, None)
+                } else if difference > (1 << 6) - 1 {
+                    (Some(true), Some(nbit_num!(entity_index, 11)), None)
+                } else {
+                    (Some(false), None, Some(nbit_num!(difference, 6)))
+                };
+
+            entity_states.push(EntityState {
+                entity_index,
+                increment_entity_number,
+                is_absolute_entity_index,
+                absolute_entity_index,
+                entity_index_difference,
+                has_custom_delta: *has_custom_delta,
+                has_baseline_index: false,
+                baseline_index: None,
+                delta: delta.clone(),
+            });
+
+            last_index = entity_index;
+        }
+
+        SvcPacketEntities {
+            entity_count: nbit_num!(entity_states.len(), 16),
+            entity_states,
+        }
+    }
+}
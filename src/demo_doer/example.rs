@@ -1,6 +1,6 @@
 use std::{collections::HashMap, str::from_utf8};
 
-use demosuperimpose_goldsrc::netmsg_doer::{
+use crate::netmsg_doer::{
     client_data, parse_netmsg,
     utils::{get_initial_delta, BitSliceCast},
     write_netmsg,
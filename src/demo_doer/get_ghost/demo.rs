@@ -1,23 +1,29 @@
 use std::collections::HashMap;
 
-use demosuperimpose_goldsrc::{
-    netmsg_doer::{parse_netmsg, utils::get_initial_delta},
+use crate::{
+    demo_doer::{bytes_to_string, parse_frame_netmsg},
+    netmsg_doer::utils::get_initial_delta,
     types::{EngineMessage, Message, SvcNewUserMsg},
+    utils::Buttons,
 };
 use hldemo::{Demo, FrameData};
 
+use super::types::GhostMovement;
 use super::*;
 
+/// Fails if a netmsg doesn't parse, which only happens with `parse_anim`.
 pub fn demo_ghost_parse<'a>(
     name: &str,
     demo: &Demo<'a>,
     offset: f32,
     parse_anim: bool,
-) -> GhostInfo {
+) -> Result<GhostInfo, String> {
     // New ghost
     let mut ghost = GhostInfo::new();
     ghost.set_name(name.to_owned());
-    ghost.reset_ghost_anim_frame();
+    ghost.use_frametime = true;
+    ghost.meta.map_name = Some(bytes_to_string(demo.header.map_name));
+    ghost.meta.map_crc = Some(demo.header.map_crc);
 
     let mut delta_decoders = get_initial_delta();
     let mut custom_messages = HashMap::<u8, SvcNewUserMsg>::new();
@@ -30,43 +36,55 @@ pub fn demo_ghost_parse<'a>(
     let mut sequence: Option<Vec<u8>> = None;
     let mut anim_frame: Option<Vec<u8>> = None;
     let mut animtime: Option<Vec<u8>> = None;
+    // Frametime is the time between two player infos.
+    let mut last_time: Option<f32> = None;
+    let mut start_time: Option<f32> = None;
+    // From netmsg info, also before player info.
+    let mut movement = GhostMovement::default();
+    let mut buttons: Option<u32> = None;
 
-    for (_, entry) in demo.directory.entries.iter().enumerate() {
-        for frame in &entry.frames {
+    for (entry_idx, entry) in demo.directory.entries.iter().enumerate() {
+        for (frame_idx, frame) in entry.frames.iter().enumerate() {
             match &frame.data {
                 FrameData::NetMsg((_, data)) => {
+                    let ref_params = &data.info.ref_params;
+                    let usercmd = &data.info.usercmd;
+
+                    movement.velocity = Some(ref_params.simvel);
+                    movement.onground = Some(ref_params.onground != 0);
+                    movement.health = Some(ref_params.health as f32);
+                    movement.ducking = Some(usercmd.buttons & Buttons::Duck as u16 != 0);
+                    // Only says something when switching.
+                    if usercmd.weaponselect != 0 {
+                        movement.weapon = Some(usercmd.weaponselect as i32);
+                    }
+                    buttons = Some(usercmd.buttons as u32);
+
                     if !parse_anim {
                         continue;
                     }
 
-                    let (_, messages) =
-                        parse_netmsg(data.msg, &mut delta_decoders, &mut custom_messages).unwrap();
+                    let messages = parse_frame_netmsg(
+                        data.msg,
+                        &mut delta_decoders,
+                        &mut custom_messages,
+                        entry_idx,
+                        frame_idx,
+                    )?;
 
                     for message in messages {
                         match message {
                             Message::EngineMessage(what) => match what {
                                 EngineMessage::SvcDeltaPacketEntities(what) => {
                                     for entity in &what.entity_states {
-                                        if entity.entity_index == 1 && entity.delta.is_some() {
-                                            sequence = entity
-                                                .delta
-                                                .as_ref()
-                                                .unwrap()
-                                                .get("gaitsequence\0")
-                                                .cloned();
-                                            anim_frame = entity
-                                                .delta
-                                                .as_ref()
-                                                .unwrap()
-                                                .get("frame\0")
-                                                .cloned();
-                                            animtime = entity
-                                                .delta
-                                                .as_ref()
-                                                .unwrap()
-                                                .get("animtime\0")
-                                                .cloned();
-                                        }
+                                        let delta = match &entity.delta {
+                                            Some(delta) if entity.entity_index == 1 => delta,
+                                            _ => continue,
+                                        };
+
+                                        sequence = delta.get("gaitsequence\0").cloned();
+                                        anim_frame = delta.get("frame\0").cloned();
+                                        animtime = delta.get("animtime\0").cloned();
                                     }
                                     // These numbers are not very close to what we want.
                                     // They are vieworigin, not player origin.
@@ -80,15 +98,24 @@ pub fn demo_ghost_parse<'a>(
                     }
                 }
                 FrameData::ClientData(what) => {
+                    let frametime = last_time.map_or(0., |last_time| frame.time - last_time);
+                    last_time = Some(frame.time);
+                    let start_time = *start_time.get_or_insert(frame.time);
+                    movement.fov = Some(what.fov);
+
                     // Append frame on this frame because the demo orders like it.
-                    ghost.append_frame(
+                    ghost.append_frame_with_time(
+                        frametime,
                         what.origin,
                         what.viewangles,
                         sequence.to_owned(),
                         anim_frame.to_owned(),
                         animtime.to_owned(),
-                        None,
+                        buttons,
                     );
+                    let ghost_frame = ghost.frames.last_mut().unwrap();
+                    ghost_frame.time = frame.time - start_time;
+                    ghost_frame.movement = movement.clone();
 
                     // Reset for next find.
                     sequence = None;
@@ -100,5 +127,7 @@ pub fn demo_ghost_parse<'a>(
         }
     }
 
-    ghost
+    ghost.apply_offset(offset);
+
+    Ok(ghost)
 }
@@ -0,0 +1,257 @@
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+use hldemo::{Demo, FrameData};
+use serde::Deserialize;
+
+use crate::{
+    demo_doer::{
+        bytes_to_string, entity_tracker::EntityTracker, info::user_info_value, parse_frame_netmsg,
+    },
+    netmsg_doer::utils::get_initial_delta,
+    types::{Delta, EngineMessage, Message, SvcNewUserMsg},
+    utils::delta_int,
+};
+
+use super::demo::demo_ghost_parse;
+use super::*;
+
+/// Which player of a demo becomes the ghost.
+///
+/// From text, `recorder` and `all` are themselves, a number is an entity index and anything else
+/// is a name.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum GhostPlayer {
+    /// Whoever recorded the demo, from `ClientData`.
+    #[default]
+    Recorder,
+    /// Entity index, which is one more than the player slot.
+    Entity(u16),
+    /// Name from user info, case does not matter.
+    Name(String),
+    /// Every player as their own ghost.
+    All,
+}
+
+impl FromStr for GhostPlayer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Err("player is empty".to_owned()),
+            "recorder" => Ok(Self::Recorder),
+            "all" => Ok(Self::All),
+            _ => match s.parse::<u16>() {
+                Ok(0) => Err("entity 0 is the world, players start at 1".to_owned()),
+                Ok(entity_index) => Ok(Self::Entity(entity_index)),
+                Err(_) => Ok(Self::Name(s.to_owned())),
+            },
+        }
+    }
+}
+
+impl TryFrom<String> for GhostPlayer {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// A player from [`demo_players_ghost_parse`].
+pub struct PlayerGhost {
+    pub entity_index: u16,
+    /// Last name from user info, if there was any.
+    pub name: Option<String>,
+    pub ghost: GhostInfo,
+}
+
+/// Every player as a ghost, read from packet entities so it works for any player in multiplayer
+/// and HLTV demos, not only the recorder.
+///
+/// Players that never show up in packet entities have no ghost. Fails if a netmsg doesn't parse.
+pub fn demo_players_ghost_parse<'a>(
+    name: &str,
+    demo: &Demo<'a>,
+    offset: f32,
+) -> Result<Vec<PlayerGhost>, String> {
+    let mut delta_decoders = get_initial_delta();
+    let mut custom_messages = HashMap::<u8, SvcNewUserMsg>::new();
+    let mut entities = EntityTracker::new();
+
+    // Until `SvcServerInfo` says otherwise.
+    let mut max_players = 32u16;
+    let mut names = BTreeMap::<u16, String>::new();
+    let mut ghosts = BTreeMap::<u16, GhostInfo>::new();
+    // Animation is only sent when it changes, same as demo recorder ghost.
+    let mut last_anims = BTreeMap::<u16, [Option<Vec<u8>>; 3]>::new();
+
+    let mut start_time: Option<f32> = None;
+    let mut last_time = 0.;
+
+    for (entry_idx, entry) in demo.directory.entries.iter().enumerate() {
+        for (frame_idx, frame) in entry.frames.iter().enumerate() {
+            let data = match &frame.data {
+                FrameData::NetMsg((_, data)) => data,
+                _ => continue,
+            };
+
+            let messages = parse_frame_netmsg(
+                data.msg,
+                &mut delta_decoders,
+                &mut custom_messages,
+                entry_idx,
+                frame_idx,
+            )?;
+            entities.update(&messages);
+
+            let mut has_entities = false;
+
+            for message in &messages {
+                match message {
+                    Message::EngineMessage(EngineMessage::SvcServerInfo(server_info)) => {
+                        max_players = server_info.max_players as u16;
+                    }
+                    Message::EngineMessage(EngineMessage::SvcUpdateUserInfo(user_info)) => {
+                        if let Some(player_name) = user_info_value(user_info.user_info, "name") {
+                            names.insert(user_info.index as u16 + 1, player_name);
+                        }
+                    }
+                    Message::EngineMessage(
+                        EngineMessage::SvcPacketEntities(_)
+                        | EngineMessage::SvcDeltaPacketEntities(_),
+                    ) => has_entities = true,
+                    _ => (),
+                }
+            }
+
+            if !has_entities {
+                continue;
+            }
+
+            // Time can go back between entries.
+            let time = (frame.time - *start_time.get_or_insert(frame.time)).max(last_time);
+            last_time = time;
+
+            for entity_index in 1..=max_players {
+                let delta = match entities.get(entity_index) {
+                    Some(delta) if delta.contains_key("origin[0]\0") => delta,
+                    _ => continue,
+                };
+
+                let origin = delta_vec3(delta, "origin");
+                let angles = delta_vec3(delta, "angles");
+
+                let anim =
+                    ["gaitsequence\0", "frame\0", "animtime\0"].map(|key| delta.get(key).cloned());
+                let last_anim = last_anims.entry(entity_index).or_default();
+                let [sequence, anim_frame, animtime] = [0, 1, 2].map(|i| {
+                    if anim[i] != last_anim[i] {
+                        anim[i].clone()
+                    } else {
+                        None
+                    }
+                });
+                *last_anim = anim;
+
+                let ghost = ghosts.entry(entity_index).or_insert_with(|| {
+                    let mut ghost = GhostInfo::new();
+                    ghost.use_frametime = true;
+                    ghost
+                });
+
+                let frametime = ghost.frames.last().map_or(0., |last| time - last.time);
+
+                // Entity pitch is upside down compared to view.
+                ghost.append_frame_with_time(
+                    frametime,
+                    origin,
+                    [-angles[0], angles[1], angles[2]],
+                    sequence,
+                    anim_frame,
+                    animtime,
+                    None,
+                );
+                let ghost_frame = ghost.frames.last_mut().unwrap();
+                ghost_frame.time = time;
+                ghost_frame.movement.movetype =
+                    delta.get("movetype\0").map(|bytes| delta_int(bytes));
+            }
+        }
+    }
+
+    Ok(ghosts
+        .into_iter()
+        .map(|(entity_index, mut ghost)| {
+            let player_name = names.get(&entity_index).cloned();
+
+            ghost.set_name(match &player_name {
+                Some(player_name) => format!("{} ({})", name, player_name),
+                None => format!("{} (entity {})", name, entity_index),
+            });
+            ghost.meta.map_name = Some(bytes_to_string(demo.header.map_name));
+            ghost.meta.map_crc = Some(demo.header.map_crc);
+            ghost.meta.player_name = player_name.clone();
+            ghost.meta.player_entity = Some(entity_index);
+            ghost.apply_offset(offset);
+
+            PlayerGhost {
+                entity_index,
+                name: player_name,
+                ghost,
+            }
+        })
+        .collect())
+}
+
+/// Ghosts of `player`, one unless it is [`GhostPlayer::All`].
+pub fn demo_player_ghost_parse<'a>(
+    name: &str,
+    demo: &Demo<'a>,
+    offset: f32,
+    player: &GhostPlayer,
+) -> Result<Vec<GhostInfo>, String> {
+    if *player == GhostPlayer::Recorder {
+        return Ok(vec![demo_ghost_parse(name, demo, offset, true)?]);
+    }
+
+    let players = demo_players_ghost_parse(name, demo, offset)?;
+
+    let found: Vec<GhostInfo> = players
+        .into_iter()
+        .filter(|player_ghost| match player {
+            GhostPlayer::Entity(entity_index) => player_ghost.entity_index == *entity_index,
+            GhostPlayer::Name(player_name) => player_ghost
+                .name
+                .as_ref()
+                .map_or(false, |name| name.eq_ignore_ascii_case(player_name)),
+            _ => true,
+        })
+        .map(|player_ghost| player_ghost.ghost)
+        .collect();
+
+    if found.is_empty() {
+        return Err(match player {
+            GhostPlayer::Entity(entity_index) => {
+                format!("no player at entity {} in {}", entity_index, name)
+            }
+            GhostPlayer::Name(player_name) => {
+                format!("no player named \"{}\" in {}", player_name, name)
+            }
+            _ => format!("no players in {}", name),
+        });
+    }
+
+    Ok(found)
+}
+
+/// `key[0]`, `key[1]` and `key[2]` as floats, missing is 0.
+fn delta_vec3(delta: &Delta, key: &str) -> [f32; 3] {
+    [0, 1, 2].map(|i| {
+        delta
+            .get(&format!("{}[{}]\0", key, i))
+            .and_then(|bytes| bytes.as_slice().try_into().ok())
+            .map_or(0., f32::from_le_bytes)
+    })
+}
@@ -1,68 +1,70 @@
-use std::fs::File;
-use std::io::Read;
-use std::{io::Write, path::PathBuf};
+use std::path::{Path, PathBuf};
 
-use crate::demo_doer::get_ghost::romanian_jumpers::romanian_jumpers_ghost_parse;
-use crate::{
-    demo_doer::get_ghost::{
-        demo::demo_ghost_parse, simen::simen_ghost_parse, surf_gateway::surf_gateway_ghost_parse,
-    },
-    open_demo,
-};
+use super::superimpose::style::GhostStyle;
 
+use self::demo_players::GhostPlayer;
+use self::source::GhostSources;
 use self::types::GhostFrame;
 use self::types::GhostInfo;
 
 pub mod demo;
+pub mod demo_players;
+pub mod native;
 mod romanian_jumpers;
 mod simen;
+pub mod source;
 mod surf_gateway;
 
-mod types;
+pub mod types;
 
-///! Get ghost info following [`GhostInfo`] struct.
-/// Takes in path of a file and the offset of time (current unimplemented)
-pub fn get_ghost(filename: &str, offset: &f32) -> GhostInfo {
-    let pathbuf = PathBuf::from(filename);
+/// A ghost file and how much it is offset by.
+pub struct GhostFile {
+    pub path: PathBuf,
+    /// Seconds, positive starts the ghost later and negative earlier.
+    pub offset: f32,
+    /// Only demos have players other than the recorder.
+    pub player: GhostPlayer,
+    /// Nameplate text instead of the ghost name, for every ghost of the file.
+    pub label: Option<String>,
+    /// Nameplate colour, picked from a palette when not given.
+    pub label_color: Option<[u8; 3]>,
+    /// How it is drawn when superimposed.
+    pub style: GhostStyle,
+    /// Player model like `gign` or a model path, the main player's when not given.
+    pub model: Option<String>,
+}
 
-    std::io::stdout().flush().unwrap();
+impl GhostFile {
+    pub fn new(path: impl AsRef<Path>, offset: f32) -> Self {
+        Self {
+            path: path.as_ref().to_owned(),
+            offset,
+            player: GhostPlayer::Recorder,
+            label: None,
+            label_color: None,
+            style: GhostStyle::default(),
+            model: None,
+        }
+    }
 
-    let ghost = if pathbuf.to_str().unwrap().ends_with(".dem") {
-        let demo = open_demo!(filename);
-        demo_ghost_parse(filename, &demo, *offset, true)
-    } else if pathbuf.to_str().unwrap().ends_with(".simen.txt") {
-        // Either this, or use enum in main file.
-        simen_ghost_parse(filename.to_owned(), *offset)
-    } else if pathbuf.to_str().unwrap().ends_with(".sg.json") {
-        // Surf Gateway
-        surf_gateway_ghost_parse(filename.to_owned(), *offset)
-    } else if pathbuf.to_str().unwrap().ends_with(".rj.json") {
-        // Romanian-Jumprs
-        romanian_jumpers_ghost_parse(filename.to_owned(), *offset)
-    } else {
-        println!("");
-        panic!("File \"{}\" does not use supported extension.", filename);
-    };
+    pub fn with_player(path: impl AsRef<Path>, offset: f32, player: GhostPlayer) -> Self {
+        Self {
+            player,
+            ..Self::new(path, offset)
+        }
+    }
+}
 
-    ghost
+///! Get ghost info following [`GhostInfo`] struct.
+/// Takes in path of a file and the offset of time in seconds, see [`GhostInfo::apply_offset`].
+///
+/// Format is detected with [`GhostSources::new`], use that directly for more formats.
+pub fn get_ghost(path: &Path, offset: f32) -> Result<GhostInfo, String> {
+    GhostSources::new().get_ghost(path, offset, None)
 }
 
 ///! Plurality of [`get_ghost`]
 /// TODO: use rayon
-pub fn get_ghosts(others: &Vec<(String, f32)>) -> Vec<GhostInfo> {
-    others
-        .iter()
-        .enumerate()
-        .map(|(index, (filename, offset))| {
-            print!(
-                "\rParsing {} ({}/{})    ",
-                filename,
-                index + 1,
-                others.len()
-            );
-            std::io::stdout().flush().unwrap();
-
-            get_ghost(filename, offset)
-        })
-        .collect()
+pub fn get_ghosts(ghosts: &[GhostFile]) -> Result<Vec<GhostInfo>, String> {
+    GhostSources::new().get_ghosts(ghosts, None)
 }
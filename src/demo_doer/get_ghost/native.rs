@@ -0,0 +1,457 @@
+//! Our own ghost format, as JSON (`.ghost.json`) or compact binary (`.ghost`).
+//!
+//! Both carry the same things: a header with the map and player, then every frame with its
+//! time, movement and animation as typed values.
+//!
+//! Binary is little endian:
+//!
+//! ```text
+//! magic        [u8; 8]  "DSGHOST\0"
+//! version      u32
+//! name         string   u32 length then UTF-8
+//! meta mask    u8       which of map_name, map_crc, player_name, steam_id, player_entity follow
+//! meta         ...      string, u32, string, string, u16
+//! frame count  u32
+//! frames       ...
+//! ```
+//!
+//! A frame is `time`, `frametime`, `origin`, `viewangles` as f32, then a u16 mask of
+//! [`FRAME_FIELDS`] saying which of the rest follow, in that order.
+//!
+//! Version 1 was JSON only, with animation as raw delta bytes and no header or movement. It is
+//! still read.
+
+use std::path::Path;
+
+use nom::{
+    bytes::complete::tag,
+    combinator::{all_consuming, cond, map, map_res},
+    multi::length_count,
+    number::complete::{le_f32, le_i32, le_u16, le_u32, le_u8},
+    sequence::tuple,
+    IResult,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::delta_int;
+use crate::writer::ByteWriter;
+
+use super::types::{GhostAnimation, GhostMeta, GhostMovement};
+use super::{GhostFrame, GhostInfo};
+
+/// Value of `format` so the file can be told apart from other JSON.
+pub const NATIVE_FORMAT: &str = "demosuperimpose-ghost";
+/// Start of the binary format.
+pub const NATIVE_MAGIC: &[u8; 8] = b"DSGHOST\0";
+/// Bumped when the layout changes.
+pub const NATIVE_VERSION: u32 = 2;
+
+/// Optional frame fields in binary, in mask bit order.
+pub const FRAME_FIELDS: &[&str] = &[
+    "buttons", "sequence", "frame", "animtime", "velocity", "movetype", "flags", "onground",
+    "ducking", "fov", "weapon", "health",
+];
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct NativeGhostInfo {
+    format: String,
+    version: u32,
+    name: String,
+    #[serde(default)]
+    meta: GhostMeta,
+    frames: Vec<NativeGhostFrame>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct NativeGhostFrame {
+    time: f32,
+    frametime: f32,
+    origin: [f32; 3],
+    viewangles: [f32; 3],
+    #[serde(default)]
+    buttons: Option<u32>,
+    /// Gait sequence.
+    #[serde(default)]
+    sequence: Option<i32>,
+    #[serde(default)]
+    frame: Option<f32>,
+    #[serde(default)]
+    animtime: Option<f32>,
+    #[serde(default)]
+    movement: GhostMovement,
+}
+
+/// Enough to know which version to read the JSON as.
+#[derive(Deserialize)]
+struct NativeHeader {
+    format: String,
+    version: u32,
+}
+
+#[derive(Deserialize)]
+struct NativeGhostInfoV1 {
+    format: String,
+    version: u32,
+    name: String,
+    frames: Vec<NativeGhostFrameV1>,
+}
+
+#[derive(Deserialize)]
+struct NativeGhostFrameV1 {
+    time: f32,
+    frametime: f32,
+    origin: [f32; 3],
+    viewangles: [f32; 3],
+    #[serde(default)]
+    sequence: Option<Vec<u8>>,
+    #[serde(default)]
+    frame: Option<Vec<u8>>,
+    #[serde(default)]
+    animtime: Option<Vec<u8>>,
+    #[serde(default)]
+    buttons: Option<u32>,
+}
+
+impl From<NativeGhostInfoV1> for NativeGhostInfo {
+    fn from(v1: NativeGhostInfoV1) -> Self {
+        Self {
+            format: v1.format,
+            version: v1.version,
+            name: v1.name,
+            meta: GhostMeta::default(),
+            frames: v1
+                .frames
+                .into_iter()
+                .map(|frame| NativeGhostFrame {
+                    time: frame.time,
+                    frametime: frame.frametime,
+                    origin: frame.origin,
+                    viewangles: frame.viewangles,
+                    buttons: frame.buttons,
+                    sequence: frame.sequence.as_deref().map(delta_int),
+                    frame: frame.frame.as_deref().map(bytes_to_f32),
+                    animtime: frame.animtime.as_deref().map(bytes_to_f32),
+                    movement: GhostMovement::default(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl NativeGhostInfo {
+    fn from_ghost(ghost: &GhostInfo) -> Self {
+        Self {
+            format: NATIVE_FORMAT.to_owned(),
+            version: NATIVE_VERSION,
+            name: ghost.get_name(),
+            meta: ghost.meta.clone(),
+            frames: ghost
+                .frames
+                .iter()
+                .enumerate()
+                .map(|(idx, frame)| NativeGhostFrame {
+                    time: frame.time,
+                    frametime: ghost.get_frametime(idx),
+                    origin: frame.origin,
+                    viewangles: frame.viewangles,
+                    buttons: frame.buttons,
+                    sequence: frame.sequence.as_deref().map(delta_int),
+                    frame: frame.frame.as_deref().map(bytes_to_f32),
+                    animtime: frame.animtime.as_deref().map(bytes_to_f32),
+                    movement: frame.movement.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    fn into_ghost(self, offset: f32) -> GhostInfo {
+        let mut ghost = GhostInfo {
+            ghost_name: self.name,
+            entity_index: 0,
+            use_frametime: true,
+            frames: self
+                .frames
+                .into_iter()
+                .map(|frame| GhostFrame {
+                    time: frame.time,
+                    frametime: frame.frametime,
+                    origin: frame.origin,
+                    viewangles: frame.viewangles,
+                    // Written back into deltas which take as many bytes as the field needs.
+                    sequence: frame.sequence.map(|v| v.to_le_bytes().to_vec()),
+                    frame: frame.frame.map(|v| v.to_le_bytes().to_vec()),
+                    animtime: frame.animtime.map(|v| v.to_le_bytes().to_vec()),
+                    buttons: frame.buttons,
+                    movement: frame.movement,
+                })
+                .collect(),
+            animation: GhostAnimation::default(),
+            meta: self.meta,
+        };
+
+        ghost.apply_offset(offset);
+
+        ghost
+    }
+
+    fn check(&self, path: &Path) -> Result<(), String> {
+        if self.format != NATIVE_FORMAT {
+            return Err(format!(
+                "{} is not a {} file",
+                path.display(),
+                NATIVE_FORMAT
+            ));
+        }
+
+        if self.version == 0 || self.version > NATIVE_VERSION {
+            return Err(format!(
+                "{} is version {}, only versions up to {} are known",
+                path.display(),
+                self.version,
+                NATIVE_VERSION
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads either the JSON or the binary form.
+pub fn native_ghost_parse(path: &Path, offset: f32) -> Result<GhostInfo, String> {
+    let bytes =
+        std::fs::read(path).map_err(|err| format!("cannot read {}: {}", path.display(), err))?;
+
+    let native_ghost = if bytes.starts_with(NATIVE_MAGIC) {
+        match all_consuming(native_ghost_binary)(&bytes[..]) {
+            Ok((_, native_ghost)) => native_ghost,
+            Err(_) => return Err(format!("cannot parse {} as binary ghost", path.display())),
+        }
+    } else {
+        let cannot_parse =
+            |err: serde_json::Error| format!("cannot parse {}: {}", path.display(), err);
+        let header: NativeHeader = serde_json::from_slice(&bytes).map_err(cannot_parse)?;
+
+        if header.format == NATIVE_FORMAT && header.version == 1 {
+            serde_json::from_slice::<NativeGhostInfoV1>(&bytes)
+                .map_err(cannot_parse)?
+                .into()
+        } else {
+            serde_json::from_slice::<NativeGhostInfo>(&bytes).map_err(cannot_parse)?
+        }
+    };
+
+    native_ghost.check(path)?;
+
+    Ok(native_ghost.into_ghost(offset))
+}
+
+/// Writes `ghost` as JSON with nothing lost.
+pub fn native_ghost_write(ghost: &GhostInfo, path: &Path) -> Result<(), String> {
+    let text = serde_json::to_string(&NativeGhostInfo::from_ghost(ghost)).unwrap();
+    std::fs::write(path, text).map_err(|err| format!("cannot write {}: {}", path.display(), err))
+}
+
+/// Writes `ghost` as binary with nothing lost.
+pub fn native_ghost_write_binary(ghost: &GhostInfo, path: &Path) -> Result<(), String> {
+    let native_ghost = NativeGhostInfo::from_ghost(ghost);
+    let mut writer = ByteWriter::new();
+
+    writer.append_u8_slice(NATIVE_MAGIC);
+    writer.append_u32(native_ghost.version);
+    append_string(&mut writer, &native_ghost.name);
+
+    let meta = &native_ghost.meta;
+    writer.append_u8(mask(&[
+        meta.map_name.is_some(),
+        meta.map_crc.is_some(),
+        meta.player_name.is_some(),
+        meta.steam_id.is_some(),
+        meta.player_entity.is_some(),
+    ]) as u8);
+    if let Some(map_name) = &meta.map_name {
+        append_string(&mut writer, map_name);
+    }
+    if let Some(map_crc) = meta.map_crc {
+        writer.append_u32(map_crc);
+    }
+    if let Some(player_name) = &meta.player_name {
+        append_string(&mut writer, player_name);
+    }
+    if let Some(steam_id) = &meta.steam_id {
+        append_string(&mut writer, steam_id);
+    }
+    if let Some(player_entity) = meta.player_entity {
+        writer.append_u16(player_entity);
+    }
+
+    writer.append_u32(native_ghost.frames.len() as u32);
+
+    for frame in &native_ghost.frames {
+        let movement = &frame.movement;
+
+        writer.append_f32(frame.time);
+        writer.append_f32(frame.frametime);
+        writer.append_f32_array(frame.origin);
+        writer.append_f32_array(frame.viewangles);
+
+        // Same order as `FRAME_FIELDS`.
+        writer.append_u16(mask(&[
+            frame.buttons.is_some(),
+            frame.sequence.is_some(),
+            frame.frame.is_some(),
+            frame.animtime.is_some(),
+            movement.velocity.is_some(),
+            movement.movetype.is_some(),
+            movement.flags.is_some(),
+            movement.onground.is_some(),
+            movement.ducking.is_some(),
+            movement.fov.is_some(),
+            movement.weapon.is_some(),
+            movement.health.is_some(),
+        ]));
+        if let Some(buttons) = frame.buttons {
+            writer.append_u32(buttons);
+        }
+        if let Some(sequence) = frame.sequence {
+            writer.append_i32(sequence);
+        }
+        if let Some(anim_frame) = frame.frame {
+            writer.append_f32(anim_frame);
+        }
+        if let Some(animtime) = frame.animtime {
+            writer.append_f32(animtime);
+        }
+        if let Some(velocity) = movement.velocity {
+            writer.append_f32_array(velocity);
+        }
+        if let Some(movetype) = movement.movetype {
+            writer.append_i32(movetype);
+        }
+        if let Some(flags) = movement.flags {
+            writer.append_u32(flags);
+        }
+        if let Some(onground) = movement.onground {
+            writer.append_u8(onground as u8);
+        }
+        if let Some(ducking) = movement.ducking {
+            writer.append_u8(ducking as u8);
+        }
+        if let Some(fov) = movement.fov {
+            writer.append_f32(fov);
+        }
+        if let Some(weapon) = movement.weapon {
+            writer.append_i32(weapon);
+        }
+        if let Some(health) = movement.health {
+            writer.append_f32(health);
+        }
+    }
+
+    std::fs::write(path, writer.data)
+        .map_err(|err| format!("cannot write {}: {}", path.display(), err))
+}
+
+/// Bit `i` is set when `has[i]` is.
+fn mask(has: &[bool]) -> u16 {
+    has.iter()
+        .enumerate()
+        .fold(0, |mask, (i, &has)| mask | ((has as u16) << i))
+}
+
+fn append_string(writer: &mut ByteWriter, s: &str) {
+    writer.append_u32(s.len() as u32);
+    writer.append_u8_slice(s.as_bytes());
+}
+
+fn bytes_to_f32(bytes: &[u8]) -> f32 {
+    f32::from_bits(delta_int(bytes) as u32)
+}
+
+fn native_ghost_binary(i: &[u8]) -> IResult<&[u8], NativeGhostInfo> {
+    let (i, (_, version, name, meta_mask)) =
+        tuple((tag(&NATIVE_MAGIC[..]), le_u32, string, le_u8))(i)?;
+    let has = |bit: u8| meta_mask & (1 << bit) != 0;
+
+    let (i, (map_name, map_crc, player_name, steam_id, player_entity)) = tuple((
+        cond(has(0), string),
+        cond(has(1), le_u32),
+        cond(has(2), string),
+        cond(has(3), string),
+        cond(has(4), le_u16),
+    ))(i)?;
+
+    let (i, frames) = length_count(le_u32, native_ghost_binary_frame)(i)?;
+
+    Ok((
+        i,
+        NativeGhostInfo {
+            format: NATIVE_FORMAT.to_owned(),
+            version,
+            name,
+            meta: GhostMeta {
+                map_name,
+                map_crc,
+                player_name,
+                steam_id,
+                player_entity,
+            },
+            frames,
+        },
+    ))
+}
+
+fn native_ghost_binary_frame(i: &[u8]) -> IResult<&[u8], NativeGhostFrame> {
+    let (i, (time, frametime, origin, viewangles, frame_mask)) =
+        tuple((le_f32, le_f32, vec3, vec3, le_u16))(i)?;
+    let has = |bit: u16| frame_mask & (1 << bit) != 0;
+
+    let (i, (buttons, sequence, frame, animtime)) = tuple((
+        cond(has(0), le_u32),
+        cond(has(1), le_i32),
+        cond(has(2), le_f32),
+        cond(has(3), le_f32),
+    ))(i)?;
+
+    let (i, (velocity, movetype, flags, onground, ducking, fov, weapon, health)) = tuple((
+        cond(has(4), vec3),
+        cond(has(5), le_i32),
+        cond(has(6), le_u32),
+        cond(has(7), map(le_u8, |v| v != 0)),
+        cond(has(8), map(le_u8, |v| v != 0)),
+        cond(has(9), le_f32),
+        cond(has(10), le_i32),
+        cond(has(11), le_f32),
+    ))(i)?;
+
+    Ok((
+        i,
+        NativeGhostFrame {
+            time,
+            frametime,
+            origin,
+            viewangles,
+            buttons,
+            sequence,
+            frame,
+            animtime,
+            movement: GhostMovement {
+                velocity,
+                movetype,
+                flags,
+                onground,
+                ducking,
+                fov,
+                weapon,
+                health,
+            },
+        },
+    ))
+}
+
+fn vec3(i: &[u8]) -> IResult<&[u8], [f32; 3]> {
+    map(tuple((le_f32, le_f32, le_f32)), |(x, y, z)| [x, y, z])(i)
+}
+
+fn string(i: &[u8]) -> IResult<&[u8], String> {
+    map_res(length_count(le_u32, le_u8), String::from_utf8)(i)
+}
@@ -1,7 +1,8 @@
-use std::path::PathBuf;
+use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
+use super::types::{GhostAnimation, GhostMeta, GhostMovement};
 use super::{GhostFrame, GhostInfo};
 
 // Order of appearance for serde.
@@ -22,17 +23,21 @@ struct RomanianJumpersGhostFrame {
     buttons: u32,
 }
 
-pub fn romanian_jumpers_ghost_parse(filename: String, offset: f32) -> GhostInfo {
-    let pathbuf = PathBuf::from(filename.to_owned());
-    let file = match std::fs::read_to_string(&pathbuf) {
-        Ok(file) => file,
-        Err(_) => panic!("Cannot read file {}", filename),
-    };
+pub fn romanian_jumpers_ghost_parse(path: &Path, offset: f32) -> Result<GhostInfo, String> {
+    let filename = path.display().to_string();
+    let file = std::fs::read_to_string(path)
+        .map_err(|err| format!("cannot read {}: {}", filename, err))?;
+
+    let romanian_jumpers_ghost: RomanianJumpersGhostInfo =
+        serde_json::from_str(&file).map_err(|err| format!("cannot parse {}: {}", filename, err))?;
 
-    let romanian_jumpers_ghost: RomanianJumpersGhostInfo = serde_json::from_str(&file).unwrap();
+    let start_time = romanian_jumpers_ghost
+        .frames
+        .first()
+        .map_or(0., |frame| frame.time);
 
     // Convert romanian_jumpers_ghost to our normal ghost.
-    GhostInfo {
+    let mut ghost = GhostInfo {
         ghost_name: filename,
         entity_index: 0,
         use_frametime: true,
@@ -40,6 +45,7 @@ pub fn romanian_jumpers_ghost_parse(filename: String, offset: f32) -> GhostInfo
             .frames
             .iter()
             .map(|ghost| GhostFrame {
+                time: ghost.time - start_time,
                 frametime: ghost.frametime,
                 origin: [ghost.origin[0], -ghost.origin[2], ghost.origin[1]],
                 viewangles: [ghost.viewangles[0], ghost.viewangles[1], 0.],
@@ -47,8 +53,36 @@ pub fn romanian_jumpers_ghost_parse(filename: String, offset: f32) -> GhostInfo
                 frame: None,
                 animtime: None,
                 buttons: ghost.buttons.into(),
+                movement: GhostMovement::default(),
             })
             .collect(),
-        ghost_anim_frame: 0.,
-    }
+        animation: GhostAnimation::default(),
+        meta: GhostMeta::default(),
+    };
+
+    ghost.apply_offset(offset);
+
+    Ok(ghost)
+}
+
+/// Writes `ghost` as Romanian-Jumpers JSON.
+pub fn romanian_jumpers_ghost_write(ghost: &GhostInfo, path: &Path) -> Result<(), String> {
+    let romanian_jumpers_ghost = RomanianJumpersGhostInfo {
+        frames: ghost
+            .frames
+            .iter()
+            .enumerate()
+            .map(|(idx, frame)| RomanianJumpersGhostFrame {
+                // Opposite of the parse.
+                origin: [frame.origin[0], frame.origin[2], -frame.origin[1]],
+                viewangles: [frame.viewangles[0], frame.viewangles[1]],
+                frametime: ghost.get_frametime(idx),
+                time: frame.time,
+                buttons: frame.buttons.unwrap_or(0),
+            })
+            .collect(),
+    };
+
+    let text = serde_json::to_string(&romanian_jumpers_ghost).unwrap();
+    std::fs::write(path, text).map_err(|err| format!("cannot write {}: {}", path.display(), err))
 }
@@ -1,3 +1,4 @@
+use super::types::{GhostAnimation, GhostMeta, GhostMovement};
 use super::*;
 
 use nom::{
@@ -17,14 +18,12 @@ struct SimenGhostFrame {
     moves: [f32; 2],
 }
 
-pub fn simen_ghost_parse(filename: String, offset: f32) -> GhostInfo {
-    let pathbuf = PathBuf::from(filename.to_owned());
-    let file = match std::fs::read_to_string(&pathbuf) {
-        Ok(file) => file,
-        Err(_) => panic!("Cannot read file {}", filename),
-    };
+pub fn simen_ghost_parse(path: &Path, offset: f32) -> Result<GhostInfo, String> {
+    let filename = path.display().to_string();
+    let file = std::fs::read_to_string(path)
+        .map_err(|err| format!("cannot read {}: {}", filename, err))?;
 
-    let res = match map(
+    match map(
         preceded(
             simen_wrbot_header,
             all_consuming(delimited(
@@ -42,15 +41,59 @@ pub fn simen_ghost_parse(filename: String, offset: f32) -> GhostInfo {
             entity_index: 0,
             use_frametime: false,
             frames,
-            ghost_anim_frame: 0.,
+            animation: GhostAnimation::default(),
+            meta: GhostMeta::default(),
         },
     )(&file)
     {
-        Ok(res) => res.1,
-        Err(_) => panic!("Cannot parse file {}", filename),
-    };
+        Ok((_, mut ghost)) => {
+            // No timing in the file, it is at server frame rate.
+            ghost.time_from_frametime();
+            ghost.apply_offset(offset);
+            Ok(ghost)
+        }
+        Err(_) => Err(format!("cannot parse {} as simen ghost", filename)),
+    }
+}
+
+/// Writes `ghost` as simen wrbot text.
+///
+/// Velocity is from origin changes if the ghost doesn't have it and moves are 0. Frames should be at the server frame rate.
+pub fn simen_ghost_write(ghost: &GhostInfo, path: &Path) -> Result<(), String> {
+    // Same lines as `simen_wrbot_header`.
+    let mut text = format!(
+        "{}\n{}\n{}\n\n\n\n",
+        ghost.get_duration(),
+        ghost
+            .meta
+            .player_name
+            .clone()
+            .unwrap_or_else(|| ghost.get_name()),
+        ghost
+            .meta
+            .steam_id
+            .clone()
+            .unwrap_or_else(|| "STEAM_ID_LAN".to_owned())
+    );
+
+    for (idx, frame) in ghost.frames.iter().enumerate() {
+        let velocity = ghost.get_velocity(idx);
+
+        text += &format!(
+            "{} {} {} {} {} {} {} {} {} 0 0\n",
+            frame.viewangles[0],
+            frame.viewangles[1],
+            frame.origin[0],
+            frame.origin[1],
+            frame.origin[2],
+            velocity[0],
+            velocity[1],
+            velocity[2],
+            frame.buttons.unwrap_or(0),
+        );
+    }
 
-    res
+    std::fs::write(path, text).map_err(|err| format!("cannot write {}: {}", path.display(), err))
 }
 
 fn simen_wrbot_header(i: &str) -> IResult<&str, u8> {
@@ -70,10 +113,21 @@ fn simen_wrbot_header(i: &str) -> IResult<&str, u8> {
 fn simen_wrbot_line(i: &str) -> IResult<&str, SimenGhostFrame> {
     map(
         tuple((
-            float, float, float, float, float, float, float, float, u32, float, float,
+            float,
+            float,
+            float,
+            float,
+            float,
+            float,
+            float,
+            float,
+            preceded(space0, u32),
+            float,
+            float,
         )),
         |(pitch, yaw, posx, posy, posz, velx, vely, velz, buttons, move1, move2)| SimenGhostFrame {
             frame: GhostFrame {
+                time: 0.,
                 frametime: 0.,
                 origin: [posx, posy, posz],
                 viewangles: [pitch, yaw, 0.],
@@ -81,6 +135,10 @@ fn simen_wrbot_line(i: &str) -> IResult<&str, SimenGhostFrame> {
                 frame: None,
                 animtime: None,
                 buttons: buttons.into(),
+                movement: GhostMovement {
+                    velocity: Some([velx, vely, velz]),
+                    ..Default::default()
+                },
             },
             velocity: [velx, vely, velz],
             buttons,
@@ -0,0 +1,412 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::demo_doer::open_demo_file;
+
+use super::{
+    demo::demo_ghost_parse,
+    demo_players::{demo_player_ghost_parse, GhostPlayer},
+    native::{
+        native_ghost_parse, native_ghost_write, native_ghost_write_binary, NATIVE_FORMAT,
+        NATIVE_MAGIC,
+    },
+    romanian_jumpers::{romanian_jumpers_ghost_parse, romanian_jumpers_ghost_write},
+    simen::{simen_ghost_parse, simen_ghost_write},
+    surf_gateway::{surf_gateway_ghost_parse, surf_gateway_ghost_write},
+    types::GhostInfo,
+    GhostFile,
+};
+
+/// How many bytes of the file [`GhostSource::detect`] gets to look at.
+const DETECT_SIZE: usize = 4096;
+
+/// A ghost file format.
+///
+/// Implement this and add it to [`GhostSources`] to support a new format.
+pub trait GhostSource {
+    /// Short name to force the format with, like `simen`.
+    fn name(&self) -> &str;
+
+    /// File name ending to pick this format for writing, like `.simen.txt`.
+    fn extension(&self) -> Option<&str> {
+        None
+    }
+
+    /// Whether the file looks like this format.
+    ///
+    /// `start` is the first few KB of the file, or all of it if it is smaller.
+    fn detect(&self, path: &Path, start: &[u8]) -> bool;
+
+    /// Takes in path of a file and the offset of time in seconds.
+    ///
+    /// The offset is applied with [`GhostInfo::apply_offset`].
+    fn parse(&self, path: &Path, offset: f32) -> Result<GhostInfo, String>;
+
+    /// [`Self::parse`] for a chosen player, more than one ghost for [`GhostPlayer::All`].
+    ///
+    /// Only formats with more than one player need this.
+    fn parse_player(
+        &self,
+        path: &Path,
+        offset: f32,
+        player: &GhostPlayer,
+    ) -> Result<Vec<GhostInfo>, String> {
+        match player {
+            GhostPlayer::Recorder => Ok(vec![self.parse(path, offset)?]),
+            _ => Err(format!(
+                "{} is {}, which only has one player",
+                path.display(),
+                self.name()
+            )),
+        }
+    }
+
+    /// Writes `ghost` in this format, only for formats that can be written.
+    fn write(&self, _ghost: &GhostInfo, _path: &Path) -> Result<(), String> {
+        Err(format!("cannot write {} ghosts", self.name()))
+    }
+}
+
+/// Every known [`GhostSource`], tried in the order they are added.
+pub struct GhostSources {
+    sources: Vec<Box<dyn GhostSource>>,
+}
+
+impl GhostSources {
+    /// Comes with every format this crate knows.
+    pub fn new() -> Self {
+        let mut sources = Self::empty();
+
+        sources
+            .add(DemoSource)
+            .add(NativeSource)
+            .add(NativeBinarySource)
+            .add(SimenSource)
+            .add(SurfGatewaySource)
+            .add(RomanianJumpersSource);
+
+        sources
+    }
+
+    pub fn empty() -> Self {
+        Self { sources: vec![] }
+    }
+
+    pub fn add(&mut self, source: impl GhostSource + 'static) -> &mut Self {
+        self.sources.push(Box::new(source));
+        return self;
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn GhostSource> {
+        self.sources
+            .iter()
+            .find(|source| source.name() == name)
+            .map(|source| source.as_ref())
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.sources.iter().map(|source| source.name()).collect()
+    }
+
+    /// Same as [`Self::get`] but the error says what is there instead.
+    pub fn get_or_err(&self, name: &str) -> Result<&dyn GhostSource, String> {
+        self.get(name).ok_or_else(|| {
+            format!(
+                "unknown ghost format \"{}\", known formats are {}",
+                name,
+                self.names().join(", ")
+            )
+        })
+    }
+
+    /// Source whose [`GhostSource::extension`] the file ends with, otherwise the first source
+    /// that says it is the file's format.
+    ///
+    /// Extensions go first so a `.rj.json` with `"authid"` in it is still Romanian-Jumpers.
+    pub fn detect(&self, path: &Path) -> Result<&dyn GhostSource, String> {
+        if let Some(source) = self.by_extension(path) {
+            return Ok(source);
+        }
+
+        let mut start = vec![];
+        File::open(path)
+            .and_then(|file| file.take(DETECT_SIZE as u64).read_to_end(&mut start))
+            .map_err(|err| format!("cannot read {}: {}", path.display(), err))?;
+
+        self.sources
+            .iter()
+            .find(|source| source.detect(path, &start))
+            .map(|source| source.as_ref())
+            .ok_or_else(|| format!("cannot tell the ghost format of {}", path.display()))
+    }
+
+    fn by_extension(&self, path: &Path) -> Option<&dyn GhostSource> {
+        self.sources
+            .iter()
+            .find(|source| {
+                source
+                    .extension()
+                    .map_or(false, |extension| ends_with(path, extension))
+            })
+            .map(|source| source.as_ref())
+    }
+
+    /// Source to write `path` with, by `format` if given, otherwise by [`GhostSource::extension`].
+    pub fn output(&self, path: &Path, format: Option<&str>) -> Result<&dyn GhostSource, String> {
+        if let Some(format) = format {
+            return self.get_or_err(format);
+        }
+
+        self.by_extension(path).ok_or_else(|| {
+            let extensions: Vec<&str> = self
+                .sources
+                .iter()
+                .filter_map(|source| source.extension())
+                .collect();
+
+            format!(
+                "cannot tell the ghost format to write {} as, known endings are {}",
+                path.display(),
+                extensions.join(", ")
+            )
+        })
+    }
+
+    /// Writes `ghost` with [`Self::output`].
+    pub fn write_ghost(
+        &self,
+        ghost: &GhostInfo,
+        path: &Path,
+        format: Option<&str>,
+    ) -> Result<(), String> {
+        self.output(path, format)?.write(ghost, path)
+    }
+
+    /// Source that reads `path`, by `format` if given, otherwise whatever [`Self::detect`] finds.
+    pub fn source(&self, path: &Path, format: Option<&str>) -> Result<&dyn GhostSource, String> {
+        match format {
+            Some(format) => self.get_or_err(format),
+            None => self.detect(path),
+        }
+    }
+
+    /// Parses with `format` if given, otherwise whatever [`Self::detect`] finds.
+    pub fn get_ghost(
+        &self,
+        path: &Path,
+        offset: f32,
+        format: Option<&str>,
+    ) -> Result<GhostInfo, String> {
+        self.source(path, format)?.parse(path, offset)
+    }
+
+    /// Plurality of [`Self::get_ghost`] with progress print.
+    ///
+    /// A file can be more than one ghost when it asks for every player.
+    pub fn get_ghosts(
+        &self,
+        ghosts: &[GhostFile],
+        format: Option<&str>,
+    ) -> Result<Vec<GhostInfo>, String> {
+        Ok(self
+            .get_ghosts_by_file(ghosts, format)?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    /// [`Self::get_ghosts`] grouped by the file they come from.
+    pub fn get_ghosts_by_file(
+        &self,
+        ghosts: &[GhostFile],
+        format: Option<&str>,
+    ) -> Result<Vec<Vec<GhostInfo>>, String> {
+        let mut res = vec![];
+
+        for ghost in ghosts {
+            let source = self.source(&ghost.path, format)?;
+            res.push(source.parse_player(&ghost.path, ghost.offset, &ghost.player)?);
+        }
+
+        Ok(res)
+    }
+}
+
+impl Default for GhostSources {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn ends_with(path: &Path, suffix: &str) -> bool {
+    path.to_str().map_or(false, |path| path.ends_with(suffix))
+}
+
+/// Whether `key` shows up as a JSON key near the start.
+fn has_json_key(start: &[u8], key: &str) -> bool {
+    let key = format!("\"{}\"", key);
+
+    let is_object = start.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'{');
+
+    is_object
+        && start
+            .windows(key.len())
+            .any(|window| window == key.as_bytes())
+}
+
+/// GoldSrc demo, the recorder is the ghost unless another player is asked for.
+pub struct DemoSource;
+
+impl GhostSource for DemoSource {
+    fn name(&self) -> &str {
+        "demo"
+    }
+
+    fn detect(&self, path: &Path, start: &[u8]) -> bool {
+        start.starts_with(b"HLDEMO\0") || ends_with(path, ".dem")
+    }
+
+    fn parse(&self, path: &Path, offset: f32) -> Result<GhostInfo, String> {
+        let demo = open_demo_file(path)?;
+
+        demo_ghost_parse(&path.display().to_string(), &demo, offset, true)
+    }
+
+    fn parse_player(
+        &self,
+        path: &Path,
+        offset: f32,
+        player: &GhostPlayer,
+    ) -> Result<Vec<GhostInfo>, String> {
+        let demo = open_demo_file(path)?;
+
+        demo_player_ghost_parse(&path.display().to_string(), &demo, offset, player)
+    }
+}
+
+/// Our own `.ghost.json`, see [`super::native`].
+pub struct NativeSource;
+
+impl GhostSource for NativeSource {
+    fn name(&self) -> &str {
+        "native"
+    }
+
+    fn extension(&self) -> Option<&str> {
+        Some(".ghost.json")
+    }
+
+    fn detect(&self, path: &Path, start: &[u8]) -> bool {
+        ends_with(path, ".ghost.json") || has_json_key(start, NATIVE_FORMAT)
+    }
+
+    fn parse(&self, path: &Path, offset: f32) -> Result<GhostInfo, String> {
+        native_ghost_parse(path, offset)
+    }
+
+    fn write(&self, ghost: &GhostInfo, path: &Path) -> Result<(), String> {
+        native_ghost_write(ghost, path)
+    }
+}
+
+/// Binary `.ghost` of [`NativeSource`].
+pub struct NativeBinarySource;
+
+impl GhostSource for NativeBinarySource {
+    fn name(&self) -> &str {
+        "native-binary"
+    }
+
+    fn extension(&self) -> Option<&str> {
+        Some(".ghost")
+    }
+
+    fn detect(&self, path: &Path, start: &[u8]) -> bool {
+        start.starts_with(NATIVE_MAGIC) || ends_with(path, ".ghost")
+    }
+
+    fn parse(&self, path: &Path, offset: f32) -> Result<GhostInfo, String> {
+        native_ghost_parse(path, offset)
+    }
+
+    fn write(&self, ghost: &GhostInfo, path: &Path) -> Result<(), String> {
+        native_ghost_write_binary(ghost, path)
+    }
+}
+
+/// Simen's wrbot `.simen.txt`.
+pub struct SimenSource;
+
+impl GhostSource for SimenSource {
+    fn name(&self) -> &str {
+        "simen"
+    }
+
+    fn extension(&self) -> Option<&str> {
+        Some(".simen.txt")
+    }
+
+    fn write(&self, ghost: &GhostInfo, path: &Path) -> Result<(), String> {
+        simen_ghost_write(ghost, path)
+    }
+
+    fn detect(&self, path: &Path, _start: &[u8]) -> bool {
+        // Nothing in the file says what it is.
+        ends_with(path, ".simen.txt")
+    }
+
+    fn parse(&self, path: &Path, offset: f32) -> Result<GhostInfo, String> {
+        simen_ghost_parse(path, offset)
+    }
+}
+
+/// Surf Gateway `.sg.json`.
+pub struct SurfGatewaySource;
+
+impl GhostSource for SurfGatewaySource {
+    fn name(&self) -> &str {
+        "surf-gateway"
+    }
+
+    fn extension(&self) -> Option<&str> {
+        Some(".sg.json")
+    }
+
+    fn write(&self, ghost: &GhostInfo, path: &Path) -> Result<(), String> {
+        surf_gateway_ghost_write(ghost, path)
+    }
+
+    fn detect(&self, path: &Path, start: &[u8]) -> bool {
+        ends_with(path, ".sg.json") || has_json_key(start, "authid")
+    }
+
+    fn parse(&self, path: &Path, offset: f32) -> Result<GhostInfo, String> {
+        surf_gateway_ghost_parse(path, offset)
+    }
+}
+
+/// Romanian-Jumpers `.rj.json`.
+pub struct RomanianJumpersSource;
+
+impl GhostSource for RomanianJumpersSource {
+    fn name(&self) -> &str {
+        "romanian-jumpers"
+    }
+
+    fn extension(&self) -> Option<&str> {
+        Some(".rj.json")
+    }
+
+    fn write(&self, ghost: &GhostInfo, path: &Path) -> Result<(), String> {
+        romanian_jumpers_ghost_write(ghost, path)
+    }
+
+    fn detect(&self, path: &Path, start: &[u8]) -> bool {
+        ends_with(path, ".rj.json") || has_json_key(start, "orientation")
+    }
+
+    fn parse(&self, path: &Path, offset: f32) -> Result<GhostInfo, String> {
+        romanian_jumpers_ghost_parse(path, offset)
+    }
+}
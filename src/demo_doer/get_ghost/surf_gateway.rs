@@ -1,7 +1,8 @@
-use std::path::PathBuf;
+use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
+use super::types::{GhostAnimation, GhostMeta, GhostMovement};
 use super::{GhostFrame, GhostInfo};
 
 // Order of appearance for serde.
@@ -24,33 +25,86 @@ struct SurfGatewayGhostFrame {
     frametime: u32, // This one is something else.
 }
 
-pub fn surf_gateway_ghost_parse(filename: String, offset: f32) -> GhostInfo {
-    let pathbuf = PathBuf::from(filename.to_owned());
-    let file = match std::fs::read_to_string(&pathbuf) {
-        Ok(file) => file,
-        Err(_) => panic!("Cannot read file {}", filename),
-    };
+pub fn surf_gateway_ghost_parse(path: &Path, offset: f32) -> Result<GhostInfo, String> {
+    let filename = path.display().to_string();
+    let file = std::fs::read_to_string(path)
+        .map_err(|err| format!("cannot read {}: {}", filename, err))?;
+
+    let surf_gateway_ghost: SurfGatewayGhostInfo =
+        serde_json::from_str(&file).map_err(|err| format!("cannot parse {}: {}", filename, err))?;
 
-    let surf_gateway_ghost: SurfGatewayGhostInfo = serde_json::from_str(&file).unwrap();
+    // Frames don't have time so spread the run time over them.
+    let frametime = surf_gateway_ghost.time / surf_gateway_ghost.frames.len().max(1) as f32;
 
     // Convert surf_gateway_ghost to our normal ghost.
-    GhostInfo {
+    let mut ghost = GhostInfo {
         ghost_name: filename,
         entity_index: 0,
-        use_frametime: false,
+        use_frametime: frametime > 0.,
         frames: surf_gateway_ghost
             .frames
             .iter()
             .map(|ghost| GhostFrame {
-                frametime: 0.,
+                time: 0.,
+                frametime,
                 origin: ghost.origin,
                 viewangles: ghost.viewangles,
                 sequence: None,
                 frame: None,
                 animtime: None,
                 buttons: ghost.buttons.into(),
+                movement: GhostMovement::default(),
             })
             .collect(),
-        ghost_anim_frame: 0.,
+        animation: GhostAnimation::default(),
+        meta: GhostMeta {
+            player_name: Some(surf_gateway_ghost.name),
+            steam_id: Some(surf_gateway_ghost.authid),
+            ..Default::default()
+        },
+    };
+
+    if let Some(first) = ghost.frames.first_mut() {
+        first.movement.velocity = Some(surf_gateway_ghost.startvel);
     }
+
+    ghost.time_from_frametime();
+    ghost.apply_offset(offset);
+
+    Ok(ghost)
+}
+
+/// Writes `ghost` as Surf Gateway JSON.
+///
+/// Surf Gateway has no per frame time so frames should be at the server frame rate.
+pub fn surf_gateway_ghost_write(ghost: &GhostInfo, path: &Path) -> Result<(), String> {
+    let surf_gateway_ghost = SurfGatewayGhostInfo {
+        name: ghost
+            .meta
+            .player_name
+            .clone()
+            .unwrap_or_else(|| ghost.get_name()),
+        authid: ghost.meta.steam_id.clone().unwrap_or_default(),
+        time: ghost.get_duration(),
+        startvel: if ghost.get_size() > 0 {
+            ghost.get_velocity(0)
+        } else {
+            [0.; 3]
+        },
+        frames: ghost
+            .frames
+            .iter()
+            .map(|frame| SurfGatewayGhostFrame {
+                origin: frame.origin,
+                viewangles: frame.viewangles,
+                moves: [0.; 3],
+                buttons: frame.buttons.unwrap_or(0),
+                impulses: 0,
+                frametime: 0,
+            })
+            .collect(),
+    };
+
+    let text = serde_json::to_string(&surf_gateway_ghost).unwrap();
+    std::fs::write(path, text).map_err(|err| format!("cannot write {}: {}", path.display(), err))
 }
@@ -1,4 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{lerp, lerp_angle};
+
+/// Frame length when the ghost format does not say, 100 fps.
+pub const DEFAULT_FRAMETIME: f32 = 0.01;
+
+#[derive(Clone)]
 pub struct GhostFrame {
+    /// Seconds from the start of the ghost.
+    pub time: f32,
     pub frametime: f32,
     pub origin: [f32; 3],
     pub viewangles: [f32; 3],
@@ -6,6 +16,44 @@ pub struct GhostFrame {
     pub frame: Option<Vec<u8>>,
     pub animtime: Option<Vec<u8>>,
     pub buttons: Option<u32>,
+    pub movement: GhostMovement,
+}
+
+/// Player state beside where they are, whatever the format knows.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GhostMovement {
+    pub velocity: Option<[f32; 3]>,
+    pub movetype: Option<i32>,
+    /// [`crate::utils::EdictFlags`].
+    pub flags: Option<u32>,
+    pub onground: Option<bool>,
+    pub ducking: Option<bool>,
+    pub fov: Option<f32>,
+    /// Weapon id, like [`crate::utils::CSWeapon`] for Counter-Strike.
+    pub weapon: Option<i32>,
+    pub health: Option<f32>,
+}
+
+/// Where and who the ghost is from, whatever the format knows.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GhostMeta {
+    pub map_name: Option<String>,
+    pub map_crc: Option<u32>,
+    pub player_name: Option<String>,
+    pub steam_id: Option<String>,
+    /// Entity index in the demo the ghost is from.
+    pub player_entity: Option<u16>,
+}
+
+/// Where playback is in the ghost's animation, kept between main demo frames.
+#[derive(Clone, Default)]
+pub struct GhostAnimation {
+    /// First frame not looked at for a sequence change yet.
+    next_idx: usize,
+    /// Latest sequence and the ghost time it started.
+    sequence: Option<(Vec<u8>, f32)>,
+    /// Sequence last sent to the main demo.
+    sent: Option<Vec<u8>>,
 }
 
 pub struct GhostInfo {
@@ -13,7 +61,8 @@ pub struct GhostInfo {
     pub entity_index: u16,
     pub use_frametime: bool,
     pub frames: Vec<GhostFrame>,
-    pub ghost_anim_frame: f32,
+    pub animation: GhostAnimation,
+    pub meta: GhostMeta,
 }
 
 impl GhostInfo {
@@ -23,7 +72,8 @@ impl GhostInfo {
             entity_index: 0,
             use_frametime: false,
             frames: vec![],
-            ghost_anim_frame: 0.,
+            animation: GhostAnimation::default(),
+            meta: GhostMeta::default(),
         }
     }
 
@@ -36,7 +86,15 @@ impl GhostInfo {
         animtime: Option<Vec<u8>>,
         buttons: Option<u32>,
     ) {
-        self.append_frame_with_time(0., origin, viewangles, sequence, frame, animtime, buttons);
+        self.append_frame_with_time(
+            DEFAULT_FRAMETIME,
+            origin,
+            viewangles,
+            sequence,
+            frame,
+            animtime,
+            buttons,
+        );
     }
 
     pub fn append_frame_with_time(
@@ -49,7 +107,15 @@ impl GhostInfo {
         animtime: Option<Vec<u8>>,
         buttons: Option<u32>,
     ) {
+        // Right after the last frame is over.
+        let time = self
+            .frames
+            .len()
+            .checked_sub(1)
+            .map_or(0., |last| self.frames[last].time + self.get_frametime(last));
+
         self.frames.push(GhostFrame {
+            time,
             frametime,
             origin,
             viewangles,
@@ -57,6 +123,7 @@ impl GhostInfo {
             frame,
             animtime,
             buttons,
+            movement: GhostMovement::default(),
         });
     }
 
@@ -69,6 +136,138 @@ impl GhostInfo {
         self.frames.len()
     }
 
+    /// How long frame `idx` lasts in seconds, [`DEFAULT_FRAMETIME`] if the ghost doesn't know.
+    pub fn get_frametime(&self, idx: usize) -> f32 {
+        let frametime = self.get_frame(idx).frametime;
+
+        if self.use_frametime && frametime > 0. {
+            frametime
+        } else {
+            DEFAULT_FRAMETIME
+        }
+    }
+
+    /// Seconds until the last frame is over.
+    pub fn get_duration(&self) -> f32 {
+        self.frames
+            .len()
+            .checked_sub(1)
+            .map_or(0., |last| self.frames[last].time + self.get_frametime(last))
+    }
+
+    /// Velocity of frame `idx`, from the format if it has it, otherwise from how far it moved
+    /// between frames.
+    pub fn get_velocity(&self, idx: usize) -> [f32; 3] {
+        if let Some(velocity) = self.frames[idx].movement.velocity {
+            return velocity;
+        }
+
+        // First frame has nothing before so it uses the next one.
+        let (before, now) = match (idx.checked_sub(1), self.frames.get(idx + 1)) {
+            (Some(before), _) => (&self.frames[before], &self.frames[idx]),
+            (None, Some(next)) => (&self.frames[idx], next),
+            (None, None) => return [0.; 3],
+        };
+        let span = now.time - before.time;
+
+        if span <= 0. {
+            return [0.; 3];
+        }
+
+        [0, 1, 2].map(|i| (now.origin[i] - before.origin[i]) / span)
+    }
+
+    /// Sets every frame time by adding up [`Self::get_frametime`], for formats without timestamps.
+    pub fn time_from_frametime(&mut self) {
+        let mut time = 0.;
+
+        for idx in 0..self.frames.len() {
+            self.frames[idx].time = time;
+            time += self.get_frametime(idx);
+        }
+    }
+
+    /// Frame to show `time` seconds into the ghost.
+    ///
+    /// Before the start is the first frame, after the last frame is over is `None`.
+    pub fn get_frame_index_at(&self, time: f32) -> Option<usize> {
+        let last = self.frames.len().checked_sub(1)?;
+
+        if time > self.frames[last].time + self.get_frametime(last) {
+            return None;
+        }
+
+        let count = self.frames.partition_point(|frame| frame.time <= time);
+
+        Some(count.saturating_sub(1))
+    }
+
+    /// [`Self::get_frame_index_at`] but blended with the next frame so it is smooth at any framerate.
+    ///
+    /// Origin and viewangles are interpolated, everything else is from the frame before.
+    pub fn get_frame_at(&self, time: f32) -> Option<GhostFrame> {
+        let idx = self.get_frame_index_at(time)?;
+        let mut frame = self.frames[idx].clone();
+
+        if let Some(next) = self.frames.get(idx + 1) {
+            let span = next.time - frame.time;
+
+            if span > 0. {
+                let t = ((time - frame.time) / span).clamp(0., 1.);
+
+                for i in 0..3 {
+                    frame.origin[i] = lerp(frame.origin[i], next.origin[i], t);
+                    frame.viewangles[i] = lerp_angle(frame.viewangles[i], next.viewangles[i], t);
+                }
+            }
+        }
+
+        Some(frame)
+    }
+
+    /// Moves the ghost by `offset` seconds.
+    ///
+    /// Positive starts later by holding the first frame until then.
+    /// Negative starts earlier by dropping the frames before. The frame still showing at 0 keeps
+    /// its time before 0 so it blends into the next one like it would have.
+    pub fn apply_offset(&mut self, offset: f32) {
+        if self.frames.is_empty() || offset == 0. {
+            return;
+        }
+
+        for frame in &mut self.frames {
+            frame.time += offset;
+        }
+
+        if offset > 0. {
+            let first = GhostFrame {
+                time: 0.,
+                frametime: offset,
+                ..self.frames[0].clone()
+            };
+
+            self.frames.insert(0, first);
+        } else {
+            let first_kept = self
+                .frames
+                .iter()
+                .position(|frame| frame.time >= 0.)
+                .unwrap_or(self.frames.len());
+
+            // Keep the frame that is still showing at 0.
+            let drop = if first_kept > 0
+                && first_kept < self.frames.len()
+                && self.frames[first_kept].time > 0.
+            {
+                first_kept - 1
+            } else {
+                first_kept
+            };
+
+            self.frames.drain(..drop);
+        }
+    }
+
     pub fn set_name(&mut self, name: String) {
         self.ghost_name = name.to_owned();
     }
@@ -85,15 +284,52 @@ impl GhostInfo {
         self.entity_index
     }
 
-    pub fn increment_ghost_anim_frame(&mut self) {
-        self.ghost_anim_frame += 1.;
-    }
+    /// Animation `time` seconds into the ghost, for a main demo going forward in time.
+    ///
+    /// The sequence is only there when it changed since the last call. The frame counts up from
+    /// when the sequence started, one every [`DEFAULT_FRAMETIME`], and is `None` before any
+    /// sequence.
+    pub fn animate(&mut self, time: f32) -> (Option<Vec<u8>>, Option<f32>) {
+        let idx = match self.get_frame_index_at(time) {
+            Some(idx) => idx,
+            None => return (None, None),
+        };
 
-    pub fn reset_ghost_anim_frame(&mut self) {
-        self.ghost_anim_frame = 0.;
-    }
+        // Going back starts over, the main demo still has what was sent.
+        if self.animation.next_idx > idx + 1 {
+            self.animation = GhostAnimation {
+                sent: self.animation.sent.take(),
+                ..GhostAnimation::default()
+            };
+        }
+
+        // Sequence changes between the last call and now are not skipped.
+        for frame in &self.frames[self.animation.next_idx..=idx] {
+            if let Some(sequence) = &frame.sequence {
+                let changed = self
+                    .animation
+                    .sequence
+                    .as_ref()
+                    .map_or(true, |(current, _)| current != sequence);
+
+                if changed {
+                    self.animation.sequence = Some((sequence.clone(), frame.time));
+                }
+            }
+        }
+        self.animation.next_idx = idx + 1;
+
+        let (sequence, start) = match &self.animation.sequence {
+            Some(sequence) => sequence,
+            None => return (None, None),
+        };
+        let frame = (time - start).max(0.) / DEFAULT_FRAMETIME;
+
+        if self.animation.sent.as_ref() == Some(sequence) {
+            return (None, Some(frame));
+        }
 
-    pub fn get_ghost_anim_frame(&self) -> f32 {
-        self.ghost_anim_frame
+        self.animation.sent = Some(sequence.clone());
+        (Some(sequence.clone()), Some(frame))
     }
 }
@@ -6,40 +6,37 @@ use std::path::Path;
 use bitvec::bitvec;
 use bitvec::prelude::*;
 
-use bsp_file::bsp::LumpType;
-use bsp_file::bsp::RawMap;
-use bsp_render::level::entities::parse_entities;
-use demosuperimpose_goldsrc::get_cs_delta_msg;
-use demosuperimpose_goldsrc::insert_packet_entity_state_delta_with_index;
-use demosuperimpose_goldsrc::insert_packet_entity_state_with_index;
-use demosuperimpose_goldsrc::netmsg_doer::delta_description::DeltaDescription;
-use demosuperimpose_goldsrc::netmsg_doer::delta_packet_entities::DeltaPacketEntities;
-use demosuperimpose_goldsrc::netmsg_doer::new_movevars::NewMovevars;
-use demosuperimpose_goldsrc::netmsg_doer::packet_entities::PacketEntities;
-use demosuperimpose_goldsrc::netmsg_doer::server_info::ServerInfo;
-use demosuperimpose_goldsrc::netmsg_doer::set_view::SetView;
-use demosuperimpose_goldsrc::netmsg_doer::sign_on_num::SignOnNum;
-use demosuperimpose_goldsrc::netmsg_doer::sound::Sound;
-use demosuperimpose_goldsrc::netmsg_doer::spawn_baseline::SpawnBaseline;
-use demosuperimpose_goldsrc::netmsg_doer::NetMsgDoerWithDelta;
-use demosuperimpose_goldsrc::netmsg_doer::NetMsgDoerWithExtraInfo;
-use demosuperimpose_goldsrc::rand_int_range;
-use demosuperimpose_goldsrc::types::Delta;
-use demosuperimpose_goldsrc::types::DeltaDecoderTable;
-use demosuperimpose_goldsrc::types::EntityS;
-use demosuperimpose_goldsrc::types::EntityState;
-use demosuperimpose_goldsrc::types::EntityStateDelta;
-use demosuperimpose_goldsrc::types::OriginCoord;
-use demosuperimpose_goldsrc::types::SvcDeltaPacketEntities;
-use demosuperimpose_goldsrc::types::SvcNewMoveVars;
-use demosuperimpose_goldsrc::types::SvcPacketEntities;
-use demosuperimpose_goldsrc::types::SvcServerInfo;
-use demosuperimpose_goldsrc::types::SvcSetView;
-use demosuperimpose_goldsrc::types::SvcSignOnNum;
-use demosuperimpose_goldsrc::types::SvcSpawnBaseline;
-use demosuperimpose_goldsrc::utils::Buttons;
-use demosuperimpose_goldsrc::wrap_message;
-use demosuperimpose_goldsrc::{
+use crate::get_cs_delta_msg;
+use crate::insert_packet_entity_state_delta_with_index;
+use crate::insert_packet_entity_state_with_index;
+use crate::netmsg_doer::delta_description::DeltaDescription;
+use crate::netmsg_doer::delta_packet_entities::DeltaPacketEntities;
+use crate::netmsg_doer::new_movevars::NewMovevars;
+use crate::netmsg_doer::packet_entities::PacketEntities;
+use crate::netmsg_doer::server_info::ServerInfo;
+use crate::netmsg_doer::set_view::SetView;
+use crate::netmsg_doer::sign_on_num::SignOnNum;
+use crate::netmsg_doer::sound::Sound;
+use crate::netmsg_doer::spawn_baseline::SpawnBaseline;
+use crate::netmsg_doer::NetMsgDoerWithDelta;
+use crate::netmsg_doer::NetMsgDoerWithExtraInfo;
+use crate::rand_int_range;
+use crate::types::Delta;
+use crate::types::DeltaDecoderTable;
+use crate::types::EntityS;
+use crate::types::EntityState;
+use crate::types::EntityStateDelta;
+use crate::types::OriginCoord;
+use crate::types::SvcDeltaPacketEntities;
+use crate::types::SvcNewMoveVars;
+use crate::types::SvcPacketEntities;
+use crate::types::SvcServerInfo;
+use crate::types::SvcSetView;
+use crate::types::SvcSignOnNum;
+use crate::types::SvcSpawnBaseline;
+use crate::utils::Buttons;
+use crate::wrap_message;
+use crate::{
     init_parse, nbit_num, nbit_str,
     netmsg_doer::{
         parse_netmsg, parse_netmsg_immutable, resource_list::ResourceList, write_netmsg, NetMsgDoer,
@@ -48,6 +45,9 @@ use demosuperimpose_goldsrc::{
     types::{EngineMessage, Message, SvcDeltaDescription, SvcResourceList, SvcSound},
     utils::{NetMsgDataMethods, ResourceType},
 };
+use bsp_file::bsp::LumpType;
+use bsp_file::bsp::RawMap;
+use bsp_render::level::entities::parse_entities;
 use hldemo::Directory;
 use hldemo::DirectoryEntry;
 use hldemo::Header;
@@ -61,7 +61,7 @@ use nom::AsBytes;
 
 use crate::get_cs_delta_decoder_table;
 
-use super::get_ghost::get_ghost;
+use super::get_ghost::{source::GhostSources, types::GhostInfo};
 
 const DEMO_BUFFER_SIZE: [u8; 8] = [1, 0, 0, 0, 0, 0, 180, 66];
 const DEFAULT_IN_SEQ: i32 = 143791;
@@ -72,7 +72,14 @@ const MAX_PLAYERS: i32 = 1;
 // Eh, maybe someone can spot this and use for different mod.
 const GAME_DIR: &str = "cstrike";
 
-pub fn ghost_to_demo<'a>(ghost_file_name: &'a Path, map_file_name: &'a Path) -> Demo<'a> {
+/// `format` forces the ghost format by its [`GhostSources`] name, otherwise it is detected.
+pub fn ghost_to_demo<'a>(
+    ghost_file_name: &'a Path,
+    map_file_name: &'a Path,
+    format: Option<&str>,
+) -> Result<Demo<'a>, String> {
+    let ghost_info = GhostSources::new().get_ghost(ghost_file_name, 0., format)?;
+
     let mut map_name = vec![0u8; 260];
     let map_file_name_stem = map_file_name.file_stem().unwrap().to_str().unwrap();
     map_name[..map_file_name_stem.len()].copy_from_slice(map_file_name_stem.as_bytes());
@@ -132,7 +139,7 @@ pub fn ghost_to_demo<'a>(ghost_file_name: &'a Path, map_file_name: &'a Path) ->
         insert_base_netmsg(&mut demo, map_file_name);
     insert_ghost(
         &mut demo,
-        ghost_file_name.to_str().unwrap(),
+        &ghost_info,
         None,
         None,
         game_resource_index_start,
@@ -140,7 +147,7 @@ pub fn ghost_to_demo<'a>(ghost_file_name: &'a Path, map_file_name: &'a Path) ->
         delta_packet_entities,
     );
 
-    demo
+    Ok(demo)
 }
 
 #[derive(Debug)]
@@ -531,16 +538,13 @@ fn insert_base_netmsg(
 
 pub fn insert_ghost(
     demo: &mut Demo,
-    ghost_file_name: &str,
+    ghost_info: &GhostInfo,
     override_frametime: Option<f32>,
     override_fov: Option<f32>,
     game_resource_index_start: usize,
     packet_entities: Vec<u8>,
     delta_packet_entities: SvcDeltaPacketEntities,
 ) {
-    // setup
-    let ghost_info = get_ghost(ghost_file_name, &0.);
-
     // set directory entry info
     let entry1 = &mut demo.directory.entries[1];
 
@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use super::*;
+
+/// Summary of a demo.
+#[derive(Debug, Serialize)]
+pub struct DemoInfo {
+    pub demo_protocol: i32,
+    pub net_protocol: i32,
+    pub map_name: String,
+    pub map_crc: u32,
+    pub game_dir: String,
+    pub entries: Vec<EntryInfo>,
+    /// From the first `SvcServerInfo`.
+    pub hostname: Option<String>,
+    pub max_players: Option<u8>,
+    /// Everyone who had user info at some point, last known info wins.
+    pub players: Vec<PlayerInfo>,
+    /// Message name to how many times it shows up.
+    pub messages: BTreeMap<String, usize>,
+    /// From `usercmd.msec` of netmsg frames.
+    pub average_fps: Option<f32>,
+    pub resource_count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EntryInfo {
+    pub entry_type: i32,
+    pub description: String,
+    pub frame_count: i32,
+    /// Frames that are actually there.
+    pub frames: usize,
+    pub track_time: f32,
+    /// Time between the first and last frame.
+    pub duration: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlayerInfo {
+    pub index: u8,
+    pub id: u32,
+    pub name: String,
+}
+
+/// Goes over every frame and netmsg once.
+pub fn info(demo: &Demo) -> DemoInfo {
+    let mut delta_decoders = get_initial_delta();
+    let mut custom_messages = HashMap::<u8, SvcNewUserMsg>::new();
+
+    let mut hostname = None;
+    let mut max_players = None;
+    let mut players = BTreeMap::<u8, PlayerInfo>::new();
+    let mut messages = BTreeMap::<String, usize>::new();
+    let mut resource_count = 0;
+
+    let mut total_msec = 0u64;
+    let mut usercmd_count = 0u64;
+
+    let entries = demo
+        .directory
+        .entries
+        .iter()
+        .map(|entry| EntryInfo {
+            entry_type: entry.entry_type,
+            description: bytes_to_string(entry.description),
+            frame_count: entry.frame_count,
+            frames: entry.frames.len(),
+            track_time: entry.track_time,
+            duration: match (entry.frames.first(), entry.frames.last()) {
+                (Some(first), Some(last)) => last.time - first.time,
+                _ => 0.,
+            },
+        })
+        .collect();
+
+    for (entry_idx, entry) in demo.directory.entries.iter().enumerate() {
+        for frame in &entry.frames {
+            let data = match &frame.data {
+                FrameData::NetMsg((_, data)) => data,
+                _ => continue,
+            };
+
+            // Loading segment has no input.
+            if entry_idx != 0 {
+                total_msec += data.info.usercmd.msec as u64;
+                usercmd_count += 1;
+            }
+
+            let (_, netmsg) =
+                parse_netmsg(data.msg, &mut delta_decoders, &mut custom_messages).unwrap();
+
+            for message in &netmsg {
+                *messages.entry(message_name(message)).or_default() += 1;
+
+                match message {
+                    Message::EngineMessage(EngineMessage::SvcServerInfo(server_info)) => {
+                        if hostname.is_none() {
+                            hostname = Some(bytes_to_string(server_info.hostname));
+                            max_players = Some(server_info.max_players);
+                        }
+                    }
+                    Message::EngineMessage(EngineMessage::SvcUpdateUserInfo(user_info)) => {
+                        if let Some(name) = user_info_value(user_info.user_info, "name") {
+                            players.insert(
+                                user_info.index,
+                                PlayerInfo {
+                                    index: user_info.index,
+                                    id: user_info.id,
+                                    name,
+                                },
+                            );
+                        }
+                    }
+                    Message::EngineMessage(EngineMessage::SvcResourceList(resource_list)) => {
+                        resource_count += resource_list.resources.len();
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    let average_fps = if total_msec > 0 {
+        Some(1000. * usercmd_count as f32 / total_msec as f32)
+    } else {
+        None
+    };
+
+    DemoInfo {
+        demo_protocol: demo.header.demo_protocol,
+        net_protocol: demo.header.net_protocol,
+        map_name: bytes_to_string(demo.header.map_name),
+        map_crc: demo.header.map_crc,
+        game_dir: bytes_to_string(demo.header.game_dir),
+        entries,
+        hostname,
+        max_players,
+        players: players.into_values().collect(),
+        messages,
+        average_fps,
+        resource_count,
+    }
+}
+
+/// Value of `key` in `\key\value\key\value` user info.
+pub fn user_info_value(user_info: &[u8], key: &str) -> Option<String> {
+    let user_info = bytes_to_string(user_info);
+    let mut parts = user_info.split('\\').skip(1);
+
+    while let (Some(k), Some(v)) = (parts.next(), parts.next()) {
+        if k == key {
+            return Some(v.to_owned());
+        }
+    }
+
+    None
+}
+
+impl DemoInfo {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+impl fmt::Display for DemoInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "demo protocol: {}", self.demo_protocol)?;
+        writeln!(f, "net protocol: {}", self.net_protocol)?;
+        writeln!(f, "map: {} (crc {})", self.map_name, self.map_crc)?;
+        writeln!(f, "game dir: {}", self.game_dir)?;
+
+        if let Some(hostname) = &self.hostname {
+            writeln!(f, "hostname: {}", hostname)?;
+        }
+        if let Some(max_players) = self.max_players {
+            writeln!(f, "max players: {}", max_players)?;
+        }
+        if let Some(average_fps) = self.average_fps {
+            writeln!(f, "average fps: {:.2}", average_fps)?;
+        }
+        writeln!(f, "resources: {}", self.resource_count)?;
+
+        writeln!(f, "entries:")?;
+        for (entry_idx, entry) in self.entries.iter().enumerate() {
+            writeln!(
+                f,
+                "  {}: {} (type {}), {} frames, {:.3}s",
+                entry_idx, entry.description, entry.entry_type, entry.frames, entry.duration
+            )?;
+        }
+
+        writeln!(f, "players:")?;
+        for player in &self.players {
+            writeln!(f, "  {}: {} (id {})", player.index, player.name, player.id)?;
+        }
+
+        writeln!(f, "messages:")?;
+        for (name, count) in &self.messages {
+            writeln!(f, "  {}: {}", name, count)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,272 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use super::get_ghost::{demo_players::GhostPlayer, source::GhostSources, GhostFile};
+use super::kz_stats::{KzAddOns, KzStats};
+use super::offset_viewangles::{ViewFx, ViewFxPass};
+use super::pipeline::Pipeline;
+use super::remove_entities::RemoveEntities;
+use super::superimpose::{style::GhostStyle, superimpose_pass, Progress, SuperimposeOptions};
+use super::*;
+
+/// Everything for one run, read from a TOML or JSON file.
+///
+/// ```toml
+/// demo = "gold.dem"
+/// output = "out.dem"
+/// ghost_folder = "ghosts"
+/// nameplates = true
+///
+/// [[ghosts]]
+/// path = "gold2.dem"
+/// offset = 1.5
+/// label = "gold"
+/// label_color = [255, 200, 0]
+/// model = "gign"
+///
+/// [ghosts.style]
+/// rendermode = 5
+/// renderamt = 120
+/// rendercolor = [0, 160, 255]
+///
+/// [[ghosts]]
+/// path = "server.dem"
+/// player = "all"
+///
+/// [kz_stats]
+/// keys = true
+/// speedometer = true
+///
+/// [[view_fx]]
+/// effect = "back-flip"
+/// start = 230
+/// end = 350
+/// ```
+///
+/// Relative paths are relative to the job file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Job {
+    /// Main demo.
+    pub demo: PathBuf,
+    pub output: PathBuf,
+    #[serde(default)]
+    pub ghosts: Vec<JobGhost>,
+    /// Every file in this folder is a ghost with no offset.
+    #[serde(default)]
+    pub ghost_folder: Option<PathBuf>,
+    /// Reads every ghost as this format instead of detecting it, like `simen`.
+    #[serde(default)]
+    pub ghost_format: Option<String>,
+    /// Draws ghost names above their heads.
+    ///
+    /// Only the 4 closest ghosts in view have one at a time, see [`super::superimpose::nameplate`].
+    #[serde(default)]
+    pub nameplates: bool,
+    #[serde(default)]
+    pub remove_entities: Vec<u16>,
+    /// Done in order.
+    #[serde(default)]
+    pub view_fx: Vec<JobViewFx>,
+    #[serde(default)]
+    pub kz_stats: JobKzStats,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobGhost {
+    pub path: PathBuf,
+    /// In seconds.
+    #[serde(default)]
+    pub offset: f32,
+    /// `recorder`, `all`, an entity index or a name, only for demos.
+    #[serde(default)]
+    pub player: GhostPlayer,
+    /// Nameplate text instead of the ghost name.
+    pub label: Option<String>,
+    /// Nameplate colour as `[r, g, b]`.
+    pub label_color: Option<[u8; 3]>,
+    /// Look when superimposed, see [`GhostStyle`].
+    #[serde(default)]
+    pub style: GhostStyle,
+    /// Player model like `gign` or a model path.
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobViewFx {
+    pub effect: ViewFx,
+    /// First frame.
+    pub start: usize,
+    /// Last frame.
+    pub end: usize,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobKzStats {
+    #[serde(default)]
+    pub keys: bool,
+    #[serde(default)]
+    pub speedometer: bool,
+}
+
+impl Job {
+    /// Reads a job file. `.json` is JSON and anything else is TOML.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("cannot read {}: {}", path.display(), err))?;
+
+        let is_json = path.extension().map_or(false, |ext| ext == "json");
+
+        let mut job = if is_json {
+            Self::from_json(&text)
+        } else {
+            Self::from_toml(&text)
+        }
+        .map_err(|err| format!("cannot parse {}: {}", path.display(), err))?;
+
+        if let Some(folder) = path.parent() {
+            job.relative_to(folder);
+        }
+
+        job.check()?;
+
+        Ok(job)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|err| err.to_string())
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        serde_json::from_str(text).map_err(|err| err.to_string())
+    }
+
+    /// Makes relative paths relative to `folder`.
+    pub fn relative_to(&mut self, folder: &Path) {
+        let join = |path: &mut PathBuf| {
+            if path.is_relative() {
+                *path = folder.join(&*path);
+            }
+        };
+
+        join(&mut self.demo);
+        join(&mut self.output);
+
+        for ghost in &mut self.ghosts {
+            join(&mut ghost.path);
+        }
+
+        if let Some(ghost_folder) = &mut self.ghost_folder {
+            join(ghost_folder);
+        }
+    }
+
+    /// Catches mistakes before anything runs.
+    pub fn check(&self) -> Result<(), String> {
+        if !self.demo.is_file() {
+            return Err(format!("cannot find {}", self.demo.display()));
+        }
+
+        for ghost in &self.ghosts {
+            if !ghost.path.is_file() {
+                return Err(format!("cannot find ghost {}", ghost.path.display()));
+            }
+
+            ghost
+                .style
+                .check()
+                .map_err(|err| format!("ghost {} style: {}", ghost.path.display(), err))?;
+        }
+
+        if let Some(folder) = &self.ghost_folder {
+            if !folder.is_dir() {
+                return Err(format!("{} is not a folder", folder.display()));
+            }
+        }
+
+        if let Some(format) = &self.ghost_format {
+            GhostSources::new().get_or_err(format)?;
+        }
+
+        for fx in &self.view_fx {
+            if fx.start >= fx.end {
+                return Err(format!(
+                    "{:?} start frame {} is not before end frame {}",
+                    fx.effect, fx.start, fx.end
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Superimposes, removes entities, does view effects then adds kz stats and writes.
+    ///
+    /// Every step is a pass of one [`Pipeline`] so netmsg is parsed and written once.
+    /// `progress` is as in [`SuperimposeOptions::progress`].
+    pub fn run(&self, progress: impl Fn(Progress) + 'static) -> Result<(), String> {
+        let mut demo = open_demo_file(&self.demo)?;
+        let mut pipeline = Pipeline::new();
+
+        if !self.ghosts.is_empty() || self.ghost_folder.is_some() {
+            let mut options = SuperimposeOptions::new();
+
+            for ghost in &self.ghosts {
+                let mut file =
+                    GhostFile::with_player(&ghost.path, ghost.offset, ghost.player.clone());
+                file.label = ghost.label.clone();
+                file.label_color = ghost.label_color;
+                file.style = ghost.style.clone();
+                file.model = ghost.model.clone();
+
+                options.add_file(file);
+            }
+
+            if let Some(folder) = &self.ghost_folder {
+                options.add_folder(folder)?;
+            }
+
+            if let Some(format) = &self.ghost_format {
+                options.format(format);
+            }
+
+            if self.nameplates {
+                options.nameplates();
+            }
+
+            options.progress(progress);
+
+            pipeline.add_pass(superimpose_pass(options.get())?);
+        }
+
+        if !self.remove_entities.is_empty() {
+            pipeline.add_pass(RemoveEntities::new(self.remove_entities.clone()));
+        }
+
+        for fx in &self.view_fx {
+            pipeline.add_pass(ViewFxPass::new(&demo, fx.effect, fx.start, fx.end));
+        }
+
+        if self.kz_stats.keys || self.kz_stats.speedometer {
+            let mut addons = KzAddOns::new();
+            if self.kz_stats.keys {
+                addons.add_keys();
+            }
+            if self.kz_stats.speedometer {
+                addons.add_speedometer();
+            }
+
+            pipeline.add_pass(KzStats::new(addons.get()));
+        }
+
+        pipeline.run(&mut demo)?;
+
+        write_demo_file(demo, &self.output)
+    }
+}
@@ -0,0 +1,756 @@
+use crate::netmsg_doer::write_single_netmsg;
+use hldemo::{
+    ClientDataData, ConsoleCommandData, DemoBufferData, Directory, DirectoryEntry, EventArgs,
+    EventData, Frame, Header, MoveVars, NetMsgData, NetMsgFrameType, NetMsgInfo, RefParams,
+    SoundData, UserCmd, WeaponAnimData,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::path::Path;
+
+use super::*;
+
+// hldemo types don't do serde so they are mirrored here.
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Header")]
+struct HeaderDef<'a> {
+    demo_protocol: i32,
+    net_protocol: i32,
+    #[serde(
+        serialize_with = "c_str::padded::serialize::<260, _>",
+        deserialize_with = "c_str::padded::deserialize::<260, _>"
+    )]
+    map_name: &'a [u8],
+    #[serde(
+        serialize_with = "c_str::padded::serialize::<260, _>",
+        deserialize_with = "c_str::padded::deserialize::<260, _>"
+    )]
+    game_dir: &'a [u8],
+    map_crc: u32,
+    directory_offset: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "ConsoleCommandData")]
+struct ConsoleCommandDataDef<'a> {
+    #[serde(
+        serialize_with = "c_str::padded::serialize::<64, _>",
+        deserialize_with = "c_str::padded::deserialize::<64, _>"
+    )]
+    command: &'a [u8],
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "ClientDataData")]
+struct ClientDataDataDef {
+    origin: [f32; 3],
+    viewangles: [f32; 3],
+    weapon_bits: i32,
+    fov: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "EventArgs")]
+struct EventArgsDef {
+    flags: i32,
+    entity_index: i32,
+    origin: [f32; 3],
+    angles: [f32; 3],
+    velocity: [f32; 3],
+    ducking: i32,
+    fparam1: f32,
+    fparam2: f32,
+    iparam1: i32,
+    iparam2: i32,
+    bparam1: i32,
+    bparam2: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "EventData")]
+struct EventDataDef {
+    flags: i32,
+    index: i32,
+    delay: f32,
+    #[serde(with = "EventArgsDef")]
+    args: EventArgs,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "WeaponAnimData")]
+struct WeaponAnimDataDef {
+    anim: i32,
+    body: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "SoundData")]
+struct SoundDataDef<'a> {
+    channel: i32,
+    #[serde(with = "c_str")]
+    sample: &'a [u8],
+    attenuation: f32,
+    volume: f32,
+    flags: i32,
+    pitch: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "DemoBufferData")]
+struct DemoBufferDataDef<'a> {
+    #[serde(deserialize_with = "leak::slice")]
+    buffer: &'a [u8],
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "RefParams")]
+struct RefParamsDef {
+    vieworg: [f32; 3],
+    viewangles: [f32; 3],
+    forward: [f32; 3],
+    right: [f32; 3],
+    up: [f32; 3],
+    frametime: f32,
+    time: f32,
+    intermission: i32,
+    paused: i32,
+    spectator: i32,
+    onground: i32,
+    waterlevel: i32,
+    simvel: [f32; 3],
+    simorg: [f32; 3],
+    viewheight: [f32; 3],
+    idealpitch: f32,
+    cl_viewangles: [f32; 3],
+    health: i32,
+    crosshairangle: [f32; 3],
+    viewsize: f32,
+    punchangle: [f32; 3],
+    maxclients: i32,
+    viewentity: i32,
+    playernum: i32,
+    max_entities: i32,
+    demoplayback: i32,
+    hardware: i32,
+    smoothing: i32,
+    ptr_cmd: i32,
+    ptr_movevars: i32,
+    viewport: [i32; 4],
+    next_view: i32,
+    only_client_draw: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "UserCmd")]
+struct UserCmdDef {
+    lerp_msec: i16,
+    msec: u8,
+    viewangles: [f32; 3],
+    forwardmove: f32,
+    sidemove: f32,
+    upmove: f32,
+    lightlevel: i8,
+    buttons: u16,
+    impulse: i8,
+    weaponselect: i8,
+    impact_index: i32,
+    impact_position: [f32; 3],
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "MoveVars")]
+struct MoveVarsDef<'a> {
+    gravity: f32,
+    stopspeed: f32,
+    maxspeed: f32,
+    spectatormaxspeed: f32,
+    accelerate: f32,
+    airaccelerate: f32,
+    wateraccelerate: f32,
+    friction: f32,
+    edgefriction: f32,
+    waterfriction: f32,
+    entgravity: f32,
+    bounce: f32,
+    stepsize: f32,
+    maxvelocity: f32,
+    zmax: f32,
+    wave_height: f32,
+    footsteps: i32,
+    #[serde(
+        serialize_with = "c_str::padded::serialize::<32, _>",
+        deserialize_with = "c_str::padded::deserialize::<32, _>"
+    )]
+    sky_name: &'a [u8],
+    rollangle: f32,
+    rollspeed: f32,
+    skycolor_r: f32,
+    skycolor_g: f32,
+    skycolor_b: f32,
+    skyvec_x: f32,
+    skyvec_y: f32,
+    skyvec_z: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "NetMsgInfo")]
+struct NetMsgInfoDef<'a> {
+    timestamp: f32,
+    #[serde(with = "RefParamsDef")]
+    ref_params: RefParams,
+    #[serde(with = "UserCmdDef")]
+    usercmd: UserCmd,
+    #[serde(borrow, with = "MoveVarsDef")]
+    movevars: MoveVars<'a>,
+    view: [f32; 3],
+    viewmodel: i32,
+}
+
+/// The whole demo with every netmsg parsed into messages.
+#[derive(Serialize, Deserialize)]
+pub struct JsonDemo<'a> {
+    #[serde(borrow, with = "HeaderDef")]
+    pub header: Header<'a>,
+    #[serde(borrow)]
+    pub entries: Vec<JsonEntry<'a>>,
+}
+
+/// Offset is left out because the writer works it out.
+#[derive(Serialize, Deserialize)]
+pub struct JsonEntry<'a> {
+    pub entry_type: i32,
+    #[serde(
+        serialize_with = "c_str::padded::serialize::<64, _>",
+        deserialize_with = "c_str::padded::deserialize::<64, _>"
+    )]
+    pub description: &'a [u8],
+    pub flags: i32,
+    pub cd_track: i32,
+    pub track_time: f32,
+    pub frame_count: i32,
+    pub file_length: i32,
+    #[serde(borrow)]
+    pub frames: Vec<JsonFrame<'a>>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct JsonFrame<'a> {
+    pub time: f32,
+    pub frame: i32,
+    #[serde(borrow)]
+    pub data: JsonFrameData<'a>,
+}
+
+/// `M` is how messages are kept, [`Value`] with deltas decoded by default.
+#[derive(Serialize, Deserialize)]
+pub enum JsonFrameData<'a, M = Value> {
+    DemoStart,
+    #[serde(borrow, with = "ConsoleCommandDataDef")]
+    ConsoleCommand(ConsoleCommandData<'a>),
+    #[serde(with = "ClientDataDataDef")]
+    ClientData(ClientDataData),
+    NextSection,
+    #[serde(with = "EventDataDef")]
+    Event(EventData),
+    #[serde(with = "WeaponAnimDataDef")]
+    WeaponAnim(WeaponAnimData),
+    #[serde(borrow, with = "SoundDataDef")]
+    Sound(SoundData<'a>),
+    #[serde(borrow, with = "DemoBufferDataDef")]
+    DemoBuffer(DemoBufferData<'a>),
+    NetMsg {
+        /// 0 for Start, 1 for Normal, anything else is unknown.
+        frame_type: u8,
+        #[serde(borrow, with = "NetMsgInfoDef")]
+        info: NetMsgInfo<'a>,
+        incoming_sequence: i32,
+        incoming_acknowledged: i32,
+        incoming_reliable_acknowledged: i32,
+        incoming_reliable_sequence: i32,
+        outgoing_sequence: i32,
+        reliable_sequence: i32,
+        last_reliable_sequence: i32,
+        messages: Vec<M>,
+    },
+}
+
+/// Demo to JSON, netmsg included as parsed messages.
+pub fn export(demo: &Demo) -> Result<String, String> {
+    let mut delta_decoders = get_initial_delta();
+    let mut custom_messages = HashMap::<u8, SvcNewUserMsg>::new();
+
+    let mut entries = vec![];
+
+    for (entry_idx, entry) in demo.directory.entries.iter().enumerate() {
+        let mut frames = vec![];
+
+        for (frame_idx, frame) in entry.frames.iter().enumerate() {
+            frames.push(JsonFrame {
+                time: frame.time,
+                frame: frame.frame,
+                data: export_frame_data(
+                    &frame.data,
+                    &mut delta_decoders,
+                    &mut custom_messages,
+                    (entry_idx, frame_idx),
+                    |message, delta_decoders| message_to_value(&message, delta_decoders).map(Some),
+                )?,
+            });
+        }
+
+        entries.push(JsonEntry {
+            entry_type: entry.entry_type,
+            description: entry.description,
+            flags: entry.flags,
+            cd_track: entry.cd_track,
+            track_time: entry.track_time,
+            frame_count: entry.frame_count,
+            file_length: entry.file_length,
+            frames,
+        });
+    }
+
+    let demo = JsonDemo {
+        header: demo.header.clone(),
+        entries,
+    };
+
+    serde_json::to_string_pretty(&demo).map_err(|err| format!("cannot write JSON: {}", err))
+}
+
+/// `message` turns every parsed message into what is kept, `None` leaves it out.
+///
+/// `location` is the entry and frame index, for errors.
+pub(super) fn export_frame_data<'a, M>(
+    data: &FrameData<'a>,
+    delta_decoders: &mut DeltaDecoderTable,
+    custom_messages: &mut HashMap<u8, SvcNewUserMsg<'a>>,
+    location: (usize, usize),
+    mut message: impl FnMut(Message<'a>, &DeltaDecoderTable) -> Result<Option<M>, String>,
+) -> Result<JsonFrameData<'a, M>, String> {
+    let data = match data {
+        FrameData::DemoStart => JsonFrameData::DemoStart,
+        FrameData::ConsoleCommand(data) => JsonFrameData::ConsoleCommand(data.clone()),
+        FrameData::ClientData(data) => JsonFrameData::ClientData(data.clone()),
+        FrameData::NextSection => JsonFrameData::NextSection,
+        FrameData::Event(data) => JsonFrameData::Event(data.clone()),
+        FrameData::WeaponAnim(data) => JsonFrameData::WeaponAnim(data.clone()),
+        FrameData::Sound(data) => JsonFrameData::Sound(data.clone()),
+        FrameData::DemoBuffer(data) => JsonFrameData::DemoBuffer(data.clone()),
+        FrameData::NetMsg((type_, data)) => {
+            let (entry_idx, frame_idx) = location;
+            let messages = parse_frame_netmsg(
+                data.msg,
+                delta_decoders,
+                custom_messages,
+                entry_idx,
+                frame_idx,
+            )?;
+
+            let mut kept = vec![];
+            for msg in messages {
+                if let Some(msg) = message(msg, delta_decoders)? {
+                    kept.push(msg);
+                }
+            }
+
+            JsonFrameData::NetMsg {
+                frame_type: match type_ {
+                    NetMsgFrameType::Start => 0,
+                    NetMsgFrameType::Normal => 1,
+                    NetMsgFrameType::Unknown(what) => *what,
+                },
+                info: data.info.clone(),
+                incoming_sequence: data.incoming_sequence,
+                incoming_acknowledged: data.incoming_acknowledged,
+                incoming_reliable_acknowledged: data.incoming_reliable_acknowledged,
+                incoming_reliable_sequence: data.incoming_reliable_sequence,
+                outgoing_sequence: data.outgoing_sequence,
+                reliable_sequence: data.reliable_sequence,
+                last_reliable_sequence: data.last_reliable_sequence,
+                messages: kept,
+            }
+        }
+    };
+
+    Ok(data)
+}
+
+/// Message as JSON with delta fields as numbers and strings, sorted by name.
+pub(super) fn message_to_value(
+    message: &Message,
+    delta_decoders: &DeltaDecoderTable,
+) -> Result<Value, String> {
+    let mut value = serde_json::to_value(message)
+        .map_err(|err| format!("cannot write {}: {}", message_name(message), err))?;
+    let name = engine_message_name(&value);
+
+    // Bytes always decode, at worst they stay bytes.
+    visit_deltas(&mut value, &name, delta_decoders, &decode_delta)?;
+
+    Ok(value)
+}
+
+/// [`message_to_value`] the other way around.
+fn value_to_message<'a>(
+    mut value: Value,
+    delta_decoders: &DeltaDecoderTable,
+) -> Result<Message<'a>, String> {
+    let name = engine_message_name(&value);
+    visit_deltas(&mut value, &name, delta_decoders, &encode_delta)?;
+
+    Message::deserialize(value).map_err(|err| format!("cannot read message {}: {}", name, err))
+}
+
+/// `SvcEvent` from `{"EngineMessage": {"SvcEvent": {..}}}`.
+fn engine_message_name(value: &Value) -> String {
+    value
+        .get("EngineMessage")
+        .and_then(|message| message.as_object())
+        .and_then(|message| message.keys().next())
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Descriptions a delta can be made of, by the field it is in.
+fn delta_decoder_names(message_name: &str, key: &str) -> Option<&'static [&'static str]> {
+    match key {
+        "client_data" => Some(&["clientdata_t\0"]),
+        "weapon_data" => Some(&["weapon_data_t\0"]),
+        "event_args" => Some(&["event_t\0"]),
+        "delta" if message_name == "SvcEvent" => Some(&["event_t\0"]),
+        "delta" | "extra_data" => Some(&[
+            "entity_state_player_t\0",
+            "entity_state_t\0",
+            "custom_entity_state_t\0",
+        ]),
+        _ => None,
+    }
+}
+
+type DeltaVisitor = dyn Fn(&mut Map<String, Value>, &[&DeltaDecoder]) -> Result<(), String>;
+
+/// Goes through the whole message and calls `visit` on every delta.
+fn visit_deltas(
+    value: &mut Value,
+    message_name: &str,
+    delta_decoders: &DeltaDecoderTable,
+    visit: &DeltaVisitor,
+) -> Result<(), String> {
+    match value {
+        Value::Object(object) => {
+            for (key, field) in object.iter_mut() {
+                let decoders: Vec<&DeltaDecoder> = delta_decoder_names(message_name, key)
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|name| delta_decoders.get(*name))
+                    .collect();
+
+                match field {
+                    Value::Object(delta) if !decoders.is_empty() => visit(delta, &decoders)?,
+                    Value::Array(deltas) if key == "extra_data" => {
+                        for delta in deltas {
+                            if let Value::Object(delta) = delta {
+                                visit(delta, &decoders)?;
+                            }
+                        }
+                    }
+                    field => visit_deltas(field, message_name, delta_decoders, visit)?,
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                visit_deltas(value, message_name, delta_decoders, visit)?;
+            }
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
+
+/// Entity delta field as a number or string, its bytes when it doesn't decode.
+pub(super) fn decode_entity_field(
+    key: &str,
+    bytes: &[u8],
+    delta_decoders: &DeltaDecoderTable,
+) -> Value {
+    let decoders: Vec<&DeltaDecoder> = delta_decoder_names("", "delta")
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|name| delta_decoders.get(*name))
+        .collect();
+
+    find_description(&decoders, key)
+        .and_then(|description| decode_delta_field(description, bytes))
+        .unwrap_or_else(|| json!(bytes))
+}
+
+fn find_description<'a>(decoders: &[&'a DeltaDecoder], name: &str) -> Option<&'a DeltaDecoderS> {
+    decoders
+        .iter()
+        .flat_map(|decoder| decoder.iter())
+        .find(|description| description.name == name.as_bytes())
+}
+
+fn decode_delta(delta: &mut Map<String, Value>, decoders: &[&DeltaDecoder]) -> Result<(), String> {
+    for (key, value) in std::mem::take(delta) {
+        let bytes: Vec<u8> = serde_json::from_value(value).map_err(|err| err.to_string())?;
+
+        let value = find_description(decoders, &key)
+            .and_then(|description| decode_delta_field(description, &bytes))
+            .unwrap_or_else(|| json!(bytes));
+
+        delta.insert(key.trim_end_matches('\0').to_owned(), value);
+    }
+
+    Ok(())
+}
+
+fn encode_delta(delta: &mut Map<String, Value>, decoders: &[&DeltaDecoder]) -> Result<(), String> {
+    for (key, value) in std::mem::take(delta) {
+        let key = format!("{}\0", key);
+
+        // Bytes are what didn't decode.
+        let bytes: Vec<u8> = if value.is_array() {
+            serde_json::from_value(value).map_err(|err| err.to_string())?
+        } else {
+            let description = find_description(decoders, &key)
+                .ok_or_else(|| format!("no delta description for {}", key))?;
+
+            encode_delta_field(description, &value)
+                .ok_or_else(|| format!("cannot encode {} as {}", value, key))?
+        };
+
+        delta.insert(key, json!(bytes));
+    }
+
+    Ok(())
+}
+
+/// Same types as `parse_delta_field` in `netmsg_doer::utils`, `None` when the bytes don't fit.
+fn decode_delta_field(description: &DeltaDecoderS, bytes: &[u8]) -> Option<Value> {
+    let has = |flag: DeltaType| description.flags & flag as u32 != 0;
+    let is_signed = has(DeltaType::Signed);
+
+    if has(DeltaType::Byte) {
+        let bytes = bytes.try_into().ok()?;
+        Some(if is_signed {
+            json!(i8::from_le_bytes(bytes))
+        } else {
+            json!(u8::from_le_bytes(bytes))
+        })
+    } else if has(DeltaType::Short) {
+        let bytes = bytes.try_into().ok()?;
+        Some(if is_signed {
+            json!(i16::from_le_bytes(bytes))
+        } else {
+            json!(u16::from_le_bytes(bytes))
+        })
+    } else if has(DeltaType::Integer) {
+        let bytes = bytes.try_into().ok()?;
+        Some(if is_signed {
+            json!(i32::from_le_bytes(bytes))
+        } else {
+            json!(u32::from_le_bytes(bytes))
+        })
+    } else if has(DeltaType::Float)
+        || has(DeltaType::TimeWindow8)
+        || has(DeltaType::TimeWindowBig)
+        || has(DeltaType::Angle)
+    {
+        let value = f32::from_le_bytes(bytes.try_into().ok()?);
+
+        // Shortest text that is still the same f32, not the f64 it widens to.
+        if !value.is_finite() {
+            return None;
+        }
+
+        value
+            .to_string()
+            .parse::<f64>()
+            .ok()
+            .map(|value| json!(value))
+    } else if has(DeltaType::String) {
+        let text = bytes.strip_suffix(&[0])?;
+        if text.contains(&0) {
+            return None;
+        }
+
+        std::str::from_utf8(text).ok().map(|text| json!(text))
+    } else {
+        None
+    }
+}
+
+fn encode_delta_field(description: &DeltaDecoderS, value: &Value) -> Option<Vec<u8>> {
+    let has = |flag: DeltaType| description.flags & flag as u32 != 0;
+    let is_signed = has(DeltaType::Signed);
+
+    fn int<T: TryFrom<i64>>(value: &Value) -> Option<T> {
+        value.as_i64().and_then(|value| T::try_from(value).ok())
+    }
+
+    if has(DeltaType::Byte) {
+        if is_signed {
+            int::<i8>(value).map(|value| value.to_le_bytes().to_vec())
+        } else {
+            int::<u8>(value).map(|value| value.to_le_bytes().to_vec())
+        }
+    } else if has(DeltaType::Short) {
+        if is_signed {
+            int::<i16>(value).map(|value| value.to_le_bytes().to_vec())
+        } else {
+            int::<u16>(value).map(|value| value.to_le_bytes().to_vec())
+        }
+    } else if has(DeltaType::Integer) {
+        if is_signed {
+            int::<i32>(value).map(|value| value.to_le_bytes().to_vec())
+        } else {
+            int::<u32>(value).map(|value| value.to_le_bytes().to_vec())
+        }
+    } else if has(DeltaType::Float)
+        || has(DeltaType::TimeWindow8)
+        || has(DeltaType::TimeWindowBig)
+        || has(DeltaType::Angle)
+    {
+        value
+            .as_f64()
+            .map(|value| (value as f32).to_le_bytes().to_vec())
+    } else if has(DeltaType::String) {
+        value.as_str().map(|text| {
+            let mut bytes = text.as_bytes().to_vec();
+            bytes.push(0);
+            bytes
+        })
+    } else {
+        None
+    }
+}
+
+/// JSON from [`export`] back to a demo that can be written.
+///
+/// Messages are written one by one and parsed back right away so delta descriptions
+/// and user messages declared in the JSON are known to the messages after them.
+pub fn import<'a>(json: &'a str) -> Result<Demo<'a>, String> {
+    let demo: JsonDemo<'a> =
+        serde_json::from_str(json).map_err(|err| format!("cannot read demo json: {}", err))?;
+
+    let mut delta_decoders = get_initial_delta();
+    let mut custom_messages = HashMap::<u8, SvcNewUserMsg>::new();
+
+    let mut entries = vec![];
+    for entry in demo.entries {
+        let mut frames = vec![];
+        for frame in entry.frames {
+            frames.push(Frame {
+                time: frame.time,
+                frame: frame.frame,
+                data: import_frame_data(frame.data, &mut delta_decoders, &mut custom_messages)?,
+            });
+        }
+
+        entries.push(DirectoryEntry {
+            entry_type: entry.entry_type,
+            description: entry.description,
+            flags: entry.flags,
+            cd_track: entry.cd_track,
+            track_time: entry.track_time,
+            frame_count: entry.frame_count,
+            offset: 0, // will be corrected when written
+            file_length: entry.file_length,
+            frames,
+        });
+    }
+
+    Ok(Demo {
+        header: demo.header,
+        directory: Directory { entries },
+    })
+}
+
+fn import_frame_data<'a>(
+    data: JsonFrameData<'a>,
+    delta_decoders: &mut DeltaDecoderTable,
+    custom_messages: &mut HashMap<u8, SvcNewUserMsg<'a>>,
+) -> Result<FrameData<'a>, String> {
+    let data = match data {
+        JsonFrameData::DemoStart => FrameData::DemoStart,
+        JsonFrameData::ConsoleCommand(data) => FrameData::ConsoleCommand(data),
+        JsonFrameData::ClientData(data) => FrameData::ClientData(data),
+        JsonFrameData::NextSection => FrameData::NextSection,
+        JsonFrameData::Event(data) => FrameData::Event(data),
+        JsonFrameData::WeaponAnim(data) => FrameData::WeaponAnim(data),
+        JsonFrameData::Sound(data) => FrameData::Sound(data),
+        JsonFrameData::DemoBuffer(data) => FrameData::DemoBuffer(data),
+        JsonFrameData::NetMsg {
+            frame_type,
+            info,
+            incoming_sequence,
+            incoming_acknowledged,
+            incoming_reliable_acknowledged,
+            incoming_reliable_sequence,
+            outgoing_sequence,
+            reliable_sequence,
+            last_reliable_sequence,
+            messages,
+        } => {
+            let mut msg: Vec<u8> = vec![];
+
+            for message in messages {
+                let message = value_to_message(message, delta_decoders)?;
+                let name = message_name(&message);
+
+                let bytes: &'a [u8] =
+                    write_single_netmsg(message, delta_decoders, custom_messages).leak();
+                parse_netmsg(bytes, delta_decoders, custom_messages)
+                    .map_err(|err| format!("cannot parse {} back: {}", name, err))?;
+
+                msg.extend_from_slice(bytes);
+            }
+
+            let frame_type = match frame_type {
+                0 => NetMsgFrameType::Start,
+                1 => NetMsgFrameType::Normal,
+                what => NetMsgFrameType::Unknown(what),
+            };
+
+            FrameData::NetMsg((
+                frame_type,
+                NetMsgData {
+                    info,
+                    incoming_sequence,
+                    incoming_acknowledged,
+                    incoming_reliable_acknowledged,
+                    incoming_reliable_sequence,
+                    outgoing_sequence,
+                    reliable_sequence,
+                    last_reliable_sequence,
+                    msg: msg.leak(),
+                },
+            ))
+        }
+    };
+
+    Ok(data)
+}
+
+/// [`export`] straight to a file.
+pub fn export_file(demo: &Demo, path: impl AsRef<Path>) -> Result<(), String> {
+    let path = path.as_ref();
+
+    std::fs::write(path, export(demo)?)
+        .map_err(|err| format!("cannot write {}: {}", path.display(), err))
+}
+
+/// [`import`] straight from a file.
+pub fn import_file<'a>(path: impl AsRef<Path>) -> Result<Demo<'a>, String> {
+    let path = path.as_ref();
+
+    let json = std::fs::read_to_string(path)
+        .map_err(|err| format!("cannot read {}: {}", path.display(), err))?;
+
+    import(json.leak())
+}
@@ -1,9 +1,12 @@
+use hldemo::{Frame, NetMsgData};
+
 use crate::utils::Buttons;
 use crate::wrap_message;
 
 use self::add_keys::add_keys;
 use self::add_speedometer::add_speedometer;
 
+use super::pipeline::{DemoPass, PassContext, Pipeline};
 use super::*;
 
 pub mod add_keys;
@@ -78,63 +81,88 @@ impl<'a> KzInfo<'a> {
     }
 }
 
-pub fn add_kz_stats(demo: &mut Demo, addons: &KzAddOns) {
-    let mut delta_decoders = get_initial_delta();
-    let mut custom_messages = HashMap::<u8, SvcNewUserMsg>::new();
-
-    for (entry_idx, entry) in demo.directory.entries.iter_mut().enumerate() {
-        let mut curr: Option<KzInfo> = None;
-        let mut prev: Option<KzInfo> = None;
-
-        for frame in &mut entry.frames {
-            match &mut frame.data {
-                FrameData::NetMsg((_, netmsg)) => {
-                    let (_, mut messages) =
-                        parse_netmsg(netmsg.msg, &mut delta_decoders, &mut custom_messages)
-                            .unwrap();
-
-                    if let Some(ref mut curr) = curr {
-                        curr.forward = netmsg.info.usercmd.forwardmove;
-                        curr.side = netmsg.info.usercmd.sidemove;
-                        curr.up = netmsg.info.usercmd.upmove;
-                        curr.buttons = netmsg.info.usercmd.buttons;
-                        // movetype?
-                        // weapon?
-                        // flags?
-                    }
-
-                    if addons.speedometer {
-                        if let Some(temp_entity) = add_speedometer(prev.as_ref(), curr.as_ref()) {
-                            messages.push(wrap_message!(SvcTempEntity, temp_entity));
-                        }
-                    }
-
-                    if addons.keys {
-                        if let Some(temp_entity) = add_keys(curr.as_ref()) {
-                            messages.push(wrap_message!(SvcTempEntity, temp_entity));
-                        }
-                    }
-
-                    let write = write_netmsg(messages, &mut delta_decoders, &custom_messages);
-                    netmsg.msg = write.leak();
-                }
-                FrameData::ClientData(client_data) => {
-                    prev = curr;
-                    curr = Some(KzInfo::new(
-                        client_data.origin,
-                        client_data.viewangles,
-                        client_data.weapon_bits,
-                        frame.time,
-                    ));
-                }
-                FrameData::ConsoleCommand(command) => {
-                    if let Some(ref mut curr) = curr {
-                        curr.commands = command.command;
-                    }
+pub fn add_kz_stats(demo: &mut Demo, addons: &KzAddOns) -> Result<(), String> {
+    Pipeline::new().add_pass(KzStats::new(addons)).run(demo)
+}
+
+pub struct KzStats<'a> {
+    keys: bool,
+    speedometer: bool,
+    curr: Option<KzInfo<'a>>,
+    prev: Option<KzInfo<'a>>,
+}
+
+impl<'a> KzStats<'a> {
+    pub fn new(addons: &KzAddOns) -> Self {
+        Self {
+            keys: addons.keys,
+            speedometer: addons.speedometer,
+            curr: None,
+            prev: None,
+        }
+    }
+}
+
+impl<'a> DemoPass<'a> for KzStats<'a> {
+    fn frame(&mut self, ctx: &PassContext<'_, 'a>, frame: &mut Frame<'a>) -> Result<(), String> {
+        // Nothing carries over to next entry.
+        if ctx.frame_idx == 0 {
+            self.curr = None;
+            self.prev = None;
+        }
+
+        match &frame.data {
+            FrameData::ClientData(client_data) => {
+                self.prev = self.curr.take();
+                self.curr = Some(KzInfo::new(
+                    client_data.origin,
+                    client_data.viewangles,
+                    client_data.weapon_bits,
+                    frame.time,
+                ));
+            }
+            FrameData::ConsoleCommand(command) => {
+                if let Some(ref mut curr) = self.curr {
+                    curr.commands = command.command;
                 }
-                _ => (),
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    fn messages(
+        &mut self,
+        _ctx: &PassContext<'_, 'a>,
+        netmsg: &mut NetMsgData<'a>,
+        messages: &mut Vec<Message<'a>>,
+    ) -> Result<bool, String> {
+        if let Some(ref mut curr) = self.curr {
+            curr.forward = netmsg.info.usercmd.forwardmove;
+            curr.side = netmsg.info.usercmd.sidemove;
+            curr.up = netmsg.info.usercmd.upmove;
+            curr.buttons = netmsg.info.usercmd.buttons;
+            // movetype?
+            // weapon?
+            // flags?
+        }
+
+        let message_count = messages.len();
+
+        if self.speedometer {
+            if let Some(temp_entity) = add_speedometer(self.prev.as_ref(), self.curr.as_ref()) {
+                messages.push(wrap_message!(SvcTempEntity, temp_entity));
             }
         }
+
+        if self.keys {
+            if let Some(temp_entity) = add_keys(self.curr.as_ref()) {
+                messages.push(wrap_message!(SvcTempEntity, temp_entity));
+            }
+        }
+
+        Ok(messages.len() != message_count)
     }
 }
 
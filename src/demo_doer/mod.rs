@@ -1,29 +1,133 @@
-use demosuperimpose_goldsrc::netmsg_doer::{
+use crate::netmsg_doer::{
     parse_netmsg,
     utils::{get_initial_delta, BitSliceCast},
     write_netmsg,
 };
-use demosuperimpose_goldsrc::types::*;
+use crate::types::*;
 
 use std::collections::HashMap;
 
-use hldemo::{Demo, FrameData};
+use hldemo::{Demo, DirectoryEntry, Frame, FrameData};
 
 pub mod add_debug;
+pub mod annotate;
+pub mod concat;
+pub mod diff;
+pub mod dump;
+pub mod entity_tracker;
 pub mod example;
 pub mod get_ghost;
 pub mod ghost_to_demo;
+pub mod info;
+pub mod job;
+pub mod json;
 pub mod kz_stats;
 pub mod netmsg_rewrite_test;
 pub mod offset_viewangles;
+pub mod pipeline;
 pub mod remove_entities;
+pub mod resample;
 pub mod superimpose;
+pub mod time_scale;
 pub mod trenchbroom_player_point;
+pub mod trim;
 
 // use bitvec::bitvec;
 // use bitvec::prelude::*;
 
-use crate::write_demo;
+use crate::writer::DemoWriter;
 
 use std::fs::File;
 use std::io::Read;
+use std::path::Path;
+use std::str::from_utf8;
+
+/// Opens a demo file.
+///
+/// Bytes are leaked so the demo can live as long as it needs to.
+pub fn open_demo_file<'a>(path: impl AsRef<Path>) -> Result<Demo<'a>, String> {
+    let path = path.as_ref();
+
+    File::open(path)
+        .map_err(|err| err.to_string())
+        .and_then(read_demo)
+        .map_err(|err| format!("cannot read {}: {}", path.display(), err))
+}
+
+/// [`open_demo_file`] but from anything that reads.
+pub fn read_demo<'a>(mut reader: impl Read) -> Result<Demo<'a>, String> {
+    let mut bytes = Vec::new();
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(|err| err.to_string())?;
+
+    Demo::parse(bytes.leak()).map_err(|err| format!("not a demo: {:?}", err))
+}
+
+pub fn write_demo_file(demo: Demo, path: impl AsRef<Path>) -> Result<(), String> {
+    let path = path.as_ref();
+
+    DemoWriter::new(path.to_string_lossy().into_owned())
+        .write_file(demo)
+        .map_err(|err| format!("cannot write {}: {}", path.display(), err))
+}
+
+/// Name of the message type, like `SvcPacketEntities` or the name of the user message.
+pub fn message_name(message: &Message) -> String {
+    match message {
+        Message::UserMessage(message) => from_utf8(message.name)
+            .unwrap_or("UserMessage")
+            .trim_end_matches('\0')
+            .to_owned(),
+        Message::EngineMessage(message) => {
+            let debug = format!("{:?}", message);
+            debug
+                .split(|c: char| !c.is_alphanumeric())
+                .next()
+                .unwrap()
+                .to_owned()
+        }
+    }
+}
+
+/// Null terminated bytes as text.
+pub fn bytes_to_string(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes)
+        .trim_end_matches('\0')
+        .to_owned()
+}
+
+/// [`parse_netmsg`] with an error saying which frame, for passes that return `Result`.
+pub fn parse_frame_netmsg<'a>(
+    msg: &'a [u8],
+    delta_decoders: &mut DeltaDecoderTable,
+    custom_messages: &mut HashMap<u8, SvcNewUserMsg<'a>>,
+    entry_idx: usize,
+    frame_idx: usize,
+) -> Result<Vec<Message<'a>>, String> {
+    parse_netmsg(msg, delta_decoders, custom_messages)
+        .map(|(_, messages)| messages)
+        .map_err(|err| {
+            let reason = match err {
+                nom::Err::Error(err) | nom::Err::Failure(err) => {
+                    format!("{:?} with {} bytes left", err.code, err.input.len())
+                }
+                nom::Err::Incomplete(_) => "cut off".to_owned(),
+            };
+
+            format!(
+                "cannot parse netmsg of entry {} frame {}: {}",
+                entry_idx, frame_idx, reason
+            )
+        })
+}
+
+/// Takes the frames of `entry` for a pass that puts back only some of them.
+///
+/// `NextSection` is left out, the writer adds it back at the end of every entry.
+pub fn take_frames<'a>(entry: &mut DirectoryEntry<'a>) -> Vec<Frame<'a>> {
+    std::mem::take(&mut entry.frames)
+        .into_iter()
+        .filter(|frame| !matches!(frame.data, FrameData::NextSection))
+        .collect()
+}
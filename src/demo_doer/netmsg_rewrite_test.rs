@@ -1,8 +1,6 @@
-use std::collections::HashMap;
-
-use demosuperimpose_goldsrc::netmsg_doer::{parse_netmsg, utils::get_initial_delta, write_netmsg};
-use hldemo::{Demo, FrameData};
+use hldemo::{Demo, NetMsgData};
 
+use super::pipeline::{DemoPass, PassContext, Pipeline};
 use super::*;
 
 pub fn netmsg_rewrite_test(demo: &str) {
@@ -12,26 +10,25 @@ pub fn netmsg_rewrite_test(demo: &str) {
 
     let mut demo = hldemo::Demo::parse(bytes.leak()).unwrap(); // heh
 
-    _netmsg_rewrite_test(&mut demo);
+    _netmsg_rewrite_test(&mut demo).unwrap();
 
     write_demo!("test.dem", demo);
 }
 
-fn _netmsg_rewrite_test(demo: &mut Demo) {
-    let mut delta_decoders = get_initial_delta();
-    let mut custom_messages = HashMap::<u8, SvcNewUserMsg>::new();
-
-    for entry in &mut demo.directory.entries {
-        for frame in &mut entry.frames {
-            if let FrameData::NetMsg((_, data)) = &mut frame.data {
-                let (_, messages) =
-                    parse_netmsg(data.msg, &mut delta_decoders, &mut custom_messages).unwrap();
-
-                let write = write_netmsg(messages, &mut delta_decoders, &custom_messages);
+fn _netmsg_rewrite_test(demo: &mut Demo) -> Result<(), String> {
+    Pipeline::new().add_pass(NetmsgRewriteTest).run(demo)
+}
 
-                data.msg = write.leak();
-                // data.msg = &[];
-            }
-        }
+/// Rewrites every netmsg without changing anything.
+pub struct NetmsgRewriteTest;
+
+impl<'a> DemoPass<'a> for NetmsgRewriteTest {
+    fn messages(
+        &mut self,
+        _ctx: &PassContext<'_, 'a>,
+        _data: &mut NetMsgData<'a>,
+        _messages: &mut Vec<Message<'a>>,
+    ) -> Result<bool, String> {
+        Ok(true)
     }
 }
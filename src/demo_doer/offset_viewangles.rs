@@ -1,5 +1,182 @@
+use std::str::FromStr;
+
+use hldemo::Frame;
+use serde::Deserialize;
+
+use super::pipeline::{DemoPass, PassContext, Pipeline};
 use super::*;
 
+/// One of the view effects below, by name.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ViewFx {
+    FrontFlip,
+    BackFlip,
+    SpinLeft,
+    SpinRight,
+    PauseView,
+}
+
+impl FromStr for ViewFx {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "front-flip" => Ok(Self::FrontFlip),
+            "back-flip" => Ok(Self::BackFlip),
+            "spin-left" => Ok(Self::SpinLeft),
+            "spin-right" => Ok(Self::SpinRight),
+            "pause-view" => Ok(Self::PauseView),
+            _ => Err(format!(
+                "unknown view effect {}, expected front-flip, back-flip, spin-left, spin-right or pause-view",
+                s
+            )),
+        }
+    }
+}
+
+/// Does `effect` from frame `start` to frame `end`.
+pub fn view_fx(demo: &mut Demo, effect: ViewFx, start: usize, end: usize) -> Result<(), String> {
+    run_view_fx(demo, effect.kind(), start, end)
+}
+
+fn run_view_fx(demo: &mut Demo, kind: ViewFxKind, start: usize, end: usize) -> Result<(), String> {
+    let pass = ViewFxPass::with_kind(demo, kind, start, end);
+    Pipeline::new().add_pass(pass).run(demo)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ViewFxKind {
+    /// See [`scalar_complete_rotation`].
+    Rotation {
+        scalar: f32,
+        viewangles_index: usize,
+    },
+    /// See [`pause_view`].
+    Pause,
+}
+
+impl ViewFx {
+    fn kind(self) -> ViewFxKind {
+        let rotation = |scalar, viewangles_index| ViewFxKind::Rotation {
+            scalar,
+            viewangles_index,
+        };
+
+        match self {
+            ViewFx::FrontFlip => rotation(1., 0),
+            ViewFx::BackFlip => rotation(-1., 0),
+            ViewFx::SpinLeft => rotation(1., 1),
+            ViewFx::SpinRight => rotation(-1., 1),
+            ViewFx::PauseView => ViewFxKind::Pause,
+        }
+    }
+}
+
+/// [`view_fx`] as a pass so it can run along with other passes.
+///
+/// The frame each entry ends at is looked up when the pass is made, so effects that overlap
+/// each see the view from before any of them.
+pub struct ViewFxPass {
+    kind: ViewFxKind,
+    start: usize,
+    end: usize,
+    // Frame the effect ends at and the view there, for every entry but LOADING.
+    ends: Vec<Option<(usize, [f32; 3])>>,
+    // First frame of the current entry that has the effect, its viewangles and vieworg.
+    started: Option<(usize, [f32; 3], [f32; 3])>,
+}
+
+impl ViewFxPass {
+    pub fn new(demo: &Demo, effect: ViewFx, start: usize, end: usize) -> Self {
+        Self::with_kind(demo, effect.kind(), start, end)
+    }
+
+    fn with_kind(demo: &Demo, kind: ViewFxKind, start: usize, end: usize) -> Self {
+        let ends = demo
+            .directory
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(entry_idx, entry)| match kind {
+                ViewFxKind::Rotation { .. } if entry_idx != 0 => {
+                    Some(search_client_data_frame(&entry.frames, end))
+                }
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            kind,
+            start,
+            end,
+            ends,
+            started: None,
+        }
+    }
+}
+
+impl<'a> DemoPass<'a> for ViewFxPass {
+    fn frame(&mut self, ctx: &PassContext<'_, 'a>, frame: &mut Frame<'a>) -> Result<(), String> {
+        if ctx.frame_idx == 0 {
+            self.started = None;
+        }
+
+        let ref_params = match &mut frame.data {
+            FrameData::NetMsg((_, data)) if ctx.entry_idx != 0 && ctx.frame_idx >= self.start => {
+                &mut data.info.ref_params
+            }
+            _ => return Ok(()),
+        };
+
+        let (start, start_viewangles, start_vieworigin) =
+            *self
+                .started
+                .get_or_insert((ctx.frame_idx, ref_params.viewangles, ref_params.vieworg));
+
+        match self.kind {
+            ViewFxKind::Rotation {
+                scalar,
+                viewangles_index,
+            } => {
+                // Override end because we have more accurate number.
+                let (end, end_viewangles) = match self.ends[ctx.entry_idx] {
+                    Some(end) => end,
+                    None => return Ok(()),
+                };
+
+                if ctx.frame_idx >= end {
+                    return Ok(());
+                }
+
+                let length = if scalar.is_sign_positive() {
+                    360. * scalar.abs().floor() - start_viewangles[viewangles_index]
+                        + end_viewangles[viewangles_index]
+                } else {
+                    -(360. * scalar.abs().floor() + start_viewangles[viewangles_index]
+                        - end_viewangles[viewangles_index])
+                };
+
+                // Gradient change
+                // `length` says how much we spin, so we cannot end with length but something plus length.
+                // Because we start with `start`, so it ends with `start` offset by `length`, which is `end_frame`.
+                let t = (ctx.frame_idx - start) as f32 / (end - start) as f32;
+                ref_params.viewangles[viewangles_index] = (1. - t)
+                    * start_viewangles[viewangles_index]
+                    + t * (start_viewangles[viewangles_index] + length);
+            }
+            ViewFxKind::Pause => {
+                if ctx.frame_idx < self.end {
+                    ref_params.viewangles = start_viewangles;
+                    ref_params.vieworg = start_vieworigin;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Offset the yaw for `amount` starting at `over_end` but with `over_start` to smoothly change.
 pub fn offset_yaw(demo: &mut Demo, over_start: usize, over_end: usize, amount: f32) {
     for (entry_idx, entry) in demo.directory.entries.iter_mut().enumerate() {
@@ -100,126 +277,44 @@ fn scalar_complete_rotation(
     end: usize,
     scalar: f32,
     viewangles_index: usize,
-) {
-    for (entry_idx, entry) in demo.directory.entries.iter_mut().enumerate() {
-        if entry_idx == 0 {
-            continue;
-        }
-
-        // Frame at over_end
-        let (end, end_frame_viewangles) = search_client_data_frame(&entry.frames, end);
-        let mut start_frame_viewangles: Option<[f32; 3]> = None;
-        let mut length: Option<f32> = None;
-        let mut range = end - start;
-        let mut start = start;
-
-        for (frame_idx, frame) in entry.frames.iter_mut().enumerate() {
-            match &mut frame.data {
-                FrameData::NetMsg((_, data)) => {
-                    if frame_idx < start {
-                        continue;
-                    }
-
-                    if frame_idx >= start && start_frame_viewangles.is_none() {
-                        let sign = if scalar.is_sign_positive() { 1. } else { -1. };
-
-                        start_frame_viewangles = Some(data.info.ref_params.viewangles);
-                        length = if scalar.is_sign_positive() {
-                            Some(
-                                (360. * scalar.abs().floor()
-                                    - start_frame_viewangles.unwrap()[viewangles_index]
-                                    + end_frame_viewangles[viewangles_index])
-                                    * sign,
-                            )
-                        } else {
-                            Some(
-                                (360. * scalar.abs().floor()
-                                    + start_frame_viewangles.unwrap()[viewangles_index]
-                                    - end_frame_viewangles[viewangles_index])
-                                    * sign,
-                            )
-                        };
-
-                        // Reassign for correct interpolation
-                        start = frame_idx;
-                        range = end - start;
-                    }
-
-                    if frame_idx >= start && frame_idx < end && length.is_some() {
-                        // Gradient change
-                        let t = (frame_idx - start) as f32 / range as f32;
-                        // `length` says how much we spin, so we cannot end with length but something plus length.
-                        // Because we start with `start`, so it ends with `start` offset by `length`, which is `end_frame`.
-                        data.info.ref_params.viewangles[viewangles_index] = (1. - t)
-                            * start_frame_viewangles.unwrap()[viewangles_index]
-                            + t * (start_frame_viewangles.unwrap()[viewangles_index]
-                                + length.unwrap());
-                    }
-                }
-                _ => (),
-            }
-        }
-    }
+) -> Result<(), String> {
+    let kind = ViewFxKind::Rotation {
+        scalar,
+        viewangles_index,
+    };
+    run_view_fx(demo, kind, start, end)
 }
 
 /// Generic flip. Scalar is to go how fast. For frontflip and backflip.
-pub fn scalar_flip(demo: &mut Demo, start: usize, end: usize, scalar: f32) {
-    scalar_complete_rotation(demo, start, end, scalar, 0);
+pub fn scalar_flip(demo: &mut Demo, start: usize, end: usize, scalar: f32) -> Result<(), String> {
+    scalar_complete_rotation(demo, start, end, scalar, 0)
 }
 
 /// Rotate pitch by around 360 degrees forward from `start` pitch to `end` pitch.
-pub fn front_flip(demo: &mut Demo, start: usize, end: usize) {
-    scalar_flip(demo, start, end, 1.);
+pub fn front_flip(demo: &mut Demo, start: usize, end: usize) -> Result<(), String> {
+    scalar_flip(demo, start, end, 1.)
 }
 
 /// Frontflip but is backflip
-pub fn back_flip(demo: &mut Demo, start: usize, end: usize) {
+pub fn back_flip(demo: &mut Demo, start: usize, end: usize) -> Result<(), String> {
     scalar_flip(demo, start, end, -1.)
 }
 
-pub fn scalar_spin(demo: &mut Demo, start: usize, end: usize, scalar: f32) {
-    scalar_complete_rotation(demo, start, end, scalar, 1);
+pub fn scalar_spin(demo: &mut Demo, start: usize, end: usize, scalar: f32) -> Result<(), String> {
+    scalar_complete_rotation(demo, start, end, scalar, 1)
 }
 
-pub fn spin_left(demo: &mut Demo, start: usize, end: usize) {
-    scalar_spin(demo, start, end, 1.);
+pub fn spin_left(demo: &mut Demo, start: usize, end: usize) -> Result<(), String> {
+    scalar_spin(demo, start, end, 1.)
 }
 
-pub fn spin_right(demo: &mut Demo, start: usize, end: usize) {
+pub fn spin_right(demo: &mut Demo, start: usize, end: usize) -> Result<(), String> {
     scalar_spin(demo, start, end, -1.)
 }
 
 /// Makes the view stopped until `end` while everything goes on normally. This also changes vieworigin.
-pub fn pause_view(demo: &mut Demo, start: usize, end: usize) {
-    for (entry_idx, entry) in demo.directory.entries.iter_mut().enumerate() {
-        if entry_idx == 0 {
-            continue;
-        }
-
-        let mut start_frame_viewangles: Option<[f32; 3]> = None;
-        let mut start_frame_vieworigin: Option<[f32; 3]> = None;
-
-        for (frame_idx, frame) in entry.frames.iter_mut().enumerate() {
-            match &mut frame.data {
-                FrameData::NetMsg((_, data)) => {
-                    if frame_idx < start {
-                        continue;
-                    }
-
-                    if frame_idx >= start && start_frame_viewangles.is_none() {
-                        start_frame_viewangles = Some(data.info.ref_params.viewangles);
-                        start_frame_vieworigin = Some(data.info.ref_params.vieworg);
-                    }
-
-                    if frame_idx >= start && frame_idx < end && start_frame_viewangles.is_some() {
-                        data.info.ref_params.viewangles = start_frame_viewangles.unwrap();
-                        data.info.ref_params.vieworg = start_frame_vieworigin.unwrap();
-                    }
-                }
-                _ => (),
-            }
-        }
-    }
+pub fn pause_view(demo: &mut Demo, start: usize, end: usize) -> Result<(), String> {
+    run_view_fx(demo, ViewFxKind::Pause, start, end)
 }
 
 // Specify how far we search for the frame.
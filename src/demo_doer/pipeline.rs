@@ -0,0 +1,108 @@
+use hldemo::{Frame, NetMsgData};
+
+use super::*;
+
+/// Where the current frame is and what its netmsg was decoded with.
+pub struct PassContext<'c, 'a> {
+    pub entry_idx: usize,
+    pub frame_idx: usize,
+    /// Frames in the current entry.
+    pub frame_count: usize,
+    pub delta_decoders: &'c DeltaDecoderTable,
+    pub custom_messages: &'c HashMap<u8, SvcNewUserMsg<'a>>,
+}
+
+/// One pass over a demo, run by [`Pipeline`].
+///
+/// Every hook has a default so a pass only needs what it cares about. An error stops the run.
+pub trait DemoPass<'a> {
+    /// Every frame, before messages of the frame if it is a netmsg.
+    fn frame(&mut self, _ctx: &PassContext<'_, 'a>, _frame: &mut Frame<'a>) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Decoded messages of a netmsg frame.
+    ///
+    /// Returns whether `messages` is changed so the netmsg is only written when needed.
+    fn messages(
+        &mut self,
+        _ctx: &PassContext<'_, 'a>,
+        _data: &mut NetMsgData<'a>,
+        _messages: &mut Vec<Message<'a>>,
+    ) -> Result<bool, String> {
+        Ok(false)
+    }
+}
+
+/// Runs passes in order with every netmsg parsed once and written once.
+pub struct Pipeline<'p, 'a> {
+    passes: Vec<Box<dyn DemoPass<'a> + 'p>>,
+}
+
+impl<'p, 'a> Pipeline<'p, 'a> {
+    pub fn new() -> Self {
+        Self { passes: vec![] }
+    }
+
+    pub fn add_pass(&mut self, pass: impl DemoPass<'a> + 'p) -> &mut Self {
+        self.passes.push(Box::new(pass));
+        return self;
+    }
+
+    /// Stops at the first netmsg that doesn't parse or the first pass that fails, frames before
+    /// it are already changed.
+    pub fn run(&mut self, demo: &mut Demo<'a>) -> Result<(), String> {
+        let mut delta_decoders = get_initial_delta();
+        let mut custom_messages = HashMap::<u8, SvcNewUserMsg>::new();
+
+        for (entry_idx, entry) in demo.directory.entries.iter_mut().enumerate() {
+            let frame_count = entry.frames.len();
+
+            for (frame_idx, frame) in entry.frames.iter_mut().enumerate() {
+                {
+                    let ctx = PassContext {
+                        entry_idx,
+                        frame_idx,
+                        frame_count,
+                        delta_decoders: &delta_decoders,
+                        custom_messages: &custom_messages,
+                    };
+
+                    for pass in &mut self.passes {
+                        pass.frame(&ctx, frame)?;
+                    }
+                }
+
+                if let FrameData::NetMsg((_, data)) = &mut frame.data {
+                    let mut messages = parse_frame_netmsg(
+                        data.msg,
+                        &mut delta_decoders,
+                        &mut custom_messages,
+                        entry_idx,
+                        frame_idx,
+                    )?;
+
+                    let ctx = PassContext {
+                        entry_idx,
+                        frame_idx,
+                        frame_count,
+                        delta_decoders: &delta_decoders,
+                        custom_messages: &custom_messages,
+                    };
+
+                    let mut is_dirty = false;
+                    for pass in &mut self.passes {
+                        is_dirty |= pass.messages(&ctx, data, &mut messages)?;
+                    }
+
+                    if is_dirty {
+                        let write = write_netmsg(messages, &delta_decoders, &custom_messages);
+                        data.msg = write.leak();
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+use hldemo::Frame;
+
+use crate::utils::{lerp, lerp_angles, lerp_vec};
+
+use super::entity_tracker::EntityTracker;
+use super::*;
+
+/// View of one source netmsg frame, kept around so the next kept frame can interpolate from it.
+#[derive(Clone, Copy)]
+struct View {
+    time: f32,
+    client_time: f32,
+    vieworg: [f32; 3],
+    viewangles: [f32; 3],
+    simorg: [f32; 3],
+    cl_viewangles: [f32; 3],
+}
+
+/// Resamples playback to `fps` frames per second.
+///
+/// Frames are kept at evenly spaced times. `ClientData` and the view part of `ref_params` are
+/// interpolated between the source frames around that time. Frames in between are dropped but
+/// their netmsg messages, events and sounds are moved onto the next kept frame. Because the delta
+/// chain is cut, the next entity update after a dropped one is sent as a full `SvcPacketEntities`.
+///
+/// `fps` has to be above 0, the demo is left alone otherwise. The demo is only half resampled if
+/// a netmsg doesn't parse.
+pub fn resample(demo: &mut Demo, fps: f32) -> Result<(), String> {
+    if !fps.is_finite() || fps <= 0. {
+        return Err(format!("fps {} has to be above 0", fps));
+    }
+
+    let step = 1. / fps;
+
+    let mut delta_decoders = get_initial_delta();
+    let mut custom_messages = HashMap::<u8, SvcNewUserMsg>::new();
+    let mut entities = EntityTracker::new();
+
+    let mut next_slot: Option<f32> = None;
+    let mut frame_number: Option<i32> = None;
+
+    // Last source frames, kept or not.
+    let mut last_client_data: Option<(f32, [f32; 3], [f32; 3])> = None;
+    let mut last_view: Option<View> = None;
+
+    if let Some(loading) = demo.directory.entries.first() {
+        entities.read_loading(loading, &mut delta_decoders, &mut custom_messages)?;
+    }
+
+    for (entry_idx, entry) in demo.directory.entries.iter_mut().enumerate().skip(1) {
+        let frames = take_frames(entry);
+
+        // From dropped frames, waiting for the next kept one.
+        let mut carried_frames: Vec<Frame> = vec![];
+        let mut carried_messages: Vec<Message> = vec![];
+        let mut carried_msec = 0u32;
+        let mut needs_full_update = false;
+
+        // (source frame number, is kept, slot time, frame number)
+        let mut tick: Option<(i32, bool, f32, i32)> = None;
+
+        for (frame_idx, mut frame) in frames.into_iter().enumerate() {
+            if matches!(frame.data, FrameData::DemoStart) {
+                entry.frames.push(frame);
+                continue;
+            }
+
+            // Frames with the same number are one tick.
+            let (_, is_kept, slot, number) = match tick {
+                Some(tick) if tick.0 == frame.frame => tick,
+                _ => {
+                    let slot = *next_slot.get_or_insert(frame.time);
+                    let is_kept = frame.time >= slot;
+                    let mut number = frame_number.unwrap_or(frame.frame);
+
+                    if is_kept {
+                        let mut next = slot + step;
+                        while next <= frame.time {
+                            next += step;
+                        }
+                        next_slot = Some(next);
+
+                        number = frame_number.map_or(frame.frame, |number| number + 1);
+                        frame_number = Some(number);
+
+                        for mut carried in carried_frames.drain(..) {
+                            carried.time = slot;
+                            carried.frame = number;
+                            entry.frames.push(carried);
+                        }
+                    }
+
+                    let new_tick = (frame.frame, is_kept, slot, number);
+                    tick = Some(new_tick);
+                    new_tick
+                }
+            };
+
+            if !is_kept && !matches!(frame.data, FrameData::ClientData(_) | FrameData::NetMsg(_)) {
+                carried_frames.push(frame);
+                continue;
+            }
+
+            match &mut frame.data {
+                FrameData::ClientData(client_data) => {
+                    let source = (frame.time, client_data.origin, client_data.viewangles);
+
+                    if is_kept {
+                        if let Some((prev_time, origin, viewangles)) = last_client_data {
+                            let t = fraction(prev_time, frame.time, slot);
+
+                            client_data.origin = lerp_vec(origin, client_data.origin, t);
+                            client_data.viewangles =
+                                lerp_angles(viewangles, client_data.viewangles, t);
+                        }
+                    }
+
+                    last_client_data = Some(source);
+                }
+                FrameData::NetMsg((_, data)) => {
+                    let mut messages = parse_frame_netmsg(
+                        data.msg,
+                        &mut delta_decoders,
+                        &mut custom_messages,
+                        entry_idx,
+                        frame_idx,
+                    )?;
+                    entities.update(&messages);
+
+                    let ref_params = &mut data.info.ref_params;
+                    let source = View {
+                        time: frame.time,
+                        client_time: ref_params.time,
+                        vieworg: ref_params.vieworg,
+                        viewangles: ref_params.viewangles,
+                        simorg: ref_params.simorg,
+                        cl_viewangles: ref_params.cl_viewangles,
+                    };
+
+                    carried_msec += data.info.usercmd.msec as u32;
+
+                    if !is_kept {
+                        needs_full_update |= messages.iter().any(is_entity_message);
+                        carried_messages
+                            .extend(messages.into_iter().filter(|m| !is_per_frame_message(m)));
+                    } else {
+                        if let Some(prev) = last_view {
+                            let t = fraction(prev.time, frame.time, slot);
+
+                            ref_params.time = lerp(prev.client_time, ref_params.time, t);
+                            ref_params.vieworg = lerp_vec(prev.vieworg, ref_params.vieworg, t);
+                            ref_params.viewangles =
+                                lerp_angles(prev.viewangles, ref_params.viewangles, t);
+                            ref_params.simorg = lerp_vec(prev.simorg, ref_params.simorg, t);
+                            ref_params.cl_viewangles =
+                                lerp_angles(prev.cl_viewangles, ref_params.cl_viewangles, t);
+                        }
+
+                        ref_params.frametime = step;
+                        data.info.usercmd.msec = carried_msec.min(255) as u8;
+                        carried_msec = 0;
+
+                        let is_dirty = needs_full_update || !carried_messages.is_empty();
+
+                        if needs_full_update && entities.make_full_update(&mut messages) {
+                            needs_full_update = false;
+                        }
+
+                        if is_dirty {
+                            let messages: Vec<Message> =
+                                carried_messages.drain(..).chain(messages).collect();
+
+                            let write = write_netmsg(messages, &delta_decoders, &custom_messages);
+                            data.msg = write.leak();
+                        }
+                    }
+
+                    last_view = Some(source);
+                }
+                _ => (),
+            }
+
+            if !is_kept {
+                continue;
+            }
+
+            frame.time = slot;
+            frame.frame = number;
+            entry.frames.push(frame);
+        }
+
+        // Nothing left to put these on so they go at the end.
+        if let Some(last) = entry.frames.last() {
+            let (time, number) = (last.time, last.frame);
+
+            for mut carried in carried_frames {
+                carried.time = time;
+                carried.frame = number;
+                entry.frames.push(carried);
+            }
+        }
+
+        // Same for messages, onto the last kept netmsg.
+        if !carried_messages.is_empty() {
+            let last_netmsg =
+                entry
+                    .frames
+                    .iter_mut()
+                    .enumerate()
+                    .rev()
+                    .find_map(|(frame_idx, frame)| match &mut frame.data {
+                        FrameData::NetMsg((_, data)) => Some((frame_idx, data)),
+                        _ => None,
+                    });
+
+            if let Some((frame_idx, data)) = last_netmsg {
+                let messages = parse_frame_netmsg(
+                    data.msg,
+                    &mut delta_decoders,
+                    &mut custom_messages,
+                    entry_idx,
+                    frame_idx,
+                )?;
+                let messages: Vec<Message> = messages.into_iter().chain(carried_messages).collect();
+
+                let write = write_netmsg(messages, &delta_decoders, &custom_messages);
+                data.msg = write.leak();
+            }
+        }
+
+        if let Some(last) = entry.frames.last() {
+            entry.frame_count = last.frame;
+            entry.track_time = last.time;
+        }
+    }
+
+    Ok(())
+}
+
+/// Messages that only describe the frame they are in and are useless on another frame.
+fn is_per_frame_message(message: &Message) -> bool {
+    is_entity_message(message)
+        || matches!(
+            message,
+            Message::EngineMessage(
+                EngineMessage::SvcNop | EngineMessage::SvcTime(_) | EngineMessage::SvcClientData(_)
+            )
+        )
+}
+
+fn is_entity_message(message: &Message) -> bool {
+    matches!(
+        message,
+        Message::EngineMessage(
+            EngineMessage::SvcPacketEntities(_) | EngineMessage::SvcDeltaPacketEntities(_)
+        )
+    )
+}
+
+/// Where `time` is between `from` and `to`.
+fn fraction(from: f32, to: f32, time: f32) -> f32 {
+    if to > from {
+        ((time - from) / (to - from)).clamp(0., 1.)
+    } else {
+        1.
+    }
+}
@@ -1,432 +1,705 @@
-use std::{fs, io::Write, path::PathBuf};
+use std::{fs, path::Path, rc::Rc};
+
+use crate::nbit_num;
+use hldemo::{Demo, Frame, FrameData, NetMsgData};
+
+use crate::{
+    demo_doer::get_ghost::{
+        demo_players::GhostPlayer,
+        source::{GhostSource, GhostSources},
+        types::GhostInfo,
+        GhostFile,
+    },
+    wrap_message,
+    writer::BitWriter,
+};
+
+use self::model::precache_models;
+use self::nameplate::{nameplate_messages, Nameplate, View, NAMEPLATE_CHANNELS};
+use self::style::GhostStyle;
+
+use super::pipeline::{DemoPass, PassContext, Pipeline};
+use super::*;
 
-use demosuperimpose_goldsrc::nbit_num;
-use hldemo::{Demo, FrameData};
+pub mod model;
+pub mod nameplate;
+pub mod style;
+
+/// What superimposing is up to, for the caller to show. Nothing is printed otherwise.
+pub enum Progress<'p> {
+    /// Reading ghost file `index` out of `count`.
+    Ghost {
+        path: &'p Path,
+        index: usize,
+        count: usize,
+    },
+    /// Working on a frame of the main demo.
+    Frame {
+        entry_idx: usize,
+        frame_idx: usize,
+        frame_count: usize,
+    },
+    /// Something is left out but superimposing goes on.
+    Warning(String),
+}
 
-use crate::{demo_doer::superimpose::get_ghost::get_ghosts, open_demo, writer::BitWriter};
+type ProgressFn = Rc<dyn Fn(Progress)>;
 
-use super::*;
+pub struct SuperimposeOptions {
+    ghosts: Vec<GhostFile>,
+    sources: GhostSources,
+    format: Option<String>,
+    nameplates: bool,
+    progress: ProgressFn,
+}
+
+impl SuperimposeOptions {
+    pub fn new() -> Self {
+        Self {
+            ghosts: vec![],
+            sources: GhostSources::new(),
+            format: None,
+            nameplates: false,
+            progress: Rc::new(|_| ()),
+        }
+    }
+
+    /// `offset` is in seconds, positive starts the ghost later and negative earlier.
+    pub fn add_ghost(&mut self, path: impl AsRef<Path>, offset: f32) -> &mut Self {
+        self.ghosts.push(GhostFile::new(path, offset));
+        return self;
+    }
+
+    /// [`Self::add_ghost`] with a player other than the recorder, the file must be a demo.
+    pub fn add_player(
+        &mut self,
+        path: impl AsRef<Path>,
+        offset: f32,
+        player: GhostPlayer,
+    ) -> &mut Self {
+        self.ghosts
+            .push(GhostFile::with_player(path, offset, player));
+        return self;
+    }
+
+    /// [`Self::add_ghost`] with everything else a ghost file has, like its label.
+    pub fn add_file(&mut self, ghost: GhostFile) -> &mut Self {
+        self.ghosts.push(ghost);
+        return self;
+    }
+
+    /// Every file in the folder as ghost with no offset.
+    pub fn add_folder(&mut self, folder: impl AsRef<Path>) -> Result<&mut Self, String> {
+        let folder = folder.as_ref();
+        let read_err = |err| format!("cannot read {}: {}", folder.display(), err);
+
+        for file in fs::read_dir(folder).map_err(read_err)? {
+            self.add_ghost(file.map_err(read_err)?.path(), 0.);
+        }
+
+        Ok(self)
+    }
+
+    /// Adds a ghost format on top of the ones this crate knows.
+    pub fn add_source(&mut self, source: impl GhostSource + 'static) -> &mut Self {
+        self.sources.add(source);
+        return self;
+    }
+
+    /// Reads every ghost as this format instead of detecting it, like `simen`.
+    pub fn format(&mut self, format: &str) -> &mut Self {
+        self.format = Some(format.to_owned());
+        return self;
+    }
+
+    /// Draws ghost names above their heads, see [`nameplate`] for how many at a time.
+    pub fn nameplates(&mut self) -> &mut Self {
+        self.nameplates = true;
+        return self;
+    }
+
+    /// Called with what is going on, like which ghost is being read.
+    pub fn progress(&mut self, progress: impl Fn(Progress) + 'static) -> &mut Self {
+        self.progress = Rc::new(progress);
+        return self;
+    }
+
+    pub fn get(&self) -> &Self {
+        return self;
+    }
+}
 
-pub fn superimpose_folder<'a>(main: String, folder: String) -> Demo<'a> {
-    let pathbuf = PathBuf::from(folder);
-    let files = fs::read_dir(pathbuf).unwrap();
+/// Opens the main demo then puts the ghosts in.
+pub fn superimpose<'a>(main: &Path, options: &SuperimposeOptions) -> Result<Demo<'a>, String> {
+    let mut main_demo = open_demo_file(main)?;
 
-    let others: Vec<(String, f32)> = files
-        .map(|file| (file.unwrap().path().to_str().unwrap().to_owned(), 0.))
-        .collect();
+    superimpose_demo(&mut main_demo, options)?;
 
-    superimpose(main, others)
+    Ok(main_demo)
 }
 
-pub fn superimpose<'a>(main: String, others: Vec<(String, f32)>) -> Demo<'a> {
-    println!("Total demos: {} + 1", others.len());
+/// [`superimpose`] on an opened demo.
+///
+/// Fails without touching the demo if any ghost cannot be read.
+pub fn superimpose_demo(main_demo: &mut Demo, options: &SuperimposeOptions) -> Result<(), String> {
+    let superimpose = superimpose_pass(options)?;
+
+    Pipeline::new().add_pass(superimpose).run(main_demo)
+}
+
+/// Reads every ghost for a [`Superimpose`] pass, for running it along with other passes.
+pub fn superimpose_pass(options: &SuperimposeOptions) -> Result<Superimpose, String> {
+    let mut ghosts = vec![];
+    for (index, file) in options.ghosts.iter().enumerate() {
+        (options.progress)(Progress::Ghost {
+            path: &file.path,
+            index,
+            count: options.ghosts.len(),
+        });
+
+        let file_ghosts = options
+            .sources
+            .get_ghosts_by_file(std::slice::from_ref(file), options.format.as_deref())?;
+        ghosts.extend(file_ghosts);
+    }
+
+    let mut nameplates = vec![];
+    let mut styles = vec![];
+    let mut models = vec![];
+    for (file, file_ghosts) in options.ghosts.iter().zip(&ghosts) {
+        for ghost in file_ghosts {
+            nameplates.push(Nameplate::new(ghost, file, nameplates.len()));
+            styles.push(file.style.clone());
+            models.push(file.model.clone());
+        }
+    }
 
-    let mut main_demo = open_demo!(main);
-    let mut ghosts = get_ghosts(&others);
-    // New line for our print finally
-    println!("");
+    let mut superimpose = Superimpose::new(ghosts.into_iter().flatten().collect());
+    superimpose
+        .styles(styles)
+        .models(models)
+        .progress(options.progress.clone());
+    if options.nameplates {
+        if nameplates.len() > NAMEPLATE_CHANNELS.len() {
+            (options.progress)(Progress::Warning(format!(
+                "{} ghosts but only the {} closest in view get a nameplate at a time",
+                nameplates.len(),
+                NAMEPLATE_CHANNELS.len()
+            )));
+        }
 
-    let mut delta_decoders = get_initial_delta();
-    let mut custom_messages = HashMap::<u8, SvcNewUserMsg>::new();
+        superimpose.nameplates(nameplates);
+    }
 
-    let mut main_demo_player_delta = Delta::new();
+    Ok(superimpose)
+}
 
+/// Puts ghosts in as entities following the player baseline.
+pub struct Superimpose {
+    ghosts: Vec<GhostInfo>,
+    main_demo_player_delta: Delta,
     // This keeps track of the currently available entity_index for ghost.
-    let mut other_demos_indices: Vec<u16> = vec![];
-
-    // Track the current ghost frame
-    let mut current_frame_index = 0;
-
-    for (entry_idx, entry) in main_demo.directory.entries.iter_mut().enumerate() {
-        let frame_count = entry.frames.len();
-        for (frame_idx, frame) in entry.frames.iter_mut().enumerate() {
-            print!(
-                "\rWorking on entry {} frame {} out of {}   ",
-                entry_idx, frame_idx, frame_count
-            );
-            std::io::stdout().flush().unwrap();
-
-            if let FrameData::NetMsg((_, data)) = &mut frame.data {
-                let (_, mut messages) =
-                    parse_netmsg(data.msg, &mut delta_decoders, &mut custom_messages).unwrap();
-
-                for message in &mut messages {
-                    match message {
-                        Message::EngineMessage(what) => match what {
-                            EngineMessage::SvcSpawnBaseline(baseline) => {
-                                for ghost in &mut ghosts {
-                                    // Find free entities indices.
-                                    let mut current_free_entity = 0;
-                                    let mut insert_idx = 0;
-
-                                    for (idx, entity) in baseline.entities.iter().enumerate() {
-                                        if entity.index.to_u16() == 1 {
-                                            main_demo_player_delta = entity.delta.clone();
-                                        }
-
-                                        if entity.index.to_u16() == current_free_entity
-                                            || other_demos_indices.contains(&current_free_entity)
-                                        {
-                                            current_free_entity += 1;
-                                            insert_idx = idx + 1;
-                                        } else {
-                                            break;
-                                        }
-                                    }
-
-                                    other_demos_indices.push(current_free_entity);
-                                    ghost.set_entity_index(current_free_entity);
-
-                                    // Insert new baseline.
-                                    let other_demo_entity_idx = nbit_num!(current_free_entity, 11);
-                                    let other_demo_type = nbit_num!(1, 2);
-
-                                    let mut other_demo_delta = main_demo_player_delta.clone();
-                                    other_demo_delta.remove("gravity\0");
-                                    other_demo_delta.remove("friction\0");
-                                    other_demo_delta.remove("usehull\0");
-                                    other_demo_delta.remove("spectator\0");
-
-                                    // This modelindex is default model.
-                                    other_demo_delta.insert(
-                                        "modelindex\0".to_string(),
-                                        main_demo_player_delta
-                                            .get("modelindex\0")
-                                            .clone()
-                                            .unwrap()
-                                            .to_vec(),
-                                    );
-
-                                    baseline.entities.insert(
-                                        insert_idx,
-                                        EntityS {
-                                            entity_index: other_demo_entity_idx.to_u16(),
-                                            index: other_demo_entity_idx,
-                                            type_: other_demo_type,
-                                            delta: other_demo_delta,
-                                        },
-                                    );
+    other_demos_indices: Vec<u16>,
+    // Main demo time of the current frame.
+    time: f32,
+    // Main demo time when ghosts start, which is the first packet entities.
+    start_time: Option<f32>,
+    // One for each ghost when there are nameplates.
+    nameplates: Vec<Nameplate>,
+    // Main demo fov from client data.
+    fov: f32,
+    // One for each ghost, or none for all as the main player.
+    styles: Vec<GhostStyle>,
+    // Same, `None` is the main player's model.
+    models: Vec<Option<String>>,
+    // Model index of `models` once the resource list has them.
+    model_indices: Vec<Option<u16>>,
+    progress: ProgressFn,
+}
+
+impl Superimpose {
+    pub fn new(ghosts: Vec<GhostInfo>) -> Self {
+        Self {
+            ghosts,
+            main_demo_player_delta: Delta::new(),
+            other_demos_indices: vec![],
+            time: 0.,
+            start_time: None,
+            nameplates: vec![],
+            fov: 0.,
+            styles: vec![],
+            models: vec![],
+            model_indices: vec![],
+            progress: Rc::new(|_| ()),
+        }
+    }
+
+    /// How each ghost is drawn, in the same order as the ghosts.
+    pub fn styles(&mut self, styles: Vec<GhostStyle>) -> &mut Self {
+        self.styles = styles;
+        return self;
+    }
+
+    /// Draws these above the ghosts, in the same order as the ghosts.
+    pub fn nameplates(&mut self, nameplates: Vec<Nameplate>) -> &mut Self {
+        self.nameplates = nameplates;
+        return self;
+    }
+
+    /// See [`SuperimposeOptions::progress`].
+    pub fn progress(&mut self, progress: Rc<dyn Fn(Progress)>) -> &mut Self {
+        self.progress = progress;
+        return self;
+    }
+
+    /// Player model of each ghost, see [`model::model_path`].
+    pub fn models(&mut self, models: Vec<Option<String>>) -> &mut Self {
+        self.models = models;
+        return self;
+    }
+
+    /// Main player's model index unless the ghost has its own.
+    fn modelindex(&self, ghost_idx: usize) -> Vec<u8> {
+        match self.model_indices.get(ghost_idx) {
+            Some(Some(index)) => (*index as u32).to_le_bytes().to_vec(),
+            _ => self
+                .main_demo_player_delta
+                .get("modelindex\0")
+                .unwrap()
+                .to_vec(),
+        }
+    }
+
+    /// Seconds into the ghosts, starting from the first packet entities.
+    fn ghost_time(&mut self) -> f32 {
+        self.time - *self.start_time.get_or_insert(self.time)
+    }
+}
+
+/// The game only takes 256 entities in one update.
+fn too_many_entities(ctx: &PassContext, entity_count: u16) -> String {
+    format!(
+        "entry {} frame {}: {} entities is over the limit of 256 in one update",
+        ctx.entry_idx, ctx.frame_idx, entity_count
+    )
+}
+
+impl<'a> DemoPass<'a> for Superimpose {
+    fn frame(&mut self, ctx: &PassContext<'_, 'a>, frame: &mut Frame<'a>) -> Result<(), String> {
+        self.time = frame.time;
+
+        if let FrameData::ClientData(client_data) = &frame.data {
+            self.fov = client_data.fov;
+        }
+
+        (self.progress)(Progress::Frame {
+            entry_idx: ctx.entry_idx,
+            frame_idx: ctx.frame_idx,
+            frame_count: ctx.frame_count,
+        });
+
+        Ok(())
+    }
+
+    fn messages(
+        &mut self,
+        ctx: &PassContext<'_, 'a>,
+        data: &mut NetMsgData<'a>,
+        messages: &mut Vec<Message<'a>>,
+    ) -> Result<bool, String> {
+        let mut is_changed = false;
+
+        for message in messages.iter_mut() {
+            match message {
+                Message::EngineMessage(what) => match what {
+                    EngineMessage::SvcResourceList(resource_list) => {
+                        let models: Vec<String> = self.models.iter().flatten().cloned().collect();
+
+                        if !models.is_empty() {
+                            let resource_count = resource_list.resources.len();
+
+                            match precache_models(resource_list, &models) {
+                                Ok(indices) => {
+                                    is_changed |= resource_list.resources.len() != resource_count;
+
+                                    let mut indices = indices.into_iter();
+                                    self.model_indices = self
+                                        .models
+                                        .iter()
+                                        .map(|model| model.as_ref().and_then(|_| indices.next()))
+                                        .collect();
                                 }
+                                Err(err) => (self.progress)(Progress::Warning(format!(
+                                    "{}, ghosts use the player model",
+                                    err
+                                ))),
                             }
-                            EngineMessage::SvcPacketEntities(packet) => {
-                                for what in &packet.entity_states {
-                                    if what.entity_index == 1 {
-                                        if what.delta.get("modelindex\0").is_some() {
-                                            main_demo_player_delta = what.delta.clone();
-                                        }
-                                    }
+                        }
+                    }
+                    EngineMessage::SvcSpawnBaseline(baseline) => {
+                        is_changed |= !self.ghosts.is_empty();
+
+                        for ghost_idx in 0..self.ghosts.len() {
+                            // Find free entities indices.
+                            let mut current_free_entity = 0;
+                            let mut insert_idx = 0;
+
+                            for (idx, entity) in baseline.entities.iter().enumerate() {
+                                if entity.index.to_u16() == 1 {
+                                    self.main_demo_player_delta = entity.delta.clone();
                                 }
 
-                                for ghost in ghosts.iter() {
-                                    // Change count.
-                                    packet.entity_count =
-                                        nbit_num!(packet.entity_count.to_u32() + 1, 16);
-
-                                    if ghost.get_size() <= current_frame_index {
-                                        continue;
-                                    }
-
-                                    let mut other_demo_entity_state_delta = Delta::new();
-
-                                    other_demo_entity_state_delta.insert(
-                                        "origin[0]\0".to_string(),
-                                        ghost.get_frame(current_frame_index).origin[0]
-                                            .to_le_bytes()
-                                            .to_vec(),
-                                    );
-                                    other_demo_entity_state_delta.insert(
-                                        "origin[1]\0".to_string(),
-                                        ghost.get_frame(current_frame_index).origin[1]
-                                            .to_le_bytes()
-                                            .to_vec(),
-                                    );
-                                    other_demo_entity_state_delta.insert(
-                                        "origin[2]\0".to_string(),
-                                        ghost.get_frame(current_frame_index).origin[2]
-                                            .to_le_bytes()
-                                            .to_vec(),
-                                    );
-
-                                    other_demo_entity_state_delta.insert(
-                                        "modelindex\0".to_string(),
-                                        main_demo_player_delta
-                                            .get("modelindex\0")
-                                            .unwrap()
-                                            .to_owned(),
-                                    );
-                                    // other_demo_entity_state_delta.insert(
-                                    //     "framerate\0".to_string(),
-                                    //     0.01f32.to_le_bytes().to_vec(),
-                                    // );
-                                    // other_demo_entity_state_delta.insert(
-                                    //     "controller[0]\0".to_string(),
-                                    //     127u32.to_le_bytes().to_vec(),
-                                    // );
-                                    // other_demo_entity_state_delta
-                                    //     .insert("solid\0".to_string(), 4u32.to_le_bytes().to_vec());
-                                    // other_demo_entity_state_delta.insert(
-                                    //     "movetype\0".to_string(),
-                                    //     7u32.to_le_bytes().to_vec(),
-                                    // );
-
-                                    // Insert entity then change the value for entity index difference correctly.
-                                    let mut insert_index = 0;
-                                    for entity in &packet.entity_states {
-                                        if entity.entity_index > ghost.get_entity_index() {
-                                            break;
-                                        }
-
-                                        insert_index += 1;
-                                    }
-
-                                    // Entity 0 is always there so there is no need to handle weird case where ghost index is 0.
-                                    // Insert between insert entity and ghost entity
-                                    let before_entity = &packet.entity_states[insert_index - 1];
-                                    let mut is_absolute_entity_index = false;
-                                    let mut ghost_absolute_entity_index: Option<BitType> = None;
-                                    let mut ghost_entity_index_difference: Option<BitType> = None;
-
-                                    // If difference is more than 63, we do absolute entity index instead.
-                                    // The reason is that difference is only 6 bits, so 63 max.
-                                    let difference =
-                                        ghost.get_entity_index() - before_entity.entity_index;
-                                    if difference > (1 << 6) - 1 {
-                                        let mut index = BitWriter::new();
-                                        index.append_u32_range(ghost.get_entity_index() as u32, 11);
-
-                                        ghost_absolute_entity_index = Some(index.data.to_owned());
-                                        is_absolute_entity_index = true;
-                                    } else {
-                                        let mut diff = BitWriter::new();
-                                        diff.append_u32_range(
-                                            (ghost.get_entity_index() - before_entity.entity_index)
-                                                as u32,
-                                            6,
-                                        );
-                                        ghost_entity_index_difference = Some(diff.data.to_owned());
-                                    }
-
-                                    let other_demo_entity_state = EntityState {
-                                        entity_index: ghost.get_entity_index(), // This doesn't really do anything but for you to read.
-                                        increment_entity_number: false,
-                                        is_absolute_entity_index: Some(is_absolute_entity_index),
-                                        absolute_entity_index: ghost_absolute_entity_index,
-                                        entity_index_difference: ghost_entity_index_difference,
-                                        has_custom_delta: false,
-                                        has_baseline_index: false,
-                                        baseline_index: None,
-                                        delta: other_demo_entity_state_delta,
-                                    };
-
-                                    // Insert between ghost entity and next entity.
-                                    // If it is last entity then there is no need to change.
-                                    if insert_index < packet.entity_states.len() {
-                                        let next_entity = &mut packet.entity_states[insert_index];
-                                        let difference =
-                                            next_entity.entity_index - ghost.get_entity_index();
-
-                                        if difference > (1 << 6) - 1 {
-                                            // It is possible that by the time this is hit,
-                                            // the next entity is already numbered by absolute index.
-                                        } else {
-                                            let mut next_entity_index_difference = BitWriter::new();
-                                            next_entity_index_difference
-                                                .append_u32_range(difference as u32, 6);
-
-                                            next_entity.entity_index_difference =
-                                                Some(next_entity_index_difference.data);
-                                        }
-                                    }
-
-                                    packet
-                                        .entity_states
-                                        .insert(insert_index, other_demo_entity_state);
+                                if entity.index.to_u16() == current_free_entity
+                                    || self.other_demos_indices.contains(&current_free_entity)
+                                {
+                                    current_free_entity += 1;
+                                    insert_idx = idx + 1;
+                                } else {
+                                    break;
                                 }
+                            }
+
+                            self.other_demos_indices.push(current_free_entity);
+                            self.ghosts[ghost_idx].set_entity_index(current_free_entity);
+
+                            // Insert new baseline.
+                            let other_demo_entity_idx = nbit_num!(current_free_entity, 11);
+                            let other_demo_type = nbit_num!(1, 2);
 
-                                if packet.entity_count.to_u16() >= 256 {
-                                    println!("");
-                                    panic!("Exceeding 256 entities update limit ({} entities). Demo will not work.", packet.entity_count.to_u16())
+                            let mut other_demo_delta = self.main_demo_player_delta.clone();
+                            other_demo_delta.remove("gravity\0");
+                            other_demo_delta.remove("friction\0");
+                            other_demo_delta.remove("usehull\0");
+                            other_demo_delta.remove("spectator\0");
+
+                            // This modelindex is default model unless the ghost has one.
+                            other_demo_delta
+                                .insert("modelindex\0".to_string(), self.modelindex(ghost_idx));
+
+                            if let Some(style) = self.styles.get(ghost_idx) {
+                                style.apply(&mut other_demo_delta);
+                            }
+
+                            baseline.entities.insert(
+                                insert_idx,
+                                EntityS {
+                                    entity_index: other_demo_entity_idx.to_u16(),
+                                    index: other_demo_entity_idx,
+                                    type_: other_demo_type,
+                                    delta: other_demo_delta,
+                                },
+                            );
+                        }
+                    }
+                    EngineMessage::SvcPacketEntities(packet) => {
+                        for what in &packet.entity_states {
+                            if what.entity_index == 1 {
+                                if what.delta.get("modelindex\0").is_some() {
+                                    self.main_demo_player_delta = what.delta.clone();
                                 }
                             }
-                            EngineMessage::SvcDeltaPacketEntities(packet) => {
-                                for ghost in ghosts.iter_mut() {
-                                    // Increment entity count because we have ghost
-                                    // Should increase before the continue line because we don't remove entity.
-                                    packet.entity_count =
-                                        nbit_num!(packet.entity_count.to_u32() + 1, 16);
-
-                                    if ghost.get_size() <= current_frame_index {
-                                        continue;
-                                    }
-
-                                    let mut other_demo_entity_state_delta = Delta::new();
-
-                                    // Origin/viewangles
-                                    other_demo_entity_state_delta.insert(
-                                        "origin[0]\0".to_string(),
-                                        ghost.get_frame(current_frame_index).origin[0]
-                                            .to_le_bytes()
-                                            .to_vec(),
-                                    );
-                                    other_demo_entity_state_delta.insert(
-                                        "origin[1]\0".to_string(),
-                                        ghost.get_frame(current_frame_index).origin[1]
-                                            .to_le_bytes()
-                                            .to_vec(),
-                                    );
-                                    other_demo_entity_state_delta.insert(
-                                        "origin[2]\0".to_string(),
-                                        ghost.get_frame(current_frame_index).origin[2]
-                                            .to_le_bytes()
-                                            .to_vec(),
-                                    );
-                                    other_demo_entity_state_delta.insert(
-                                        "angles[0]\0".to_string(),
-                                        (ghost.get_frame(current_frame_index).viewangles[0] * -1.)
-                                            .to_le_bytes()
-                                            .to_vec(),
-                                    );
-                                    other_demo_entity_state_delta.insert(
-                                        "angles[1]\0".to_string(),
-                                        ghost.get_frame(current_frame_index).viewangles[1]
-                                            .to_le_bytes()
-                                            .to_vec(),
-                                    );
-                                    other_demo_entity_state_delta.insert(
-                                        "angles[2]\0".to_string(),
-                                        ghost.get_frame(current_frame_index).viewangles[2]
-                                            .to_le_bytes()
-                                            .to_vec(),
-                                    );
-
-                                    // Animation
-                                    // Eh, I dont know.
-                                    if let Some(sequence) =
-                                        &ghost.get_frame(current_frame_index).sequence
-                                    {
-                                        other_demo_entity_state_delta
-                                            .insert("sequence\0".to_string(), sequence.to_vec());
-                                        ghost.reset_ghost_anim_frame();
-                                    }
-
-                                    if let Some(_) = ghost.get_frame(current_frame_index).frame {
-                                        // It uses tracked value for frame value.
-                                        other_demo_entity_state_delta.insert(
-                                            "frame\0".to_string(),
-                                            ghost.get_ghost_anim_frame().to_le_bytes().to_vec(),
-                                        );
-                                        ghost.increment_ghost_anim_frame();
-                                    }
-
-                                    if let Some(animtime) =
-                                        &ghost.get_frame(current_frame_index).animtime
-                                    {
-                                        other_demo_entity_state_delta
-                                            .insert("animtime\0".to_string(), animtime.to_vec());
-                                    }
-
-                                    // Insert entity then change the value for entity index difference correctly.
-                                    let mut insert_index = 0;
-                                    for entity in &packet.entity_states {
-                                        if entity.entity_index > ghost.get_entity_index() {
-                                            break;
-                                        }
-
-                                        insert_index += 1;
-                                    }
-
-                                    // If there is no update, subscribing to `entity_states` array would be out of index.
-                                    let other_demo_entity_state = if insert_index > 0 {
-                                        // Insert between insert entity and ghost entity
-                                        let before_entity = &packet.entity_states[insert_index - 1];
-                                        let mut is_absolute_entity_index = false;
-                                        let mut ghost_absolute_entity_index: Option<BitType> = None;
-                                        let mut ghost_entity_index_difference: Option<BitType> =
-                                            None;
-
-                                        // If difference is more than 63, we do absolute entity index instead.
-                                        // The reason is that difference is only 6 bits, so 63 max.
-                                        let difference =
-                                            ghost.get_entity_index() - before_entity.entity_index;
-                                        if difference > (1 << 6) - 1 {
-                                            ghost_absolute_entity_index =
-                                                Some(nbit_num!(ghost.get_entity_index(), 11));
-                                            is_absolute_entity_index = true;
-                                        } else {
-                                            ghost_entity_index_difference = Some(nbit_num!(
-                                                ghost.get_entity_index()
-                                                    - before_entity.entity_index,
-                                                6
-                                            ));
-                                        }
-
-                                        EntityStateDelta {
-                                            entity_index: ghost.get_entity_index(), // This doesn't really do anything but for you to read.
-                                            remove_entity: false,
-                                            is_absolute_entity_index,
-                                            absolute_entity_index: ghost_absolute_entity_index,
-                                            entity_index_difference: ghost_entity_index_difference,
-                                            has_custom_delta: Some(false),
-                                            delta: Some(other_demo_entity_state_delta),
-                                        }
-                                    } else {
-                                        EntityStateDelta {
-                                            entity_index: ghost.get_entity_index(), // This doesn't really do anything but for you to read.
-                                            remove_entity: false,
-                                            is_absolute_entity_index: true,
-                                            absolute_entity_index: Some(nbit_num!(
-                                                ghost.get_entity_index(),
-                                                11
-                                            )),
-                                            entity_index_difference: None,
-                                            has_custom_delta: Some(false),
-                                            delta: Some(other_demo_entity_state_delta),
-                                        }
-                                    };
-
-                                    // Insert between ghost entity and next entity.
-                                    // If it is last entity then there is no need to change.
-                                    if insert_index < packet.entity_states.len() {
-                                        let next_entity = &mut packet.entity_states[insert_index];
-                                        let difference =
-                                            next_entity.entity_index - ghost.get_entity_index();
-
-                                        if difference > (1 << 6) - 1 {
-                                            // It is possible that by the time this is hit,
-                                            // the next entity is already numbered by absolute index.
-                                        } else {
-                                            next_entity.entity_index_difference =
-                                                Some(nbit_num!(difference, 6));
-                                        }
-                                    }
-
-                                    packet
-                                        .entity_states
-                                        .insert(insert_index, other_demo_entity_state);
+                        }
+
+                        let ghost_time = self.ghost_time();
+                        is_changed |= !self.ghosts.is_empty();
+
+                        for (ghost_idx, ghost) in self.ghosts.iter().enumerate() {
+                            // Change count.
+                            packet.entity_count = nbit_num!(packet.entity_count.to_u32() + 1, 16);
+
+                            let ghost_frame = match ghost.get_frame_at(ghost_time) {
+                                Some(ghost_frame) => ghost_frame,
+                                None => continue,
+                            };
+
+                            let mut other_demo_entity_state_delta = Delta::new();
+
+                            other_demo_entity_state_delta.insert(
+                                "origin[0]\0".to_string(),
+                                ghost_frame.origin[0].to_le_bytes().to_vec(),
+                            );
+                            other_demo_entity_state_delta.insert(
+                                "origin[1]\0".to_string(),
+                                ghost_frame.origin[1].to_le_bytes().to_vec(),
+                            );
+                            other_demo_entity_state_delta.insert(
+                                "origin[2]\0".to_string(),
+                                ghost_frame.origin[2].to_le_bytes().to_vec(),
+                            );
+
+                            other_demo_entity_state_delta
+                                .insert("modelindex\0".to_string(), self.modelindex(ghost_idx));
+
+                            if let Some(style) = self.styles.get(ghost_idx) {
+                                style.apply(&mut other_demo_entity_state_delta);
+                            }
+                            // other_demo_entity_state_delta.insert(
+                            //     "framerate\0".to_string(),
+                            //     0.01f32.to_le_bytes().to_vec(),
+                            // );
+                            // other_demo_entity_state_delta.insert(
+                            //     "controller[0]\0".to_string(),
+                            //     127u32.to_le_bytes().to_vec(),
+                            // );
+                            // other_demo_entity_state_delta
+                            //     .insert("solid\0".to_string(), 4u32.to_le_bytes().to_vec());
+                            // other_demo_entity_state_delta.insert(
+                            //     "movetype\0".to_string(),
+                            //     7u32.to_le_bytes().to_vec(),
+                            // );
+
+                            // Insert entity then change the value for entity index difference correctly.
+                            let mut insert_index = 0;
+                            for entity in &packet.entity_states {
+                                if entity.entity_index > ghost.get_entity_index() {
+                                    break;
                                 }
 
-                                // Only increment after we add entity update.
-                                current_frame_index += 1;
+                                insert_index += 1;
+                            }
+
+                            // Entity 0 is always there so there is no need to handle weird case where ghost index is 0.
+                            // Insert between insert entity and ghost entity
+                            let before_entity = &packet.entity_states[insert_index - 1];
+                            let mut is_absolute_entity_index = false;
+                            let mut ghost_absolute_entity_index: Option<BitType> = None;
+                            let mut ghost_entity_index_difference: Option<BitType> = None;
+
+                            // If difference is more than 63, we do absolute entity index instead.
+                            // The reason is that difference is only 6 bits, so 63 max.
+                            let difference = ghost.get_entity_index() - before_entity.entity_index;
+                            if difference > (1 << 6) - 1 {
+                                let mut index = BitWriter::new();
+                                index.append_u32_range(ghost.get_entity_index() as u32, 11);
+
+                                ghost_absolute_entity_index = Some(index.data.to_owned());
+                                is_absolute_entity_index = true;
+                            } else {
+                                let mut diff = BitWriter::new();
+                                diff.append_u32_range(
+                                    (ghost.get_entity_index() - before_entity.entity_index) as u32,
+                                    6,
+                                );
+                                ghost_entity_index_difference = Some(diff.data.to_owned());
+                            }
 
-                                if packet.entity_count.to_u16() >= 256 {
-                                    println!("");
-                                    panic!("Exceeding 256 entities update limit ({} entities). Demo will not work.", packet.entity_count.to_u16())
+                            let other_demo_entity_state = EntityState {
+                                entity_index: ghost.get_entity_index(), // This doesn't really do anything but for you to read.
+                                increment_entity_number: false,
+                                is_absolute_entity_index: Some(is_absolute_entity_index),
+                                absolute_entity_index: ghost_absolute_entity_index,
+                                entity_index_difference: ghost_entity_index_difference,
+                                has_custom_delta: false,
+                                has_baseline_index: false,
+                                baseline_index: None,
+                                delta: other_demo_entity_state_delta,
+                            };
+
+                            // Insert between ghost entity and next entity.
+                            // If it is last entity then there is no need to change.
+                            if insert_index < packet.entity_states.len() {
+                                let next_entity = &mut packet.entity_states[insert_index];
+                                let difference =
+                                    next_entity.entity_index - ghost.get_entity_index();
+
+                                if difference > (1 << 6) - 1 {
+                                    // It is possible that by the time this is hit,
+                                    // the next entity is already numbered by absolute index.
+                                } else {
+                                    let mut next_entity_index_difference = BitWriter::new();
+                                    next_entity_index_difference
+                                        .append_u32_range(difference as u32, 6);
+
+                                    next_entity.entity_index_difference =
+                                        Some(next_entity_index_difference.data);
                                 }
                             }
-                            _ => (),
-                        },
-                        _ => (),
+
+                            packet
+                                .entity_states
+                                .insert(insert_index, other_demo_entity_state);
+                        }
+
+                        if packet.entity_count.to_u16() >= 256 {
+                            return Err(too_many_entities(ctx, packet.entity_count.to_u16()));
+                        }
                     }
-                }
+                    EngineMessage::SvcDeltaPacketEntities(packet) => {
+                        let ghost_time = self.ghost_time();
+                        is_changed |= !self.ghosts.is_empty();
+
+                        for (ghost_idx, ghost) in self.ghosts.iter_mut().enumerate() {
+                            // Increment entity count because we have ghost
+                            // Should increase before the continue line because we don't remove entity.
+                            packet.entity_count = nbit_num!(packet.entity_count.to_u32() + 1, 16);
+
+                            let ghost_frame = match ghost.get_frame_at(ghost_time) {
+                                Some(ghost_frame) => ghost_frame,
+                                None => continue,
+                            };
+
+                            let mut other_demo_entity_state_delta = Delta::new();
+
+                            // Origin/viewangles
+                            other_demo_entity_state_delta.insert(
+                                "origin[0]\0".to_string(),
+                                ghost_frame.origin[0].to_le_bytes().to_vec(),
+                            );
+                            other_demo_entity_state_delta.insert(
+                                "origin[1]\0".to_string(),
+                                ghost_frame.origin[1].to_le_bytes().to_vec(),
+                            );
+                            other_demo_entity_state_delta.insert(
+                                "origin[2]\0".to_string(),
+                                ghost_frame.origin[2].to_le_bytes().to_vec(),
+                            );
+                            other_demo_entity_state_delta.insert(
+                                "angles[0]\0".to_string(),
+                                (ghost_frame.viewangles[0] * -1.).to_le_bytes().to_vec(),
+                            );
+                            other_demo_entity_state_delta.insert(
+                                "angles[1]\0".to_string(),
+                                ghost_frame.viewangles[1].to_le_bytes().to_vec(),
+                            );
+                            other_demo_entity_state_delta.insert(
+                                "angles[2]\0".to_string(),
+                                ghost_frame.viewangles[2].to_le_bytes().to_vec(),
+                            );
+
+                            // Animation
+                            let (sequence, anim_frame) = ghost.animate(ghost_time);
+
+                            if let Some(sequence) = sequence {
+                                other_demo_entity_state_delta
+                                    .insert("sequence\0".to_string(), sequence);
+                            }
+
+                            if let Some(anim_frame) = anim_frame {
+                                other_demo_entity_state_delta.insert(
+                                    "frame\0".to_string(),
+                                    anim_frame.to_le_bytes().to_vec(),
+                                );
+                            }
+
+                            if let Some(animtime) = &ghost_frame.animtime {
+                                other_demo_entity_state_delta
+                                    .insert("animtime\0".to_string(), animtime.to_vec());
+                            }
+
+                            if let Some(style) = self.styles.get(ghost_idx) {
+                                style.apply(&mut other_demo_entity_state_delta);
+                            }
+
+                            // Insert entity then change the value for entity index difference correctly.
+                            let mut insert_index = 0;
+                            for entity in &packet.entity_states {
+                                if entity.entity_index > ghost.get_entity_index() {
+                                    break;
+                                }
+
+                                insert_index += 1;
+                            }
 
-                let write = write_netmsg(messages, &mut delta_decoders, &custom_messages);
-                data.msg = write.leak();
+                            // If there is no update, subscribing to `entity_states` array would be out of index.
+                            let other_demo_entity_state = if insert_index > 0 {
+                                // Insert between insert entity and ghost entity
+                                let before_entity = &packet.entity_states[insert_index - 1];
+                                let mut is_absolute_entity_index = false;
+                                let mut ghost_absolute_entity_index: Option<BitType> = None;
+                                let mut ghost_entity_index_difference: Option<BitType> = None;
+
+                                // If difference is more than 63, we do absolute entity index instead.
+                                // The reason is that difference is only 6 bits, so 63 max.
+                                let difference =
+                                    ghost.get_entity_index() - before_entity.entity_index;
+                                if difference > (1 << 6) - 1 {
+                                    ghost_absolute_entity_index =
+                                        Some(nbit_num!(ghost.get_entity_index(), 11));
+                                    is_absolute_entity_index = true;
+                                } else {
+                                    ghost_entity_index_difference = Some(nbit_num!(
+                                        ghost.get_entity_index() - before_entity.entity_index,
+                                        6
+                                    ));
+                                }
+
+                                EntityStateDelta {
+                                    entity_index: ghost.get_entity_index(), // This doesn't really do anything but for you to read.
+                                    remove_entity: false,
+                                    is_absolute_entity_index,
+                                    absolute_entity_index: ghost_absolute_entity_index,
+                                    entity_index_difference: ghost_entity_index_difference,
+                                    has_custom_delta: Some(false),
+                                    delta: Some(other_demo_entity_state_delta),
+                                }
+                            } else {
+                                EntityStateDelta {
+                                    entity_index: ghost.get_entity_index(), // This doesn't really do anything but for you to read.
+                                    remove_entity: false,
+                                    is_absolute_entity_index: true,
+                                    absolute_entity_index: Some(nbit_num!(
+                                        ghost.get_entity_index(),
+                                        11
+                                    )),
+                                    entity_index_difference: None,
+                                    has_custom_delta: Some(false),
+                                    delta: Some(other_demo_entity_state_delta),
+                                }
+                            };
+
+                            // Insert between ghost entity and next entity.
+                            // If it is last entity then there is no need to change.
+                            if insert_index < packet.entity_states.len() {
+                                let next_entity = &mut packet.entity_states[insert_index];
+                                let difference =
+                                    next_entity.entity_index - ghost.get_entity_index();
+
+                                if difference > (1 << 6) - 1 {
+                                    // It is possible that by the time this is hit,
+                                    // the next entity is already numbered by absolute index.
+                                } else {
+                                    next_entity.entity_index_difference =
+                                        Some(nbit_num!(difference, 6));
+                                }
+                            }
+
+                            packet
+                                .entity_states
+                                .insert(insert_index, other_demo_entity_state);
+                        }
+
+                        if packet.entity_count.to_u16() >= 256 {
+                            return Err(too_many_entities(ctx, packet.entity_count.to_u16()));
+                        }
+                    }
+                    _ => (),
+                },
+                _ => (),
             }
         }
-    }
 
-    // Newline print.
-    println!("");
+        // Nothing to name before the ghosts start.
+        if !self.nameplates.is_empty() && self.start_time.is_some() {
+            let view = View::from_ref_params(&data.info.ref_params, self.fov);
+            let ghost_time = self.ghost_time();
+
+            let ghosts =
+                self.ghosts
+                    .iter()
+                    .zip(&self.nameplates)
+                    .filter_map(|(ghost, nameplate)| {
+                        let ghost_frame = ghost.get_frame_at(ghost_time)?;
+                        let ducking = ghost_frame.movement.ducking.unwrap_or(false);
+
+                        Some((nameplate, ghost_frame.origin, ducking))
+                    });
+
+            for temp_entity in nameplate_messages(&view, ghosts) {
+                messages.push(wrap_message!(SvcTempEntity, temp_entity));
+                is_changed = true;
+            }
+        }
 
-    main_demo
+        Ok(is_changed)
+    }
 }
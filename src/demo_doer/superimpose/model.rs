@@ -0,0 +1,81 @@
+//! Player models for ghosts other than the main player's.
+//!
+//! A model needs a model index from the resource list, so models the demo doesn't already
+//! precache are added at the end. The viewer needs to have the model file.
+
+use crate::netmsg_doer::utils::{bitslice_to_u8_vec, BitSliceCast};
+use crate::types::{Resource, SvcResourceList};
+use crate::utils::ResourceType;
+use crate::{nbit_num, nbit_str};
+
+/// Client only keeps this many models.
+const MAX_MODELS: u32 = 512;
+
+/// A model name like `gign` is `models/player/gign/gign.mdl`, anything with a `/` or `.mdl` is kept.
+pub fn model_path(model: &str) -> String {
+    if model.contains('/') || model.ends_with(".mdl") {
+        model.to_owned()
+    } else {
+        format!("models/player/{0}/{0}.mdl", model)
+    }
+}
+
+fn resource_name(resource: &Resource) -> String {
+    let name = bitslice_to_u8_vec(&resource.name);
+
+    String::from_utf8_lossy(&name)
+        .trim_end_matches('\0')
+        .to_owned()
+}
+
+/// Model index of every model, adding the ones not in the list yet.
+pub fn precache_models(
+    resource_list: &mut SvcResourceList,
+    models: &[String],
+) -> Result<Vec<u16>, String> {
+    let mut indices = vec![];
+
+    for model in models {
+        let path = model_path(model);
+
+        let existing = resource_list.resources.iter().find(|resource| {
+            resource.type_.to_u8() == ResourceType::Model as u8
+                && resource_name(resource).eq_ignore_ascii_case(&path)
+        });
+
+        if let Some(resource) = existing {
+            indices.push(resource.index.to_u16());
+            continue;
+        }
+
+        let index = resource_list
+            .resources
+            .iter()
+            .filter(|resource| resource.type_.to_u8() == ResourceType::Model as u8)
+            .map(|resource| resource.index.to_u32())
+            .max()
+            .unwrap_or(0)
+            + 1;
+
+        if index >= MAX_MODELS {
+            return Err(format!("cannot add {}, no model index left", path));
+        }
+
+        resource_list.resources.push(Resource {
+            type_: nbit_num!(ResourceType::Model, 4),
+            name: nbit_str!(format!("{}\0", path)),
+            index: nbit_num!(index, 12),
+            size: nbit_num!(0, 3 * 8),
+            // Not fatal if missing.
+            flags: nbit_num!(0, 3),
+            md5_hash: None,
+            has_extra_info: false,
+            extra_info: None,
+        });
+        resource_list.resource_count = nbit_num!(resource_list.resources.len(), 12);
+
+        indices.push(index as u16);
+    }
+
+    Ok(indices)
+}
@@ -0,0 +1,215 @@
+//! Ghost names drawn above their heads.
+//!
+//! Every frame each ghost in view is projected onto the screen of the main demo
+//! and gets a `TeTextMessage` there. Text messages on the same channel replace each other
+//! so only the closest [`NAMEPLATE_CHANNELS`] ghosts in view have a nameplate at a time.
+//! The rest get theirs when they are among the closest, [`super::superimpose_pass`] says so
+//! when there are more ghosts than channels.
+
+use hldemo::RefParams;
+
+use crate::demo_doer::get_ghost::{types::GhostInfo, GhostFile};
+use crate::types::{SvcTempEntity, TeTextMessage, TempEntityEntity};
+
+/// Channels 4 and 5 are for kz stats.
+pub const NAMEPLATE_CHANNELS: [i8; 4] = [0, 1, 2, 3];
+
+/// Colours for ghosts without one, in order.
+pub const PALETTE: [[u8; 3]; 8] = [
+    [255, 255, 255],
+    [255, 160, 0],
+    [0, 200, 255],
+    [120, 255, 80],
+    [255, 80, 200],
+    [255, 255, 80],
+    [160, 120, 255],
+    [255, 90, 90],
+];
+
+/// Units above the origin, standing and ducking.
+const STANDING_HEIGHT: f32 = 46.;
+const DUCKING_HEIGHT: f32 = 28.;
+
+/// Roughly how wide a letter is in pixels, to center the text.
+const CHAR_WIDTH: f32 = 8.;
+
+/// A bit longer than a frame so it doesn't blink, in 1/256 seconds.
+const HOLD_TIME: i16 = 26;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Nameplate {
+    pub text: String,
+    pub color: [u8; 3],
+}
+
+impl Nameplate {
+    /// Label of the file or else the ghost name, `index` picks the colour when the file has none.
+    pub fn new(ghost: &GhostInfo, file: &GhostFile, index: usize) -> Self {
+        Self {
+            text: file.label.clone().unwrap_or_else(|| ghost.get_name()),
+            color: file.label_color.unwrap_or(PALETTE[index % PALETTE.len()]),
+        }
+    }
+
+    fn text_message<'a>(&self, channel: i8, x: f32, y: f32) -> SvcTempEntity<'a> {
+        let message = format!("{}\0", self.text);
+        let message = message.leak().as_bytes();
+
+        let color: &'a [u8] = vec![self.color[0], self.color[1], self.color[2], 0].leak();
+
+        let text = TeTextMessage {
+            channel,
+            // (0, 0) is top left
+            x: x.coord_conversion(),
+            y: y.coord_conversion(),
+            effect: 0,
+            text_color: color,
+            effect_color: color,
+            fade_in_time: 0,
+            fade_out_time: 0,
+            hold_time: HOLD_TIME,
+            effect_time: None,
+            message,
+        };
+
+        SvcTempEntity {
+            entity_type: 29,
+            entity: TempEntityEntity::TeTextMessage(text),
+        }
+    }
+}
+
+/// Hex colour like `ff8800`, with or without `#`.
+pub fn parse_color(text: &str) -> Result<[u8; 3], String> {
+    let hex = text.strip_prefix('#').unwrap_or(text);
+
+    if hex.len() != 6 || !hex.is_ascii() {
+        return Err(format!("cannot parse colour {}, expected rrggbb", text));
+    }
+
+    let mut color = [0; 3];
+    for (idx, channel) in color.iter_mut().enumerate() {
+        *channel = u8::from_str_radix(&hex[idx * 2..idx * 2 + 2], 16)
+            .map_err(|_| format!("cannot parse colour {}, expected rrggbb", text))?;
+    }
+
+    Ok(color)
+}
+
+/// Where the main demo looks from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct View {
+    pub origin: [f32; 3],
+    pub forward: [f32; 3],
+    pub right: [f32; 3],
+    pub up: [f32; 3],
+    /// Horizontal, in degrees.
+    pub fov: f32,
+    /// Screen size in pixels.
+    pub width: f32,
+    pub height: f32,
+}
+
+impl View {
+    /// `fov` comes from client data, 0 means the default 90.
+    pub fn from_ref_params(ref_params: &RefParams, fov: f32) -> Self {
+        let [_, _, width, height] = ref_params.viewport;
+        let (width, height) = if width > 0 && height > 0 {
+            (width as f32, height as f32)
+        } else {
+            (640., 480.)
+        };
+
+        Self {
+            origin: ref_params.vieworg,
+            forward: ref_params.forward,
+            right: ref_params.right,
+            up: ref_params.up,
+            fov: if fov > 0. { fov } else { 90. },
+            width,
+            height,
+        }
+    }
+
+    /// Screen position from 0 to 1 with (0, 0) top left, `None` when off screen.
+    pub fn project(&self, point: [f32; 3]) -> Option<[f32; 2]> {
+        let delta = [
+            point[0] - self.origin[0],
+            point[1] - self.origin[1],
+            point[2] - self.origin[2],
+        ];
+
+        let depth = dot(delta, self.forward);
+        if depth <= 0. {
+            return None;
+        }
+
+        let scale = 1. / (self.fov.to_radians() / 2.).tan() / depth;
+        let x = 0.5 + 0.5 * dot(delta, self.right) * scale;
+        let y = 0.5 - 0.5 * dot(delta, self.up) * scale * self.width / self.height;
+
+        if !(0. ..=1.).contains(&x) || !(0. ..=1.).contains(&y) {
+            return None;
+        }
+
+        Some([x, y])
+    }
+
+    fn distance(&self, point: [f32; 3]) -> f32 {
+        (0..3)
+            .map(|i| point[i] - self.origin[i])
+            .fold(0., |acc, num| num * num + acc)
+            .sqrt()
+    }
+}
+
+/// Text messages for the closest ghosts in view, one per channel.
+///
+/// `ghosts` is the nameplate with the ghost origin and whether it is ducking.
+pub fn nameplate_messages<'a, 'b>(
+    view: &View,
+    ghosts: impl IntoIterator<Item = (&'b Nameplate, [f32; 3], bool)>,
+) -> Vec<SvcTempEntity<'a>> {
+    let mut in_view: Vec<(f32, &Nameplate, [f32; 2])> = ghosts
+        .into_iter()
+        .filter_map(|(nameplate, origin, ducking)| {
+            let height = if ducking {
+                DUCKING_HEIGHT
+            } else {
+                STANDING_HEIGHT
+            };
+            let head = [origin[0], origin[1], origin[2] + height];
+
+            view.project(head)
+                .map(|screen| (view.distance(head), nameplate, screen))
+        })
+        .collect();
+
+    in_view.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    in_view
+        .into_iter()
+        .zip(NAMEPLATE_CHANNELS)
+        .map(|((_, nameplate, [x, y]), channel)| {
+            // Left edge of the text, negative would center it on screen instead.
+            let half_width = nameplate.text.chars().count() as f32 * CHAR_WIDTH / 2. / view.width;
+            let x = (x - half_width).max(0.);
+
+            nameplate.text_message(channel, x, y)
+        })
+        .collect()
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+trait CoordConversion {
+    fn coord_conversion(&self) -> i16;
+}
+
+impl CoordConversion for f32 {
+    fn coord_conversion(&self) -> i16 {
+        (self * 8192.).round() as i16
+    }
+}
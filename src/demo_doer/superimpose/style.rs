@@ -0,0 +1,120 @@
+//! How a ghost is drawn, so ghosts stand out from the main player and each other.
+
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::types::Delta;
+
+use super::nameplate::parse_color;
+
+/// `renderfx` that draws a shell of `rendercolor` around the model, `renderamt` thick.
+pub const RENDER_FX_GLOW_SHELL: i32 = 19;
+
+/// Anything not given stays as the main player has it.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GhostStyle {
+    /// 0 normal, 1 color, 2 texture, 3 glow, 4 solid, 5 additive.
+    pub rendermode: Option<i32>,
+    /// 0 to 255, opacity for every mode but normal.
+    pub renderamt: Option<i32>,
+    pub rendercolor: Option<[u8; 3]>,
+    /// Like [`RENDER_FX_GLOW_SHELL`].
+    pub renderfx: Option<i32>,
+    /// 1 is normal size.
+    pub scale: Option<f32>,
+}
+
+impl GhostStyle {
+    /// Errors on values the game doesn't have, they would draw the ghost wrong or not at all.
+    pub fn check(&self) -> Result<(), String> {
+        let in_range = |name: &str, value: Option<i32>, max: i32| match value {
+            Some(value) if !(0..=max).contains(&value) => Err(format!(
+                "{} {} is out of range, expected 0 to {}",
+                name, value, max
+            )),
+            _ => Ok(()),
+        };
+
+        in_range("rendermode", self.rendermode, 5)?;
+        in_range("renderamt", self.renderamt, 255)?;
+        in_range("renderfx", self.renderfx, 20)?;
+
+        if let Some(scale) = self.scale {
+            if !scale.is_finite() || scale <= 0. {
+                return Err(format!("scale {} has to be above 0", scale));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Puts the style into an entity delta.
+    pub fn apply(&self, delta: &mut Delta) {
+        if let Some(rendermode) = self.rendermode {
+            delta.insert("rendermode\0".to_owned(), rendermode.to_le_bytes().to_vec());
+        }
+
+        if let Some(renderamt) = self.renderamt {
+            delta.insert("renderamt\0".to_owned(), renderamt.to_le_bytes().to_vec());
+        }
+
+        if let Some(rendercolor) = self.rendercolor {
+            for (channel, value) in ["r", "g", "b"].iter().zip(rendercolor) {
+                delta.insert(
+                    format!("rendercolor.{}\0", channel),
+                    (value as i32).to_le_bytes().to_vec(),
+                );
+            }
+        }
+
+        if let Some(renderfx) = self.renderfx {
+            delta.insert("renderfx\0".to_owned(), renderfx.to_le_bytes().to_vec());
+        }
+
+        if let Some(scale) = self.scale {
+            delta.insert("scale\0".to_owned(), scale.to_le_bytes().to_vec());
+        }
+    }
+}
+
+/// Comma separated `key=value` like `rendermode=5,renderamt=120,rendercolor=ff8800,renderfx=19,scale=1.2`.
+impl FromStr for GhostStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut style = Self::default();
+
+        for pair in s.split(',').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("cannot parse style {}, expected key=value", pair))?;
+
+            let number = || {
+                value
+                    .parse::<i32>()
+                    .map_err(|err| format!("cannot parse {}: {}", pair, err))
+            };
+
+            match key {
+                "rendermode" => style.rendermode = Some(number()?),
+                "renderamt" => style.renderamt = Some(number()?),
+                "rendercolor" => style.rendercolor = Some(parse_color(value)?),
+                "renderfx" => style.renderfx = Some(number()?),
+                "scale" => {
+                    style.scale = Some(
+                        value
+                            .parse()
+                            .map_err(|err| format!("cannot parse {}: {}", pair, err))?,
+                    )
+                }
+                _ => return Err(format!("unknown style {}", key)),
+            }
+        }
+
+        style.check()?;
+
+        Ok(style)
+    }
+}
@@ -0,0 +1,209 @@
+use crate::nbit_num;
+
+use crate::wrap_message;
+
+use super::*;
+
+/// Playback speed over the range. 1 is normal speed, 0.5 is half speed, 2 is double speed.
+pub enum SpeedCurve {
+    /// Same speed for the whole range.
+    Constant(f32),
+    /// Goes from the first speed at the start to the second speed at the end.
+    Linear(f32, f32),
+    /// Eases from normal speed into `speed` then back out, taking `ramp` seconds at each side.
+    Ramp { speed: f32, ramp: f32 },
+}
+
+impl SpeedCurve {
+    /// Every speed has to be above 0, time can't stop or go backwards.
+    fn check(&self) -> Result<(), String> {
+        let speeds = match *self {
+            SpeedCurve::Constant(speed) => vec![speed],
+            SpeedCurve::Linear(from, to) => vec![from, to],
+            SpeedCurve::Ramp { speed, ramp } => {
+                if !ramp.is_finite() || ramp < 0. {
+                    return Err(format!("ramp {} has to be 0 or more seconds", ramp));
+                }
+
+                vec![speed]
+            }
+        };
+
+        for speed in speeds {
+            if !speed.is_finite() || speed <= 0. {
+                return Err(format!("speed {} has to be above 0", speed));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `t` is seconds into the range.
+    fn speed_at(&self, t: f32, length: f32) -> f32 {
+        let speed = match *self {
+            SpeedCurve::Constant(speed) => speed,
+            SpeedCurve::Linear(from, to) => {
+                let t = if length > 0. { t / length } else { 1. };
+                from + (to - from) * t
+            }
+            SpeedCurve::Ramp { speed, ramp } => {
+                let edge = t.min(length - t).max(0.);
+
+                if ramp <= 0. || edge >= ramp {
+                    speed
+                } else {
+                    // smoothstep
+                    let t = edge / ramp;
+                    let t = t * t * (3. - 2. * t);
+                    1. + (speed - 1.) * t
+                }
+            }
+        };
+
+        speed.max(MIN_SPEED)
+    }
+}
+
+pub struct TimeScaleOptions {
+    curve: SpeedCurve,
+    svc_time_scale: bool,
+    sound_pitch: bool,
+}
+
+impl TimeScaleOptions {
+    pub fn new(curve: SpeedCurve) -> Result<Self, String> {
+        curve.check()?;
+
+        Ok(Self {
+            curve,
+            svc_time_scale: false,
+            sound_pitch: false,
+        })
+    }
+
+    /// Sends `SvcTimeScale` whenever the speed changes and lets the engine slow down instead.
+    ///
+    /// Frame times are then left as they are, otherwise playback would be slowed twice.
+    pub fn add_svc_time_scale(&mut self) -> &mut Self {
+        self.svc_time_scale = true;
+        return self;
+    }
+
+    /// Also changes pitch of sounds played in the range.
+    pub fn scale_sound_pitch(&mut self) -> &mut Self {
+        self.sound_pitch = true;
+        return self;
+    }
+
+    pub fn get(&self) -> &Self {
+        return self;
+    }
+}
+
+// Pitch when `SvcSound` doesn't have one.
+const PITCH_NORM: f32 = 100.;
+// Smallest change of speed worth another `SvcTimeScale`.
+const SPEED_EPSILON: f32 = 0.01;
+// Slowest speed so frame times stay finite.
+const MIN_SPEED: f32 = 0.001;
+
+/// Changes playback speed between `start` and `end` seconds of the original demo.
+///
+/// Everything after the range is pushed back or pulled forward by however much time the range gained or lost.
+/// With [`TimeScaleOptions::add_svc_time_scale`] times stay and the engine changes speed instead.
+pub fn time_scale(demo: &mut Demo, start: f32, end: f32, options: &TimeScaleOptions) {
+    let mut delta_decoders = get_initial_delta();
+    let mut custom_messages = HashMap::<u8, SvcNewUserMsg>::new();
+    let needs_netmsg = options.svc_time_scale || options.sound_pitch;
+
+    let speed_at = |time: f32| {
+        if time < start || time > end {
+            1.
+        } else {
+            options.curve.speed_at(time - start, end - start)
+        }
+    };
+
+    // (original, new) for frame time and client time.
+    let mut frame_clock: Option<(f32, f32)> = None;
+    let mut client_clock: Option<(f32, f32)> = None;
+    let mut last_sent_speed = 1.;
+
+    for (entry_idx, entry) in demo.directory.entries.iter_mut().enumerate() {
+        for frame in &mut entry.frames {
+            if entry_idx == 0 {
+                if let FrameData::NetMsg((_, data)) = &frame.data {
+                    if needs_netmsg {
+                        parse_netmsg(data.msg, &mut delta_decoders, &mut custom_messages).unwrap();
+                    }
+                }
+
+                continue;
+            }
+
+            let (original, new) = frame_clock.unwrap_or((frame.time, frame.time));
+            let speed = speed_at((original + frame.time) / 2.);
+            // Engine does it when it gets `SvcTimeScale`.
+            let time_speed = if options.svc_time_scale { 1. } else { speed };
+
+            let time = new + (frame.time - original) / time_speed;
+            frame_clock = Some((frame.time, time));
+            frame.time = time;
+
+            if let FrameData::NetMsg((_, data)) = &mut frame.data {
+                let client_time = data.info.ref_params.time;
+                let (original, new) = client_clock.unwrap_or((client_time, client_time));
+
+                data.info.ref_params.time = new + (client_time - original) / time_speed;
+                client_clock = Some((client_time, data.info.ref_params.time));
+
+                data.info.ref_params.frametime /= time_speed;
+                data.info.usercmd.msec = (data.info.usercmd.msec as f32 / time_speed)
+                    .round()
+                    .clamp(0., 255.) as u8;
+
+                if !needs_netmsg {
+                    continue;
+                }
+
+                let (_, mut messages) =
+                    parse_netmsg(data.msg, &mut delta_decoders, &mut custom_messages).unwrap();
+                let mut is_dirty = false;
+
+                if options.sound_pitch && speed != 1. {
+                    for message in &mut messages {
+                        if let Message::EngineMessage(EngineMessage::SvcSound(sound)) = message {
+                            let has_pitch = sound.flags.to_u16() & 8 != 0;
+                            let pitch = if has_pitch {
+                                sound.pitch.to_u8() as f32
+                            } else {
+                                PITCH_NORM
+                            };
+
+                            sound.flags = nbit_num!(sound.flags.to_u16() | 8, 9);
+                            sound.pitch = nbit_num!((pitch * speed).round().clamp(1., 255.), 8);
+                            is_dirty = true;
+                        }
+                    }
+                }
+
+                if options.svc_time_scale && (speed - last_sent_speed).abs() > SPEED_EPSILON {
+                    let time_scale = SvcTimeScale { time_scale: speed };
+                    messages.push(wrap_message!(SvcTimeScale, time_scale));
+
+                    last_sent_speed = speed;
+                    is_dirty = true;
+                }
+
+                if is_dirty {
+                    let write = write_netmsg(messages, &delta_decoders, &custom_messages);
+                    data.msg = write.leak();
+                }
+            }
+        }
+
+        if let Some(last) = entry.frames.last() {
+            entry.track_time = last.time;
+        }
+    }
+}
@@ -1,48 +1,21 @@
-use crate::open_demo;
-
 use super::*;
 use std::{fs::File, io::Write, path::Path};
 
-pub fn trenchbroom_player_point(demo: &Demo) {
-    let ghost = get_ghost::demo::demo_ghost_parse("trenchbroom", demo, 0., false);
-    let mut file = File::create("trenchbroom_player_point.txt").unwrap();
+/// Writes player origin of every frame to `output`, one point per line.
+pub fn trenchbroom_player_point(demo: &Demo, output: impl AsRef<Path>) -> Result<(), String> {
+    let output = output.as_ref();
+    let ghost = get_ghost::demo::demo_ghost_parse("trenchbroom", demo, 0., false)?;
+    let write_err = |err| format!("cannot write {}: {}", output.display(), err);
+    let mut file = File::create(output).map_err(write_err)?;
 
     for frame in ghost.frames {
-        match write!(
+        writeln!(
             file,
-            "{} {} {}\n",
+            "{} {} {}",
             frame.origin[0], frame.origin[1], frame.origin[2]
-        ) {
-            Ok(_) => (),
-            Err(err) => {
-                panic!("Cannot write: {}", err)
-            }
-        }
-    }
-}
-
-pub fn trenchbroom_player_point_cli() {
-    use std::env;
-
-    let help = || {
-        println!(
-            "\
-Output file is \"trenchbroom_player_point.txt\"
-
-./binary <path to demo>"
         )
-    };
-
-    let wrap = |demo_file_name: &str| {
-        let demo_file_name = Path::new(demo_file_name);
-        let demo = open_demo!(demo_file_name);
-        trenchbroom_player_point(&demo);
-    };
-
-    let args: Vec<String> = env::args().collect();
-    match args.len() {
-        1 => help(),
-        2 => wrap(&args[1]),
-        _ => help(),
+        .map_err(write_err)?;
     }
+
+    Ok(())
 }
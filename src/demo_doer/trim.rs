@@ -0,0 +1,148 @@
+use super::entity_tracker::EntityTracker;
+use super::*;
+
+/// Cuts the demo down to playback between `start` and `end` seconds.
+///
+/// LOADING entry is left as is. Reliable messages that only change client state are carried over
+/// from dropped frames and the first kept delta update becomes a full `SvcPacketEntities`,
+/// otherwise entities would be missing because the delta chain is cut.
+///
+/// `start` has to be before `end` and `end` within playback. The demo is only half cut if a netmsg
+/// doesn't parse.
+pub fn trim(demo: &mut Demo, start: f32, end: f32) -> Result<(), String> {
+    if start.is_nan() || end.is_nan() || start >= end {
+        return Err(format!("start {} is not before end {}", start, end));
+    }
+
+    let last_time = demo
+        .directory
+        .entries
+        .iter()
+        .skip(1)
+        .flat_map(|entry| entry.frames.iter())
+        .map(|frame| frame.time)
+        .fold(f32::NEG_INFINITY, f32::max);
+
+    if end > last_time {
+        return Err(format!(
+            "{} to {} is not within playback, which ends at {}",
+            start, end, last_time
+        ));
+    }
+
+    let mut delta_decoders = get_initial_delta();
+    let mut custom_messages = HashMap::<u8, SvcNewUserMsg>::new();
+    let mut entities = EntityTracker::new();
+
+    // Still need delta decoders and baselines from here.
+    if let Some(loading) = demo.directory.entries.first() {
+        entities.read_loading(loading, &mut delta_decoders, &mut custom_messages)?;
+    }
+
+    // Messages from dropped frames that still matter after the cut.
+    let mut carried: Vec<Message> = vec![];
+    let mut needs_full_update = true;
+
+    // Set on the first frame in range.
+    let mut offset: Option<(f32, i32)> = None;
+
+    let mut kept_entries: Vec<bool> = vec![true];
+
+    for (entry_idx, entry) in demo.directory.entries.iter_mut().enumerate().skip(1) {
+        let mut has_playback = false;
+
+        for (frame_idx, mut frame) in take_frames(entry).into_iter().enumerate() {
+            let in_range = frame.time >= start && frame.time <= end;
+
+            if in_range && offset.is_none() {
+                offset = Some((frame.time, frame.frame));
+            }
+
+            if matches!(frame.data, FrameData::DemoStart) {
+                frame.time = 0.;
+                frame.frame = 0;
+                entry.frames.push(frame);
+                continue;
+            }
+
+            if let FrameData::NetMsg((_, data)) = &mut frame.data {
+                let is_dirty = needs_full_update || !carried.is_empty();
+
+                if is_dirty {
+                    let mut messages = parse_frame_netmsg(
+                        data.msg,
+                        &mut delta_decoders,
+                        &mut custom_messages,
+                        entry_idx,
+                        frame_idx,
+                    )?;
+                    entities.update(&messages);
+
+                    if !in_range {
+                        carried.extend(messages.into_iter().filter(is_carried_message));
+                    } else {
+                        if needs_full_update && entities.make_full_update(&mut messages) {
+                            needs_full_update = false;
+                        }
+
+                        let messages: Vec<Message> = carried.drain(..).chain(messages).collect();
+
+                        let write = write_netmsg(messages, &delta_decoders, &custom_messages);
+                        data.msg = write.leak();
+                    }
+                }
+            }
+
+            let (time_offset, frame_offset) = match offset {
+                Some(offset) if in_range => offset,
+                _ => continue,
+            };
+
+            if let FrameData::NetMsg((_, data)) = &mut frame.data {
+                data.info.ref_params.time -= time_offset;
+            }
+
+            frame.time -= time_offset;
+            frame.frame -= frame_offset;
+            entry.frames.push(frame);
+            has_playback = true;
+        }
+
+        if let Some(last) = entry.frames.last() {
+            entry.frame_count = last.frame;
+            entry.track_time = last.time;
+        }
+
+        kept_entries.push(has_playback);
+    }
+
+    if offset.is_none() {
+        return Err(format!("no frames between {} and {}", start, end));
+    }
+
+    let mut kept_entries = kept_entries.into_iter();
+    demo.directory
+        .entries
+        .retain(|_| kept_entries.next().unwrap_or(false));
+
+    Ok(())
+}
+
+/// Reliable messages that only set up client state and are fine to send late.
+fn is_carried_message(message: &Message) -> bool {
+    matches!(
+        message,
+        Message::EngineMessage(
+            EngineMessage::SvcLightStyle(_)
+                | EngineMessage::SvcUpdateUserInfo(_)
+                | EngineMessage::SvcDeltaDescription(_)
+                | EngineMessage::SvcNewUserMsg(_)
+                | EngineMessage::SvcSetView(_)
+                | EngineMessage::SvcCdTrack(_)
+                | EngineMessage::SvcRoomType(_)
+                | EngineMessage::SvcNewMovevars(_)
+                | EngineMessage::SvcTimeScale(_)
+                | EngineMessage::SvcResourceLocation(_)
+        )
+    )
+}
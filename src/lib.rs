@@ -1,3 +1,4 @@
+pub mod demo_doer;
 pub mod netmsg_doer;
 pub mod types;
 pub mod utils;
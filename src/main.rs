@@ -1,80 +1,459 @@
 extern crate hldemo;
 
-use std::io::Read;
-use std::{fs::File, path::Path};
-
-use demo_doer::{
-    add_debug, example, ghost_to_demo,
-    kz_stats::{self, add_kz_stats, KzAddOns},
-    netmsg_rewrite_test,
-    superimpose::{self, superimpose},
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+
+use demosuperimpose_goldsrc::demo_doer::{
+    dump::{dump_file, DumpOptions},
+    get_ghost::{demo_players::GhostPlayer, source::GhostSources, GhostFile},
+    ghost_to_demo::ghost_to_demo,
+    info::info,
+    job::Job,
+    kz_stats::{add_kz_stats, KzAddOns},
+    offset_viewangles::{view_fx, ViewFx},
+    open_demo_file,
+    remove_entities::remove_entities,
+    superimpose::{self, nameplate::parse_color, style::GhostStyle, Progress},
+    trenchbroom_player_point, write_demo_file,
 };
 
-use crate::demo_doer::ghost_to_demo::ghost_to_demo;
-
-mod demo_doer;
-mod types;
-mod utils;
-mod writer;
-
-fn main() {
-    ghost_to_demo_cli();
-    // // let mut demo = open_demo!("example/rvp.dem");
-    // // let mut demo = open_demo!("out.dem");
-    // // example::print_netmsg(&mut demo);
-    // // example::change_map_checksum(&mut demo);
-    // // example::rawe(&mut demo);
-    // // example::remove_netmsg_info(&mut demo);
-    // // example::remove_removable_messages(&mut demo);
-    // let mut demo = ghost_to_demo::ghost_to_demo(
-    //     Path::new("example/rvp.rj.json"),
-    //     Path::new("example/rvp_tundra-bhop.bsp"),
-    // );
-    // // let demo = superimpose::superimpose(
-    // //     "example/gold.dem".to_string(),
-    // //     vec![
-    // //         ("example/gold2.dem".to_owned(), 0.),
-    // //         ("example/gold3.dem".to_owned(), 0.),
-    // //         ("example/gold4.dem".to_owned(), 0.),
-    // //     ],
-    // // );
-    // // kz_stats::add_kz_stats(
-    // //     &mut demo,
-    // //     KzAddOns::new().add_speedometer().add_keys().get(),
-    // // );
-    // // offset_viewangles::front_flip(&mut demo, 230, 350);
-    // // offset_viewangles::back_flip(&mut demo, 500, 750);
-    // // add_debug::add_debug(&mut demo);
-    // // example::print_netmsg(&mut demo);
-    // // offset_viewangles::back_flip(&mut demo, 230, 350);
-    // // ghost_to_demo::bsp::get_map_info("example/rvp_tundra-bhop.bsp");
-    // // example::netmsg_parse_write_parse(&mut demo);
-    // write_demo!("out.dem", demo);
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Puts other demos into a demo as ghosts.
+    Superimpose {
+        /// Demo to put the ghosts in.
+        main: PathBuf,
+        /// Ghost files.
+        ghosts: Vec<PathBuf>,
+        /// Every file in this folder is a ghost.
+        #[arg(long)]
+        folder: Option<PathBuf>,
+        /// Offset in seconds of each ghost, in the same order as the ghosts.
+        #[arg(long, allow_negative_numbers = true)]
+        offset: Vec<f32>,
+        /// Reads every ghost as this format instead of detecting it, like `simen`.
+        #[arg(long)]
+        format: Option<String>,
+        /// Player of each demo ghost, in the same order as the ghosts.
+        /// `recorder` (default), `all`, an entity index or a name.
+        #[arg(long)]
+        player: Vec<GhostPlayer>,
+        /// Draws ghost names above their heads.
+        /// Only the 4 closest ghosts in view have one at a time, the game has no more text channels.
+        #[arg(long)]
+        nameplates: bool,
+        /// Nameplate text of each ghost instead of its name, in the same order as the ghosts.
+        #[arg(long)]
+        label: Vec<String>,
+        /// Nameplate colour of each ghost like `ff8800`, in the same order as the ghosts.
+        #[arg(long, value_parser = parse_color)]
+        label_color: Vec<[u8; 3]>,
+        /// Look of each ghost, in the same order as the ghosts.
+        /// Like `rendermode=5,renderamt=120,rendercolor=ff8800,renderfx=19,scale=1.2`.
+        #[arg(long)]
+        style: Vec<GhostStyle>,
+        /// Player model of each ghost like `gign` or `models/ghost.mdl`, in the same order as the ghosts.
+        #[arg(long)]
+        model: Vec<String>,
+        #[arg(short, long, default_value = "out.dem")]
+        output: PathBuf,
+    },
+    /// Makes a demo out of a ghost file.
+    GhostToDemo {
+        ghost: PathBuf,
+        map: PathBuf,
+        /// Reads the ghost as this format instead of detecting it, like `simen`.
+        #[arg(long)]
+        format: Option<String>,
+        /// Defaults to the ghost file name with `.dem`.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Writes a ghost as another ghost format.
+    Convert {
+        ghost: PathBuf,
+        /// Format is picked by the ending, like `.sg.json`, unless `--to` is given.
+        output: PathBuf,
+        /// Reads the ghost as this format instead of detecting it, like `simen`.
+        #[arg(long)]
+        format: Option<String>,
+        /// Writes as this format.
+        #[arg(long)]
+        to: Option<String>,
+        /// Player of a demo ghost, `recorder`, an entity index or a name.
+        #[arg(long, default_value = "recorder")]
+        player: GhostPlayer,
+    },
+    /// Adds speedometer and pressed keys.
+    KzStats {
+        demo: PathBuf,
+        #[arg(long)]
+        keys: bool,
+        #[arg(long)]
+        speedometer: bool,
+        #[arg(short, long, default_value = "out.dem")]
+        output: PathBuf,
+    },
+    /// Removes entities by their index.
+    RemoveEntities {
+        demo: PathBuf,
+        #[arg(required = true)]
+        entities: Vec<u16>,
+        #[arg(short, long, default_value = "out.dem")]
+        output: PathBuf,
+    },
+    /// Plays with view angles over a frame range.
+    ViewFx {
+        demo: PathBuf,
+        effect: ViewFx,
+        /// First frame.
+        start: usize,
+        /// Last frame.
+        end: usize,
+        #[arg(short, long, default_value = "out.dem")]
+        output: PathBuf,
+    },
+    /// Prints every frame and every message.
+    Dump {
+        demo: PathBuf,
+        /// One JSON object per frame instead of text.
+        #[arg(long, conflicts_with = "bits")]
+        ndjson: bool,
+        /// Annotated hexdump of where every bit of netmsg went.
+        #[arg(long)]
+        bits: bool,
+        /// Only these messages, like `SvcSound`.
+        #[arg(long)]
+        message: Vec<String>,
+        /// Only these entities.
+        #[arg(long)]
+        entity: Vec<u16>,
+        /// From this time in seconds.
+        #[arg(long)]
+        start: Option<f32>,
+        /// Until this time in seconds.
+        #[arg(long)]
+        end: Option<f32>,
+    },
+    /// Writes player origin of every frame for TrenchBroom.
+    TrenchbroomPoints {
+        demo: PathBuf,
+        #[arg(short, long, default_value = "trenchbroom_player_point.txt")]
+        output: PathBuf,
+    },
+    /// Prints what is in the demo.
+    Info {
+        demo: PathBuf,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Runs a TOML or JSON job file.
+    Job { job: PathBuf },
 }
 
-fn ghost_to_demo_cli() {
-    use std::env;
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli.command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(command: Command) -> Result<(), String> {
+    match command {
+        Command::Superimpose {
+            main,
+            ghosts,
+            folder,
+            offset,
+            format,
+            player,
+            nameplates,
+            label,
+            label_color,
+            style,
+            model,
+            output,
+        } => {
+            check_file(&main)?;
+
+            if ghosts.is_empty() && folder.is_none() {
+                return Err("no ghosts, give some ghost files or --folder".to_owned());
+            }
+
+            if offset.len() > ghosts.len() {
+                return Err(format!(
+                    "{} offsets for {} ghosts",
+                    offset.len(),
+                    ghosts.len()
+                ));
+            }
+
+            if player.len() > ghosts.len() {
+                return Err(format!(
+                    "{} players for {} ghosts",
+                    player.len(),
+                    ghosts.len()
+                ));
+            }
+
+            if label.len() > ghosts.len() || label_color.len() > ghosts.len() {
+                return Err(format!(
+                    "{} labels and {} label colours for {} ghosts",
+                    label.len(),
+                    label_color.len(),
+                    ghosts.len()
+                ));
+            }
+
+            if style.len() > ghosts.len() {
+                return Err(format!(
+                    "{} styles for {} ghosts",
+                    style.len(),
+                    ghosts.len()
+                ));
+            }
+
+            if model.len() > ghosts.len() {
+                return Err(format!(
+                    "{} models for {} ghosts",
+                    model.len(),
+                    ghosts.len()
+                ));
+            }
+
+            let mut options = superimpose::SuperimposeOptions::new();
+
+            for (ghost_idx, ghost) in ghosts.iter().enumerate() {
+                check_file(ghost)?;
+
+                let mut file = GhostFile::with_player(
+                    ghost,
+                    offset.get(ghost_idx).cloned().unwrap_or(0.),
+                    player.get(ghost_idx).cloned().unwrap_or_default(),
+                );
+                file.label = label.get(ghost_idx).cloned();
+                file.label_color = label_color.get(ghost_idx).cloned();
+                file.style = style.get(ghost_idx).cloned().unwrap_or_default();
+                file.model = model.get(ghost_idx).cloned();
+
+                options.add_file(file);
+            }
+
+            if let Some(folder) = folder {
+                if !folder.is_dir() {
+                    return Err(format!("{} is not a folder", folder.display()));
+                }
+
+                options.add_folder(folder)?;
+            }
+
+            if let Some(format) = &format {
+                options.format(format);
+            }
+
+            if nameplates {
+                options.nameplates();
+            }
 
-    let help = || println!("./binary <path to ghost> <path to map>");
+            options.progress(print_progress);
 
-    let wrap = |ghost_file_name: &str, map_file_name: &str| {
-        let ghost_file_name = Path::new(ghost_file_name);
-        let map_file_name = Path::new(map_file_name);
-        let demo = ghost_to_demo(ghost_file_name, map_file_name);
+            let demo = superimpose::superimpose(&main, options.get());
+            // Progress stays on one line until here.
+            println!();
+            write_demo_file(demo?, output)?;
+        }
+        Command::GhostToDemo {
+            ghost,
+            map,
+            format,
+            output,
+        } => {
+            check_file(&ghost)?;
+            check_file(&map)?;
 
-        let output = format!(
-            "{}.dem",
-            ghost_file_name.file_stem().unwrap().to_str().unwrap()
-        );
+            let output = output.unwrap_or_else(|| ghost.with_extension("dem"));
+            let demo = ghost_to_demo(&ghost, &map, format.as_deref())?;
+            write_demo_file(demo, output)?;
+        }
+        Command::Convert {
+            ghost,
+            output,
+            format,
+            to,
+            player,
+        } => {
+            check_file(&ghost)?;
 
-        write_demo!(output, demo);
-    };
+            let sources = GhostSources::new();
+            // Before parsing so a wrong output doesn't wait for it.
+            let writer = sources.output(&output, to.as_deref())?;
+
+            let ghosts = sources
+                .source(&ghost, format.as_deref())?
+                .parse_player(&ghost, 0., &player)?;
+
+            if ghosts.len() != 1 {
+                return Err(format!(
+                    "{} has {} players, pick one with --player",
+                    ghost.display(),
+                    ghosts.len()
+                ));
+            }
+
+            writer.write(&ghosts[0], &output)?;
+        }
+        Command::KzStats {
+            demo,
+            keys,
+            speedometer,
+            output,
+        } => {
+            check_file(&demo)?;
+
+            if !keys && !speedometer {
+                return Err("nothing to add, give --keys and/or --speedometer".to_owned());
+            }
+
+            let mut addons = KzAddOns::new();
+            if keys {
+                addons.add_keys();
+            }
+            if speedometer {
+                addons.add_speedometer();
+            }
+
+            let mut demo = open_demo_file(demo)?;
+            add_kz_stats(&mut demo, addons.get())?;
+            write_demo_file(demo, output)?;
+        }
+        Command::RemoveEntities {
+            demo,
+            entities,
+            output,
+        } => {
+            check_file(&demo)?;
+
+            let mut demo = open_demo_file(demo)?;
+            remove_entities(&mut demo, entities)?;
+            write_demo_file(demo, output)?;
+        }
+        Command::ViewFx {
+            demo,
+            effect,
+            start,
+            end,
+            output,
+        } => {
+            check_file(&demo)?;
+
+            if start >= end {
+                return Err(format!(
+                    "start frame {} is not before end frame {}",
+                    start, end
+                ));
+            }
+
+            let mut demo = open_demo_file(demo)?;
+            view_fx(&mut demo, effect, start, end)?;
+            write_demo_file(demo, output)?;
+        }
+        Command::Dump {
+            demo,
+            ndjson,
+            bits,
+            message,
+            entity,
+            start,
+            end,
+        } => {
+            check_file(&demo)?;
+
+            let mut options = DumpOptions::new();
+            if ndjson {
+                options.ndjson();
+            }
+            if bits {
+                options.bits();
+            }
+            for message in &message {
+                options.add_message(message);
+            }
+            for entity in entity {
+                options.add_entity(entity);
+            }
+            options.time_range(start, end);
+
+            dump_file(demo, options.get())?;
+        }
+        Command::TrenchbroomPoints { demo, output } => {
+            check_file(&demo)?;
+
+            let demo = open_demo_file(demo)?;
+            trenchbroom_player_point::trenchbroom_player_point(&demo, output)?;
+        }
+        Command::Job { job } => {
+            check_file(&job)?;
+
+            let res = Job::open(&job)?.run(print_progress);
+            println!();
+            res?;
+        }
+        Command::Info { demo, json } => {
+            check_file(&demo)?;
+
+            let demo = open_demo_file(demo)?;
+            let info = info(&demo);
+
+            if json {
+                println!("{}", info.to_json());
+            } else {
+                print!("{}", info);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Progress on one line that keeps being overwritten, warnings on their own lines.
+fn print_progress(progress: Progress) {
+    match progress {
+        Progress::Ghost { path, index, count } => {
+            print!("\rParsing {} ({}/{})    ", path.display(), index + 1, count)
+        }
+        Progress::Frame {
+            entry_idx,
+            frame_idx,
+            frame_count,
+        } => print!(
+            "\rWorking on entry {} frame {} out of {}   ",
+            entry_idx, frame_idx, frame_count
+        ),
+        Progress::Warning(warning) => print!("\n{}\n", warning),
+    }
+
+    std::io::stdout().flush().unwrap();
+}
 
-    let args: Vec<String> = env::args().collect();
-    match args.len() {
-        1 => help(),
-        2 => help(),
-        3 => wrap(&args[1], &args[2]),
-        _ => help(),
+fn check_file(path: &Path) -> Result<(), String> {
+    if path.is_file() {
+        Ok(())
+    } else {
+        Err(format!("cannot find {}", path.display()))
     }
 }
@@ -15,13 +15,13 @@ impl<'a> NetMsgDoerWithDelta<'a, SvcClientData> for ClientData {
             None
         };
 
-        let client_data = parse_delta(delta_decoders.get("clientdata_t\0").unwrap(), &mut br);
+        let client_data = parse_delta(i, delta_decoders.get("clientdata_t\0").unwrap(), &mut br)?;
 
         // This is a vector unlike THE docs.
         let mut weapon_data: Vec<ClientDataWeaponData> = vec![];
         while br.read_1_bit() {
             let weapon_index = br.read_n_bit(6).to_owned();
-            let delta = parse_delta(delta_decoders.get("weapon_data_t\0").unwrap(), &mut br);
+            let delta = parse_delta(i, delta_decoders.get("weapon_data_t\0").unwrap(), &mut br)?;
 
             weapon_data.push(ClientDataWeaponData {
                 weapon_index,
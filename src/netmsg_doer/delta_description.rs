@@ -17,14 +17,15 @@ impl<'a> NetMsgDoerWithDelta<'a, SvcDeltaDescription<'a>> for DeltaDescription {
         // So this would be the only message that modifies the delta decode table.
 
         let mut br = BitReader::new(i);
+        let delta_description = delta_decoders.get("delta_description_t\0").unwrap();
         let data: Vec<Delta> = (0..total_fields)
-            .map(|_| {
-                parse_delta(
-                    delta_decoders.get("delta_description_t\0").unwrap(),
-                    &mut br,
-                )
-            })
-            .collect();
+            .map(|_| parse_delta(i, delta_description, &mut br))
+            .collect::<Result<_, _>>()?;
+
+        // A cut off message fails here, before the fields below are unwrapped.
+        let range = br.get_consumed_bytes();
+        let (i, _) = take(range)(i)?;
+        let clone = &clone[..range];
 
         let decoder: DeltaDecoder = data
             .iter()
@@ -44,10 +45,6 @@ impl<'a> NetMsgDoerWithDelta<'a, SvcDeltaDescription<'a>> for DeltaDescription {
             })
             .collect();
 
-        let range = br.get_consumed_bytes();
-        let clone = &clone[..range];
-        let (i, _) = take(range)(i)?;
-
         // It really should mutate the delta decoder table here but we're respecting ownership.
         Ok((
             i,
@@ -12,8 +12,8 @@ impl<'a> NetMsgDoerWithExtraInfo<'a, SvcDeltaPacketEntities> for DeltaPacketEnti
     ) -> IResult<&'a [u8], SvcDeltaPacketEntities> {
         let mut br = BitReader::new(i);
 
-        let entity_count = br.read_n_bit(16).to_owned();
-        let delta_sequence = br.read_n_bit(8).to_owned();
+        let entity_count = br.name("entity_count").read_n_bit(16).to_owned();
+        let delta_sequence = br.name("delta_sequence").read_n_bit(8).to_owned();
 
         let mut entity_index: u16 = 0;
         let mut entity_states: Vec<EntityStateDelta> = vec![];
@@ -21,19 +21,19 @@ impl<'a> NetMsgDoerWithExtraInfo<'a, SvcDeltaPacketEntities> for DeltaPacketEnti
         loop {
             let footer = br.peek_n_bits(16).to_u16();
             if footer == 0 {
-                br.read_n_bit(16);
+                br.name("footer").read_n_bit(16);
                 break;
             }
 
-            let remove_entity = br.read_1_bit();
-            let is_absolute_entity_index = br.read_1_bit();
+            let remove_entity = br.name("remove_entity").read_1_bit();
+            let is_absolute_entity_index = br.name("is_absolute_entity_index").read_1_bit();
 
             let (absolute_entity_index, entity_index_difference) = if is_absolute_entity_index {
-                let idx = br.read_n_bit(11).to_owned();
+                let idx = br.name("absolute_entity_index").read_n_bit(11).to_owned();
                 entity_index = idx.to_u16();
                 (Some(idx), None)
             } else {
-                let diff = br.read_n_bit(6).to_owned();
+                let diff = br.name("entity_index_difference").read_n_bit(6).to_owned();
                 entity_index += diff.to_u16();
                 (None, Some(diff))
             };
@@ -51,22 +51,24 @@ impl<'a> NetMsgDoerWithExtraInfo<'a, SvcDeltaPacketEntities> for DeltaPacketEnti
                 continue;
             }
 
-            let has_custom_delta = br.read_1_bit();
+            let has_custom_delta = br.name("has_custom_delta").read_1_bit();
             let between = entity_index > 0 && entity_index <= max_client as u16;
 
             let delta = if between {
                 parse_delta(
+                    i,
                     delta_decoders.get("entity_state_player_t\0").unwrap(),
                     &mut br,
-                )
+                )?
             } else {
                 if has_custom_delta {
                     parse_delta(
+                        i,
                         delta_decoders.get("custom_entity_state_t\0").unwrap(),
                         &mut br,
-                    )
+                    )?
                 } else {
-                    parse_delta(delta_decoders.get("entity_state_t\0").unwrap(), &mut br)
+                    parse_delta(i, delta_decoders.get("entity_state_t\0").unwrap(), &mut br)?
                 }
             };
 
@@ -10,46 +10,46 @@ impl<'a> NetMsgDoerWithDelta<'a, SvcEvent> for Event {
 
         let event_count = br.read_n_bit(5).to_owned();
 
-        let events = (0..event_count.to_u8())
-            .map(|_| {
-                let event_index = br.read_n_bit(10).to_owned();
-                let has_packet_index = br.read_1_bit();
-                let packet_index = if has_packet_index {
-                    Some(br.read_n_bit(11).to_owned())
-                } else {
-                    None
-                };
-                let has_delta = if has_packet_index {
-                    Some(br.read_1_bit())
-                } else {
-                    None
-                };
-                let delta = if has_delta.is_some() && has_delta.unwrap() {
-                    Some(parse_delta(
-                        delta_decoders.get("event_t\0").unwrap(),
-                        &mut br,
-                    ))
-                } else {
-                    None
-                };
-                let has_fire_time = br.read_1_bit();
-                let fire_time = if has_fire_time {
-                    Some(br.read_n_bit(16).to_owned())
-                } else {
-                    None
-                };
+        let mut events = vec![];
+        for _ in 0..event_count.to_u8() {
+            let event_index = br.read_n_bit(10).to_owned();
+            let has_packet_index = br.read_1_bit();
+            let packet_index = if has_packet_index {
+                Some(br.read_n_bit(11).to_owned())
+            } else {
+                None
+            };
+            let has_delta = if has_packet_index {
+                Some(br.read_1_bit())
+            } else {
+                None
+            };
+            let delta = if has_delta.is_some() && has_delta.unwrap() {
+                Some(parse_delta(
+                    i,
+                    delta_decoders.get("event_t\0").unwrap(),
+                    &mut br,
+                )?)
+            } else {
+                None
+            };
+            let has_fire_time = br.read_1_bit();
+            let fire_time = if has_fire_time {
+                Some(br.read_n_bit(16).to_owned())
+            } else {
+                None
+            };
 
-                EventS {
-                    event_index,
-                    has_packet_index,
-                    packet_index,
-                    has_delta,
-                    delta,
-                    has_fire_time,
-                    fire_time,
-                }
-            })
-            .collect();
+            events.push(EventS {
+                event_index,
+                has_packet_index,
+                packet_index,
+                has_delta,
+                delta,
+                has_fire_time,
+                fire_time,
+            });
+        }
 
         let range = br.get_consumed_bytes();
         let (i, _) = take(range)(i)?;
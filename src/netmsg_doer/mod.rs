@@ -167,7 +167,8 @@ macro_rules! wrap_parse {
 // If there is any design change then Message type is wrapped again in another type that can carry extra info.
 static mut MAX_CLIENT: u8 = 0;
 
-fn parse_single_netmsg<'a>(
+/// Parses one message off the front of `i`.
+pub fn parse_single_netmsg<'a>(
     i: &'a [u8],
     delta_decoders: &mut DeltaDecoderTable,
     custom_messages: &mut HashMap<u8, SvcNewUserMsg<'a>>,
@@ -230,7 +231,8 @@ fn parse_single_netmsg<'a>(
                         },
                     )) = &res.1
                     {
-                        delta_decoders.insert(from_utf8(name).unwrap().to_owned(), fields.to_vec());
+                        delta_decoders
+                            .insert(String::from_utf8_lossy(name).into_owned(), fields.to_vec());
                     };
                     res
                 }
@@ -56,17 +56,19 @@ impl<'a> NetMsgDoerWithExtraInfo<'a, SvcPacketEntities> for PacketEntities {
 
             let delta = if between {
                 parse_delta(
+                    i,
                     delta_decoders.get("entity_state_player_t\0").unwrap(),
                     &mut br,
-                )
+                )?
             } else {
                 if has_custom_delta {
                     parse_delta(
+                        i,
                         delta_decoders.get("custom_entity_state_t\0").unwrap(),
                         &mut br,
-                    )
+                    )?
                 } else {
-                    parse_delta(delta_decoders.get("entity_state_t\0").unwrap(), &mut br)
+                    parse_delta(i, delta_decoders.get("entity_state_t\0").unwrap(), &mut br)?
                 }
             };
 
@@ -5,23 +5,23 @@ impl<'a> NetMsgDoer<'a, SvcResourceList> for ResourceList {
     fn parse(i: &'a [u8]) -> IResult<&'a [u8], SvcResourceList> {
         let mut br = BitReader::new(i);
 
-        let resource_count = br.read_n_bit(12).to_owned();
+        let resource_count = br.name("resource_count").read_n_bit(12).to_owned();
 
         let resources: Vec<Resource> = (0..resource_count.to_u16())
             .map(|_| {
-                let type_ = br.read_n_bit(4).to_owned();
-                let name = br.read_string().to_owned();
-                let index = br.read_n_bit(12).to_owned();
-                let size = br.read_n_bit(24).to_owned();
-                let flags = br.read_n_bit(3).to_owned();
+                let type_ = br.name("type").read_n_bit(4).to_owned();
+                let name = br.name("name").read_string().to_owned();
+                let index = br.name("index").read_n_bit(12).to_owned();
+                let size = br.name("size").read_n_bit(24).to_owned();
+                let flags = br.name("flags").read_n_bit(3).to_owned();
                 let md5_hash = if flags.to_u8() & 4 != 0 {
-                    Some(br.read_n_bit(128).to_owned())
+                    Some(br.name("md5_hash").read_n_bit(128).to_owned())
                 } else {
                     None
                 };
-                let has_extra_info = br.read_1_bit();
+                let has_extra_info = br.name("has_extra_info").read_1_bit();
                 let extra_info = if has_extra_info {
-                    Some(br.read_n_bit(256).to_owned())
+                    Some(br.name("extra_info").read_n_bit(256).to_owned())
                 } else {
                     None
                 };
@@ -41,17 +41,17 @@ impl<'a> NetMsgDoer<'a, SvcResourceList> for ResourceList {
 
         let mut consistencies: Vec<Consistency> = vec![];
 
-        if br.read_1_bit() {
+        if br.name("has_consistencies").read_1_bit() {
             loop {
-                let has_check_file_flag = br.read_1_bit();
+                let has_check_file_flag = br.name("has_check_file_flag").read_1_bit();
 
                 if has_check_file_flag {
-                    let is_short_index = br.read_1_bit();
+                    let is_short_index = br.name("is_short_index").read_1_bit();
 
                     let (short_index, long_index) = if is_short_index {
-                        (Some(br.read_n_bit(5).to_owned()), None)
+                        (Some(br.name("short_index").read_n_bit(5).to_owned()), None)
                     } else {
-                        (None, Some(br.read_n_bit(10).to_owned()))
+                        (None, Some(br.name("long_index").read_n_bit(10).to_owned()))
                     };
 
                     consistencies.push(Consistency {
@@ -110,25 +110,25 @@ impl<'a> NetMsgDoer<'a, SvcResourceList> for ResourceList {
         // First read bit.
         if i.consistencies.len() != 0 {
             bw.append_bit(true);
-        } else {
-            bw.append_bit(false);
-        }
 
-        for consistency in i.consistencies {
-            bw.append_bit(consistency.has_check_file_flag);
+            for consistency in i.consistencies {
+                bw.append_bit(consistency.has_check_file_flag);
 
-            if consistency.has_check_file_flag {
-                bw.append_bit(consistency.is_short_index.unwrap());
-                if consistency.is_short_index.unwrap() {
-                    bw.append_vec(consistency.short_index.unwrap());
-                } else {
-                    bw.append_vec(consistency.long_index.unwrap());
+                if consistency.has_check_file_flag {
+                    bw.append_bit(consistency.is_short_index.unwrap());
+                    if consistency.is_short_index.unwrap() {
+                        bw.append_vec(consistency.short_index.unwrap());
+                    } else {
+                        bw.append_vec(consistency.long_index.unwrap());
+                    }
                 }
             }
-        }
 
-        // Last bit for consistency.
-        bw.append_bit(false);
+            // Last bit for consistency, only read when there are any.
+            bw.append_bit(false);
+        } else {
+            bw.append_bit(false);
+        }
 
         writer.append_u8_slice(&bw.get_u8_vec());
 
@@ -8,43 +8,53 @@ impl<'a> NetMsgDoer<'a, SvcSound> for Sound {
     fn parse(i: &'a [u8]) -> IResult<&'a [u8], SvcSound> {
         let mut br = BitReader::new(i);
 
-        let flags = br.read_n_bit(9).to_owned();
+        let flags = br.name("flags").read_n_bit(9).to_owned();
         let flag_u = flags.to_u32();
         let volume = if flag_u & 1 != 0 {
-            Some(br.read_n_bit(8).to_owned())
+            Some(br.name("volume").read_n_bit(8).to_owned())
         } else {
             None
         };
         let attenuation = if flag_u & 2 != 0 {
-            Some(br.read_n_bit(8).to_owned())
+            Some(br.name("attenuation").read_n_bit(8).to_owned())
         } else {
             None
         };
-        let channel = br.read_n_bit(3).to_owned();
-        let entity_index = br.read_n_bit(11).to_owned();
+        let channel = br.name("channel").read_n_bit(3).to_owned();
+        let entity_index = br.name("entity_index").read_n_bit(11).to_owned();
         let (sound_index_long, sound_index_short) = if flag_u & 4 != 0 {
-            (Some(br.read_n_bit(16).to_owned()), None)
+            (
+                Some(br.name("sound_index_long").read_n_bit(16).to_owned()),
+                None,
+            )
         } else {
-            (None, Some(br.read_n_bit(8).to_owned()))
+            (
+                None,
+                Some(br.name("sound_index_short").read_n_bit(8).to_owned()),
+            )
         };
-        let (has_x, has_y, has_z) = (br.read_1_bit(), br.read_1_bit(), br.read_1_bit());
+        let (has_x, has_y, has_z) = (
+            br.name("has_x").read_1_bit(),
+            br.name("has_y").read_1_bit(),
+            br.name("has_z").read_1_bit(),
+        );
         let origin_x = if has_x {
-            Some(parse_origin(&mut br))
+            Some(parse_origin(&mut br, "origin_x"))
         } else {
             None
         };
         let origin_y = if has_y {
-            Some(parse_origin(&mut br))
+            Some(parse_origin(&mut br, "origin_y"))
         } else {
             None
         };
         let origin_z = if has_z {
-            Some(parse_origin(&mut br))
+            Some(parse_origin(&mut br, "origin_z"))
         } else {
             None
         };
         let pitch = if flag_u & 8 != 0 {
-            br.read_n_bit(8).to_owned()
+            br.name("pitch").read_n_bit(8).to_owned()
         } else {
             BitVec::<u8, Lsb0>::from_element(1u8)
         };
@@ -127,7 +137,9 @@ impl<'a> NetMsgDoer<'a, SvcSound> for Sound {
     }
 }
 
-fn parse_origin(br: &mut BitReader) -> OriginCoord {
+fn parse_origin(br: &mut BitReader, name: &str) -> OriginCoord {
+    let start = br.get_offset();
+
     let int_flag = br.read_1_bit();
     let fraction_flag = br.read_1_bit();
 
@@ -151,6 +163,8 @@ fn parse_origin(br: &mut BitReader) -> OriginCoord {
 
     // let unknown = br.read_n_bit(2).to_owned();
 
+    br.name_since(name, start);
+
     OriginCoord {
         int_flag,
         fraction_flag,
@@ -13,27 +13,29 @@ impl<'a> NetMsgDoerWithExtraInfo<'a, SvcSpawnBaseline> for SpawnBaseline {
         let mut br = BitReader::new(i);
         let mut entities: Vec<EntityS> = vec![];
 
-        while br.peek_n_bits(16).to_u32() != (1 << 16) - 1 {
-            let index = br.read_n_bit(11).to_owned();
+        while br.peek_n_bits(16).to_u32() != (1 << 16) - 1 && !br.is_past_end() {
+            let index = br.name("index").read_n_bit(11).to_owned();
             let entity_index = index.to_u16();
 
             let between = index.to_u16() > 0 && index.to_u16() <= max_client as u16;
-            let type_ = br.read_n_bit(2).to_owned();
+            let type_ = br.name("type").read_n_bit(2).to_owned();
 
             let delta = if type_.to_u8() & 1 != 0 {
                 if between {
                     parse_delta(
+                        i,
                         delta_decoders.get("entity_state_player_t\0").unwrap(),
                         &mut br,
-                    )
+                    )?
                 } else {
-                    parse_delta(delta_decoders.get("entity_state_t\0").unwrap(), &mut br)
+                    parse_delta(i, delta_decoders.get("entity_state_t\0").unwrap(), &mut br)?
                 }
             } else {
                 parse_delta(
+                    i,
                     delta_decoders.get("custom_entity_state_t\0").unwrap(),
                     &mut br,
-                )
+                )?
             };
 
             let res = EntityS {
@@ -47,14 +49,14 @@ impl<'a> NetMsgDoerWithExtraInfo<'a, SvcSpawnBaseline> for SpawnBaseline {
         }
 
         // Footer | last entity = (1 << 16) - 1
-        br.read_n_bit(16);
+        br.name("footer").read_n_bit(16);
 
-        let total_extra_data = br.read_n_bit(6).to_owned();
+        let total_extra_data = br.name("total_extra_data").read_n_bit(6).to_owned();
 
         let extra_data_description = delta_decoders.get("entity_state_t\0").unwrap();
         let extra_data: Vec<Delta> = (0..total_extra_data.to_u8())
-            .map(|_| parse_delta(extra_data_description, &mut br))
-            .collect();
+            .map(|_| parse_delta(i, extra_data_description, &mut br))
+            .collect::<Result<_, _>>()?;
 
         let range = br.get_consumed_bytes();
         let (i, _) = take(range)(i)?;
@@ -184,7 +184,12 @@ impl<'a> NetMsgDoer<'a, SvcTempEntity<'a>> for TempEntity {
             // Docs say 10 but its parser says 18.
             126 => map(take(18usize), |res| TempEntityEntity::TeMultigunShot(res))(i)?,
             127 => map(take(15usize), |res| TempEntityEntity::TeUserTracer(res))(i)?,
-            _ => panic!("Bad entity ({})", entity_type),
+            _ => {
+                return Err(nom::Err::Error(nom::error::Error::new(
+                    i,
+                    nom::error::ErrorKind::Switch,
+                )))
+            }
         };
 
         Ok((
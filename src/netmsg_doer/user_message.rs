@@ -49,10 +49,13 @@ impl<'a> UserMessageDoer<'a, NetMsgUserMessage<'a>> for UserMessage {
 
         writer.append_u8(i.id);
 
-        if let Some(message) = custom_messages.get(&i.id) {
-            if message.size == -1 {
-                writer.append_u8(i.data.len() as u8);
-            }
+        // Same as parsing, only messages with a known size go without length.
+        let is_size = custom_messages
+            .get(&i.id)
+            .map_or(false, |message| message.size > -1);
+
+        if !is_size {
+            writer.append_u8(i.data.len() as u8);
         }
 
         writer.append_u8_slice(i.data);
@@ -1,3 +1,5 @@
+use std::cell::RefCell;
+
 use super::*;
 
 // Eh, I am not sure how to do inclusive until so here this is instead.
@@ -45,30 +47,55 @@ pub trait BitSliceCast {
     fn to_i32(&self) -> i32;
 }
 
+// Empty slices are 0, they come from reading past the end which fails the parse anyway.
 impl BitSliceCast for BitSlice<u8> {
     // https://github.com/ferrilab/bitvec/issues/64
     fn to_u8(&self) -> u8 {
-        self.load::<u8>()
+        if self.is_empty() {
+            0
+        } else {
+            self.load::<u8>()
+        }
     }
 
     fn to_i8(&self) -> i8 {
-        self.load::<i8>()
+        if self.is_empty() {
+            0
+        } else {
+            self.load::<i8>()
+        }
     }
 
     fn to_u16(&self) -> u16 {
-        self.load::<u16>()
+        if self.is_empty() {
+            0
+        } else {
+            self.load::<u16>()
+        }
     }
 
     fn to_i16(&self) -> i16 {
-        self.load::<i16>()
+        if self.is_empty() {
+            0
+        } else {
+            self.load::<i16>()
+        }
     }
 
     fn to_u32(&self) -> u32 {
-        self.load::<u32>()
+        if self.is_empty() {
+            0
+        } else {
+            self.load::<u32>()
+        }
     }
 
     fn to_i32(&self) -> i32 {
-        self.load::<i32>()
+        if self.is_empty() {
+            0
+        } else {
+            self.load::<i32>()
+        }
     }
 }
 
@@ -80,11 +107,51 @@ fn check_flag(lhs: u32, rhs: DeltaType) -> bool {
     lhs as u32 & rhs as u32 != 0
 }
 
+thread_local! {
+    static BIT_ANNOTATIONS: RefCell<Option<Vec<BitAnnotation>>> = RefCell::new(None);
+}
+
+/// One read by a [`BitReader`] while recording with [`record_bit_annotations`].
+#[derive(Debug, Clone)]
+pub struct BitAnnotation {
+    pub name: String,
+    /// Address of the bytes the reader was made with, to line reads up with the message.
+    pub start: usize,
+    pub bit_offset: usize,
+    pub bit_len: usize,
+}
+
+/// Runs `f` and returns every read by every [`BitReader`] meanwhile.
+///
+/// Reads done before `f` fails are still there.
+pub fn record_bit_annotations<T>(f: impl FnOnce() -> T) -> (T, Vec<BitAnnotation>) {
+    BIT_ANNOTATIONS.with(|annotations| *annotations.borrow_mut() = Some(vec![]));
+
+    let res = f();
+
+    let annotations = BIT_ANNOTATIONS
+        .with(|annotations| annotations.borrow_mut().take())
+        .unwrap_or_default();
+
+    (res, annotations)
+}
+
+fn is_annotating() -> bool {
+    BIT_ANNOTATIONS.with(|annotations| annotations.borrow().is_some())
+}
+
 // Wraps bytes into bits because doing this with nom is a very bad idea.
+//
+// Reading past the end gives nothing but still moves the offset,
+// so `get_consumed_bytes` is too big and taking that many bytes fails the parse.
 pub struct BitReader {
     pub bytes: BitVec<u8, Lsb0>,
     // Bit offset, starting from starting of `bytes`.
     offset: usize,
+    // Only for annotations, checked once so reads stay cheap.
+    annotating: bool,
+    start: usize,
+    next_name: Option<String>,
 }
 
 impl BitReader {
@@ -92,34 +159,97 @@ impl BitReader {
         BitReader {
             bytes: BitVec::from_slice(bytes),
             offset: 0,
+            annotating: is_annotating(),
+            start: bytes.as_ptr() as usize,
+            next_name: None,
         }
     }
 
+    /// Names the next read when recording annotations.
+    pub fn name(&mut self, name: &str) -> &mut Self {
+        if self.annotating {
+            self.next_name = Some(name.to_owned());
+        }
+
+        self
+    }
+
+    /// Replaces reads from `bit_offset` until now with one read called `name`.
+    pub fn name_since(&mut self, name: &str, bit_offset: usize) {
+        if !self.annotating {
+            return;
+        }
+
+        let start = self.start;
+        let bit_len = self.offset - bit_offset;
+
+        BIT_ANNOTATIONS.with(|annotations| {
+            if let Some(annotations) = annotations.borrow_mut().as_mut() {
+                while annotations.last().map_or(false, |last| {
+                    last.start == start && last.bit_offset >= bit_offset
+                }) {
+                    annotations.pop();
+                }
+
+                annotations.push(BitAnnotation {
+                    name: name.to_owned(),
+                    start,
+                    bit_offset,
+                    bit_len,
+                });
+            }
+        });
+    }
+
+    fn annotate(&mut self, bit_offset: usize, bit_len: usize) {
+        if !self.annotating {
+            return;
+        }
+
+        let name = self.next_name.take().unwrap_or_default();
+        let start = self.start;
+
+        BIT_ANNOTATIONS.with(|annotations| {
+            if let Some(annotations) = annotations.borrow_mut().as_mut() {
+                annotations.push(BitAnnotation {
+                    name,
+                    start,
+                    bit_offset,
+                    bit_len,
+                });
+            }
+        });
+    }
+
     pub fn read_1_bit(&mut self) -> bool {
-        let res = self.bytes[self.offset];
+        let res = self.bytes.get(self.offset).map_or(false, |bit| *bit);
+        self.annotate(self.offset, 1);
         self.offset += 1;
         res
     }
 
     pub fn read_n_bit(&mut self, n: usize) -> &BitSlice<u8> {
-        let range = self.offset + n;
-        let res: &BitSlice<u8, Lsb0> = &self.bytes[self.offset..range];
+        let start = self.offset;
+        self.annotate(start, n);
         self.offset += n;
-        res
+        self.bits(start, self.offset)
     }
 
     pub fn read_string(&mut self) -> &BitSlice<u8> {
         let start = self.offset;
 
         // The second condition is to make sure we are aligned.
-        while self.peek_byte() != 0 || (self.offset - start) % 8 != 0 {
+        while self.offset < self.bytes.len()
+            && (self.peek_byte() != 0 || (self.offset - start) % 8 != 0)
+        {
             self.offset += 1;
         }
 
         // Includes the null terminator.
         self.offset += 8;
+        self.annotate(start, self.offset - start);
 
-        &self.bytes[start..self.offset]
+        self.bits(start, self.offset)
     }
 
     /// Peeks 8 bits and converts to u8.
@@ -128,13 +258,24 @@ impl BitReader {
     }
 
     pub fn peek_n_bits(&self, n: usize) -> &BitSlice<u8> {
-        &self.bytes[self.offset..self.offset + n]
+        self.bits(self.offset, self.offset + n)
+    }
+
+    // Whatever there is of `start..end`.
+    fn bits(&self, start: usize, end: usize) -> &BitSlice<u8> {
+        let len = self.bytes.len();
+        &self.bytes[start.min(len)..end.min(len)]
     }
 
     pub fn get_offset(&self) -> usize {
         self.offset
     }
 
+    /// Every bit is read, anything more is past the end.
+    pub fn is_past_end(&self) -> bool {
+        self.offset >= self.bytes.len()
+    }
+
     /// Returns the number of bits read into bytes.
     pub fn get_consumed_bytes(&self) -> usize {
         let current_bit = self.get_offset();
@@ -158,32 +299,41 @@ impl BitReader {
 ///
 /// After parsing the message, we will have our delta decoder for subsequent delta parsing.
 ///
-pub fn parse_delta<'a>(dd: &DeltaDecoder, br: &'a mut BitReader) -> Delta {
+/// `i` is what `br` reads, for the error when a field has no description.
+pub fn parse_delta<'a>(
+    i: &'a [u8],
+    dd: &DeltaDecoder,
+    br: &mut BitReader,
+) -> Result<Delta, nom::Err<nom::error::Error<&'a [u8]>>> {
     let mut res: Delta = Delta::new();
 
+    let mask_start = br.get_offset();
     let mask_byte_count = br.read_n_bit(3).to_u8() as usize;
     let mask_byte: Vec<u8> = (0..mask_byte_count)
         .map(|_| br.read_n_bit(8).to_u8())
         .collect();
+    br.name_since("delta mask", mask_start);
 
-    for i in 0..mask_byte_count {
+    for (byte_idx, byte) in mask_byte.iter().enumerate() {
         for j in 0..8 {
-            let index = j + i * 8;
-
-            if index == dd.len() {
-                return res;
-            }
-
-            if (mask_byte[i] & (1 << j)) != 0 {
-                let description = &dd[index];
-                let key = from_utf8(&description.name).unwrap().to_owned();
+            let index = j + byte_idx * 8;
+
+            if (byte & (1 << j)) != 0 {
+                // Mask says there is a field the description doesn't have.
+                let description = dd.get(index).ok_or_else(|| {
+                    let rest = i.get(br.get_offset() / 8..).unwrap_or_default();
+                    nom::Err::Error(nom::error::Error::new(rest, nom::error::ErrorKind::Verify))
+                })?;
+                let key = String::from_utf8_lossy(&description.name).into_owned();
+                let field_start = br.get_offset();
                 let value = parse_delta_field(description, &mut res, br);
+                br.name_since(key.trim_end_matches('\0'), field_start);
                 res.insert(key, value);
             }
         }
     }
 
-    res
+    Ok(res)
 }
 
 fn parse_delta_field(description: &DeltaDecoderS, res: &mut Delta, br: &mut BitReader) -> Vec<u8> {
@@ -1,6 +1,7 @@
 use std::{collections::HashMap, str::from_utf8};
 
 use bitvec::vec::BitVec;
+use serde::{Deserialize, Serialize};
 
 #[repr(u32)]
 #[derive(Clone, Copy)]
@@ -36,8 +37,9 @@ pub enum DeltaType {
 /// A simplified struct of delta_description_s
 ///
 /// Lots of info end up unused.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct DeltaDecoderS {
+    #[serde(serialize_with = "c_str::serialize", deserialize_with = "c_str::owned")]
     pub name: Vec<u8>,
     pub bits: u32,
     pub divisor: f32,
@@ -63,11 +65,16 @@ impl BitTypeDebug for BitType {
 }
 
 /// UserMessage
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NetMsgUserMessage<'a> {
     pub id: u8,
     // [bool; 16]
+    #[serde(
+        serialize_with = "c_str::padded::serialize::<16, _>",
+        deserialize_with = "c_str::padded::deserialize::<16, _>"
+    )]
     pub name: &'a [u8],
+    #[serde(deserialize_with = "leak::slice")]
     pub data: &'a [u8],
 }
 
@@ -78,57 +85,69 @@ pub struct NetMsgUserMessage<'a> {
 // #[derive(Clone, Debug)]
 
 /// SVC_DISCONNECT 2
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcDisconnect<'a> {
+    #[serde(with = "c_str")]
     pub reason: &'a [u8],
 }
 
 /// SVC_EVENT 3
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcEvent {
     // [bool; 5]
+    #[serde(with = "bits")]
     pub event_count: BitType,
     pub events: Vec<EventS>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EventS {
     // [bool; 10]
+    #[serde(with = "bits")]
     pub event_index: BitType,
     pub has_packet_index: bool,
     // [bool; 11]
+    #[serde(with = "bits::option")]
     pub packet_index: Option<BitType>,
     pub has_delta: Option<bool>,
     pub delta: Option<Delta>,
     pub has_fire_time: bool,
     // [bool; 16]
+    #[serde(with = "bits::option")]
     pub fire_time: Option<BitType>,
 }
 
 /// SVC_VERSION 4
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcVersion {
     pub protocol_version: u32,
 }
 
 /// SVC_SETVIEW 5
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcSetView {
     pub entity_index: i16,
 }
 
 /// SVC_SOUND 6
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcSound {
     // [bool; 9]
+    #[serde(with = "bits")]
     pub flags: BitType,
+    #[serde(with = "bits::option")]
     pub volume: Option<BitType>,
+    #[serde(with = "bits::option")]
     pub attenuation: Option<BitType>,
     // [bool; 3]
+    #[serde(with = "bits")]
     pub channel: BitType,
     // [bool; 11]
+    #[serde(with = "bits")]
     pub entity_index: BitType,
+    #[serde(with = "bits::option")]
     pub sound_index_long: Option<BitType>,
+    #[serde(with = "bits::option")]
     pub sound_index_short: Option<BitType>,
     pub has_x: bool,
     pub has_y: bool,
@@ -136,17 +155,20 @@ pub struct SvcSound {
     pub origin_x: Option<OriginCoord>,
     pub origin_y: Option<OriginCoord>,
     pub origin_z: Option<OriginCoord>,
+    #[serde(with = "bits")]
     pub pitch: BitType,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OriginCoord {
     pub int_flag: bool,
     pub fraction_flag: bool,
     pub is_negative: Option<bool>,
     // [bool; 12]
+    #[serde(with = "bits::option")]
     pub int_value: Option<BitType>,
     // [bool; 3]
+    #[serde(with = "bits::option")]
     pub fraction_value: Option<BitType>,
     // There is no unknow, Xd
     // [bool; 2]
@@ -154,25 +176,27 @@ pub struct OriginCoord {
 }
 
 /// SVC_TIME 7
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcTime {
     pub time: f32,
 }
 
 /// SVC_PRINT 8
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcPrint<'a> {
+    #[serde(with = "c_str")]
     pub message: &'a [u8],
 }
 
 /// SVC_STUFFTEXT 9
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcStuffText<'a> {
+    #[serde(with = "c_str")]
     pub command: &'a [u8],
 }
 
 /// SVC_SETANGLE 10
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcSetAngle {
     pub pitch: i16,
     pub yaw: i16,
@@ -180,79 +204,91 @@ pub struct SvcSetAngle {
 }
 
 /// SVC_SERVERINFO 11
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcServerInfo<'a> {
     pub protocol: i32,
     pub spawn_count: i32,
     pub map_checksum: i32,
     // [u8; 16]
+    #[serde(deserialize_with = "leak::slice")]
     pub client_dll_hash: &'a [u8],
     pub max_players: u8,
     pub player_index: u8,
     pub is_deathmatch: u8,
+    #[serde(with = "c_str")]
     pub game_dir: &'a [u8],
+    #[serde(with = "c_str")]
     pub hostname: &'a [u8],
+    #[serde(with = "c_str")]
     pub map_file_name: &'a [u8],
+    #[serde(with = "c_str")]
     pub map_cycle: &'a [u8],
     pub unknown: u8,
 }
 
 /// SVC_LIGHTSTYLE 12
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcLightStyle<'a> {
     pub index: u8,
+    #[serde(with = "c_str")]
     pub light_info: &'a [u8],
 }
 
 /// SVC_UPDATEUSERINFO 13
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcUpdateUserInfo<'a> {
     pub index: u8,
     pub id: u32,
+    #[serde(with = "c_str")]
     pub user_info: &'a [u8],
     // [u8; 16]
+    #[serde(deserialize_with = "leak::slice")]
     pub cd_key_hash: &'a [u8],
 }
 
 /// SVC_DELTADESCRIPTION 14
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcDeltaDescription<'a> {
+    #[serde(with = "c_str")]
     pub name: &'a [u8],
     pub total_fields: u16,
     pub fields: DeltaDecoder,
+    #[serde(deserialize_with = "leak::slice")]
     pub clone: &'a [u8],
 }
 
 /// SVC_CLIENTDATA 15
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcClientData {
     pub has_delta_update_mask: bool,
     // [bool; 8]
+    #[serde(with = "bits::option")]
     pub delta_update_mask: Option<BitType>,
     pub client_data: Delta,
     pub weapon_data: Option<Vec<ClientDataWeaponData>>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ClientDataWeaponData {
     // [bool; 6]
+    #[serde(with = "bits")]
     pub weapon_index: BitType,
     pub weapon_data: Delta,
 }
 
 /// SVC_STOPSOUND 16
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcStopSound {
     pub entity_index: i16,
 }
 
 /// SVC_PINGS 17
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcPings {
     pub pings: Vec<PingS>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PingS {
     pub has_ping_data: bool,
     pub player_id: Option<u8>,
@@ -261,11 +297,12 @@ pub struct PingS {
 }
 
 /// SVC_PARTICLE 18
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcParticle<'a> {
     // Vec3
     pub origin: Vec<i16>,
     // Vec3
+    #[serde(deserialize_with = "leak::slice")]
     pub direction: &'a [u8],
     pub count: u8,
     pub color: u8,
@@ -274,7 +311,7 @@ pub struct SvcParticle<'a> {
 /// SVC_PARTICLE 19
 
 /// SVC_SPAWNSTATIC 20
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcSpawnStatic<'a> {
     pub model_index: i16,
     pub sequence: i8,
@@ -289,175 +326,185 @@ pub struct SvcSpawnStatic<'a> {
     pub rotation_z: i8,
     pub has_render_mode: i8,
     // [u8; 3]
+    #[serde(deserialize_with = "leak::option")]
     pub render_color: Option<&'a [u8]>,
 }
 
 /// SVC_EVENTRELIABLE 21
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcEventReliable {
     // [bool; 10]
+    #[serde(with = "bits")]
     pub event_index: BitType,
     pub event_args: Delta,
     pub has_fire_time: bool,
     // [bool; 16]
+    #[serde(with = "bits::option")]
     pub fire_time: Option<BitType>,
 }
 
 /// SVC_SPAWNBASELINE 22
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcSpawnBaseline {
     pub entities: Vec<EntityS>,
     // These members are not inside EntityS like cgdangelo/talent suggests.
     // [bool; 6]
+    #[serde(with = "bits")]
     pub total_extra_data: BitType,
     pub extra_data: Vec<Delta>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EntityS {
     // Goodies
     pub entity_index: u16,
     // [bool; 11]
+    #[serde(with = "bits")]
     pub index: BitType,
     // [bool; 2]
+    #[serde(with = "bits")]
     pub type_: BitType,
     // One delta for 3 types
     pub delta: Delta,
 }
 
 /// SVC_TEMPENTITY 23
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcTempEntity<'a> {
     pub entity_type: u8,
+    #[serde(borrow)]
     pub entity: TempEntityEntity<'a>,
 }
 
 #[repr(u8)]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum TempEntityEntity<'a> {
     // [u8; 24]
+    #[serde(borrow)]
     TeBeamPoints(TeBeamPoints<'a>) = 0,
     // [u8; 20]
-    TeBeamEntPoint(&'a [u8]) = 1,
+    TeBeamEntPoint(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 1,
     // [u8; 6]
-    TeGunshot(&'a [u8]) = 2,
+    TeGunshot(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 2,
     // It is 11
     // [u8; 11]
-    TeExplosion(&'a [u8]) = 3,
+    TeExplosion(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 3,
     // [u8; 6]
-    TeTarExplosion(&'a [u8]) = 4,
+    TeTarExplosion(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 4,
     // [u8; 10]
-    TeSmoke(&'a [u8]) = 5,
+    TeSmoke(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 5,
     // [u8; 12]
-    TeTracer(&'a [u8]) = 6,
+    TeTracer(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 6,
     // [u8; 17]
-    TeLightning(&'a [u8]) = 7,
+    TeLightning(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 7,
     // [u8; 16]
-    TeBeamEnts(&'a [u8]) = 8,
+    TeBeamEnts(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 8,
     // [u8; 6]
-    TeSparks(&'a [u8]) = 9,
+    TeSparks(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 9,
     // [u8; 6]
-    TeLavaSplash(&'a [u8]) = 10,
+    TeLavaSplash(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 10,
     // [u8; 6]
-    TeTeleport(&'a [u8]) = 11,
+    TeTeleport(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 11,
     // [u8; 8]
-    TeExplosion2(&'a [u8]) = 12,
+    TeExplosion2(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 12,
+    #[serde(borrow)]
     TeBspDecal(TeBspDecal<'a>) = 13,
     // [u8; 9]
-    TeImplosion(&'a [u8]) = 14,
+    TeImplosion(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 14,
     // [u8; 19]
-    TeSpriteTrail(&'a [u8]) = 15,
+    TeSpriteTrail(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 15,
     // [u8; 10]
-    TeSprite(&'a [u8]) = 16,
+    TeSprite(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 16,
     // [u8; 16]
-    TeBeamSprite(&'a [u8]) = 18,
+    TeBeamSprite(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 18,
     // [u8; 24]
-    TeBeamTorus(&'a [u8]) = 19,
+    TeBeamTorus(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 19,
     // [u8; 24]
-    TeBeamDisk(&'a [u8]) = 20,
+    TeBeamDisk(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 20,
     // [u8; 24]
-    TeBeamCylinder(&'a [u8]) = 21,
+    TeBeamCylinder(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 21,
     // [u8; 10]
-    TeBeamFollow(&'a [u8]) = 22,
+    TeBeamFollow(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 22,
     // [u8; 11]
-    TeGlowSprite(&'a [u8]) = 23,
+    TeGlowSprite(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 23,
     // [u8; 16]
-    TeBeamRing(&'a [u8]) = 24,
+    TeBeamRing(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 24,
     // [u8; 19]
-    TeStreakSplash(&'a [u8]) = 25,
+    TeStreakSplash(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 25,
     // [u8; 12]
-    TeDLight(&'a [u8]) = 27,
+    TeDLight(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 27,
     // [u8; 16]
-    TeELight(&'a [u8]) = 28,
+    TeELight(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 28,
+    #[serde(borrow)]
     TeTextMessage(TeTextMessage<'a>) = 29,
     // [u8; 17]
-    TeLine(&'a [u8]) = 30,
+    TeLine(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 30,
     // [u8; 17]
-    TeBox(&'a [u8]) = 31,
+    TeBox(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 31,
     // [u8; 2]
-    TeKillBeam(&'a [u8]) = 99,
+    TeKillBeam(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 99,
     // [u8; 10]
-    TeLargeFunnel(&'a [u8]) = 100,
+    TeLargeFunnel(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 100,
     // [u8; 14]
-    TeBloodStream(&'a [u8]) = 101,
+    TeBloodStream(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 101,
     // [u8; 12]
-    TeShowLine(&'a [u8]) = 102,
+    TeShowLine(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 102,
     // [u8; 14]
-    TeBlood(&'a [u8]) = 103,
+    TeBlood(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 103,
     // [u8; 9]
-    TeDecal(&'a [u8]) = 104,
+    TeDecal(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 104,
     // [u8; 5]
-    TeFizz(&'a [u8]) = 105,
+    TeFizz(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 105,
     // [u8; 17]
-    TeModel(&'a [u8]) = 106,
+    TeModel(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 106,
     // [u8; 13]
-    TeExplodeModel(&'a [u8]) = 107,
+    TeExplodeModel(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 107,
     // It is 24
     // [u8; 24]
-    TeBreakModel(&'a [u8]) = 108,
+    TeBreakModel(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 108,
     // [u8; 9]
-    TeGunshotDecal(&'a [u8]) = 109,
+    TeGunshotDecal(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 109,
     // [u8; 17]
-    TeSpriteSpray(&'a [u8]) = 110,
+    TeSpriteSpray(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 110,
     // [u8; 7]
-    TeArmorRicochet(&'a [u8]) = 111,
+    TeArmorRicochet(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 111,
     // [u8; 10]
-    TePlayerDecal(&'a [u8]) = 112,
+    TePlayerDecal(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 112,
     // [u8; 10]
-    TeBubbles(&'a [u8]) = 113,
+    TeBubbles(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 113,
     // [u8; 19]
-    TeBubbleTrail(&'a [u8]) = 114,
+    TeBubbleTrail(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 114,
     // [u8; 12]
-    TeBloodSprite(&'a [u8]) = 115,
+    TeBloodSprite(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 115,
     // [u8; 7]
-    TeWorldDecal(&'a [u8]) = 116,
+    TeWorldDecal(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 116,
     // [u8; 7]
-    TeWorldDecalHigh(&'a [u8]) = 117,
+    TeWorldDecalHigh(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 117,
     // [u8; 9]
-    TeDecalHigh(&'a [u8]) = 118,
+    TeDecalHigh(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 118,
     // [u8; 16]
-    TeProjectile(&'a [u8]) = 119,
+    TeProjectile(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 119,
     // [u8; 18]
-    TeSpray(&'a [u8]) = 120,
+    TeSpray(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 120,
     // [u8; 5]
-    TePlayerSprites(&'a [u8]) = 121,
+    TePlayerSprites(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 121,
     // [u8; 10]
-    TeParticleBurst(&'a [u8]) = 122,
+    TeParticleBurst(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 122,
     // [u8; 9]
-    TeFireField(&'a [u8]) = 123,
+    TeFireField(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 123,
     // [u8; 7]
-    TePlayerAttachment(&'a [u8]) = 124,
+    TePlayerAttachment(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 124,
     // [u8; 1]
-    TeKillPlayerAttachment(&'a [u8]) = 125,
+    TeKillPlayerAttachment(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 125,
     // It is 18.
     // [u8; 18]
-    TeMultigunShot(&'a [u8]) = 126,
+    TeMultigunShot(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 126,
     // [u8; 15]
-    TeUserTracer(&'a [u8]) = 127,
+    TeUserTracer(#[serde(deserialize_with = "leak::slice")] &'a [u8]) = 127,
 }
 
 // TE_BEAMPOINTS 0
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeBeamPoints<'a> {
     // [i16; 3]
     pub start_position: Vec<i16>,
@@ -470,15 +517,17 @@ pub struct TeBeamPoints<'a> {
     pub width: u8,
     pub noise: u8,
     // [u8; 4] RGBA
+    #[serde(deserialize_with = "leak::slice")]
     pub color: &'a [u8],
     pub speed: u8,
 }
 
 // TE_BEAMENTPOINTS 1
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeBeamEntPoint<'a> {
     pub start_entity: i16,
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub end_position: &'a [i16],
     pub sprite_index: i16,
     pub start_frame: u8,
@@ -487,21 +536,24 @@ pub struct TeBeamEntPoint<'a> {
     pub width: u8,
     pub noise: u8,
     // [i16; 4] RGBA
+    #[serde(deserialize_with = "leak::slice")]
     pub color: &'a [u8],
     pub speed: u8,
 }
 
 // TE_GUNSHOT 2
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeGunShot<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
 }
 
 // TE_EXPLOSION 3
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeExplosion<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     pub sprite_index: i16,
     pub scale: u8,
@@ -510,16 +562,18 @@ pub struct TeExplosion<'a> {
 }
 
 // TE_TAREXPLOSION 4
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeTarExplosion<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
 }
 
 // TE_SMOKE 5
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeSmoke<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     pub sprite_index: i16,
     pub scale: u8,
@@ -527,20 +581,24 @@ pub struct TeSmoke<'a> {
 }
 
 // TE_TRACER 6
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeTracer<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub start_position: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub end_position: &'a [i16],
 }
 
 // TE_LIGHTNING 7
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeLightning<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub start_position: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub end_position: &'a [i16],
     pub life: u8,
     pub width: u8,
@@ -549,7 +607,7 @@ pub struct TeLightning<'a> {
 }
 
 // TE_BEAMENTS 8
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeBeamEnts<'a> {
     // [i16; 3]
     pub start_entity: i16,
@@ -560,54 +618,62 @@ pub struct TeBeamEnts<'a> {
     pub width: u8,
     pub noise: u8,
     // [i16; 4] RGBA
+    #[serde(deserialize_with = "leak::slice")]
     pub color: &'a [u8],
     pub speed: u8,
 }
 
 // TE_SPARKS 9
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeSparks<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
 }
 
 // TE_LAVASPLASH 10
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeLavaSplash<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
 }
 
 // TE_TELEPORT 11
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeTeleport<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
 }
 
 // TE_EXPLOSION2 12
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeExplosion2<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     pub color: u8,
     pub count: u8,
 }
 
 // TE_BSPDECAL 13
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeBspDecal<'a> {
     // [u8; 8]
+    #[serde(deserialize_with = "leak::slice")]
     pub unknown1: &'a [u8],
     pub entity_index: i16,
     // [u8; 2]
+    #[serde(deserialize_with = "leak::option")]
     pub unknown2: Option<&'a [u8]>,
 }
 
 // TE_IMPLOSION 14
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeImplosion<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     pub radius: u8,
     pub count: u8,
@@ -615,11 +681,13 @@ pub struct TeImplosion<'a> {
 }
 
 // TE_SPRITETRAIL 15
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeSpriteTrail<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub start_position: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub end_position: &'a [i16],
     pub sprite_index: i16,
     pub count: u8,
@@ -630,9 +698,10 @@ pub struct TeSpriteTrail<'a> {
 }
 
 // TE_SPRITE 16
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeSprite<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     pub sprite_index: i16,
     pub scale: u8,
@@ -640,21 +709,25 @@ pub struct TeSprite<'a> {
 }
 
 // TE_BEAMSPRITE 18
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeBeamSprite<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub start_position: &'a [i16],
+    #[serde(deserialize_with = "leak::slice")]
     pub end_position: &'a [i16],
     pub beam_sprite_index: i16,
     pub end_sprite_index: i16,
 }
 
 // TE_BEAMTORUS 19
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeBeamTorus<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub axis: &'a [i16],
     pub sprite_index: i16,
     pub start_frame: u8,
@@ -662,16 +735,19 @@ pub struct TeBeamTorus<'a> {
     pub width: u8,
     pub noise: u8,
     // [i16; 4] RGBA
+    #[serde(deserialize_with = "leak::slice")]
     pub color: &'a [u8],
     pub speed: u8,
 }
 
 // TE_BEAMDISK 20
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeBeamDisk<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub axis: &'a [i16],
     pub sprite_index: i16,
     pub start_frame: u8,
@@ -679,16 +755,19 @@ pub struct TeBeamDisk<'a> {
     pub width: u8,
     pub noise: u8,
     // [i16; 4] RGBA
+    #[serde(deserialize_with = "leak::slice")]
     pub color: &'a [u8],
     pub speed: u8,
 }
 
 // TE_BEAMCYLINDER 21
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeBeamCylinder<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub axis: &'a [i16],
     pub sprite_index: i16,
     pub start_frame: u8,
@@ -696,12 +775,13 @@ pub struct TeBeamCylinder<'a> {
     pub width: u8,
     pub noise: u8,
     // [i16; 4] RGBA
+    #[serde(deserialize_with = "leak::slice")]
     pub color: &'a [u8],
     pub speed: u8,
 }
 
 // TE_BEAMFOLLOW 22
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeBeamFollow<'a> {
     pub start_entity: i16,
     pub sprite_index: i16,
@@ -709,13 +789,15 @@ pub struct TeBeamFollow<'a> {
     pub life: u8,
     pub width: u8,
     // [i16; 4] RGBA
+    #[serde(deserialize_with = "leak::slice")]
     pub color: &'a [u8],
 }
 
 // TE_GLOWSPRITE 23
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeGlowSprite<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     pub model_index: i16,
     pub scale: u8,
@@ -724,7 +806,7 @@ pub struct TeGlowSprite<'a> {
 }
 
 // TE_BEAMRING 24
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeBeamRing<'a> {
     pub start_entity: i16,
     pub end_entity: i16,
@@ -735,16 +817,19 @@ pub struct TeBeamRing<'a> {
     pub width: u8,
     pub noise: u8,
     // [i16; 4] RGBA
+    #[serde(deserialize_with = "leak::slice")]
     pub color: &'a [u8],
     pub speed: u8,
 }
 
 // TE_STREAKSPLASH 25
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeStreakSplash<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub start_position: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub vector: &'a [i16],
     pub color: i16,
     pub count: u8,
@@ -752,90 +837,105 @@ pub struct TeStreakSplash<'a> {
     pub velocity_randomness: i16,
 }
 // TE_DLIGHT 27
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeDLight<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     pub radius: u8,
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub color: &'a [u8],
     pub life: u8,
     pub decay_rate: u8,
 }
 
 // TE_ELIGHT 28
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeELight<'a> {
     pub entity_index: i16,
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     pub radius: i16,
     // [i8; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub color: &'a [u8],
     pub life: u8,
     pub decay_rate: i16,
 }
 // TE_TEXTMESSAGE 29
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeTextMessage<'a> {
     pub channel: i8,
     pub x: i16,
     pub y: i16,
     pub effect: i8,
     // [u8; 4]
+    #[serde(deserialize_with = "leak::slice")]
     pub text_color: &'a [u8],
     // THE docs forgot to mention this
+    #[serde(deserialize_with = "leak::slice")]
     pub effect_color: &'a [u8],
     pub fade_in_time: i16,
     pub fade_out_time: i16,
     pub hold_time: i16,
     pub effect_time: Option<i16>,
+    #[serde(with = "c_str")]
     pub message: &'a [u8],
 }
 
 // TE_LINE 30
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeLine<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub start_position: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub end_position: &'a [i16],
     pub life: i16,
     // [i8; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub color: &'a [u8],
 }
 
 // TE_BOX 31
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeBox<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub start_position: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub end_position: &'a [i16],
     pub life: i16,
     // [i8; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub color: &'a [u8],
 }
 
 // TE_KILLBEAM 99
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeKillBeam {
     pub entity_index: i16,
 }
 
 // TE_LARGEFUNNEL 100
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeLargeFunnel<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub start_position: &'a [i16],
     pub entity_index: i16,
     pub flags: i16,
 }
 
 // TE_BLOODSTREAM 101
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeBloodStream<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     // [i16; 3]
     pub vector: i16,
@@ -844,18 +944,21 @@ pub struct TeBloodStream<'a> {
 }
 
 // TE_SHOWLINE 102
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeShowLine<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub start_position: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub end_position: &'a [i16],
 }
 
 // TE_BLOOD 103
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeBlood<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     // [i16; 3]
     pub vector: i16,
@@ -864,16 +967,17 @@ pub struct TeBlood<'a> {
 }
 
 // TE_DECAL 104
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeDecal<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub positiion: &'a [i16],
     pub decal_index: u8,
     pub entity_index: i16,
 }
 
 // TE_FIZZ 105
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeFizz {
     pub entity_index: i16,
     pub model_index: i16,
@@ -881,11 +985,13 @@ pub struct TeFizz {
 }
 
 // TE_MODEL 106
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeModel<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub velocity: &'a [i16],
     pub angle_yaw: u8,
     pub model_index: i16,
@@ -894,11 +1000,13 @@ pub struct TeModel<'a> {
 }
 
 // TE_EXPLODEMODEL 107
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeExplodeModel<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub velocity: &'a [i16],
     pub model_index: i16,
     pub count: i16,
@@ -906,13 +1014,16 @@ pub struct TeExplodeModel<'a> {
 }
 
 // TE_BREAKMODEL 108
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeBreakModel<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub size: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub velocity: &'a [i16],
     pub velocity_randomness: u8,
     pub object_index: i16,
@@ -922,20 +1033,23 @@ pub struct TeBreakModel<'a> {
 }
 
 // TE_GUNSHOTDECAL 109
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeGunshotDecal<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     pub entity_index: i16,
     pub decal: u8,
 }
 
 // TE_SPRITESPRAY 110
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeSpriteSpray<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub velocity: &'a [i16],
     pub model_index: i16,
     pub count: u8,
@@ -944,29 +1058,33 @@ pub struct TeSpriteSpray<'a> {
 }
 
 // TE_ARMORRICOCHET 111
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeArmorRicochet<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     pub scale: u8,
 }
 
 // TE_PLAYERDECAL 112
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TePlayerDecal<'a> {
     pub player_index: u8,
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     pub entity_index: i16,
     pub decal_index: u8,
 }
 
 // TE_BUBBLES 113
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeBubbles<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub min_start_positition: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub max_start_position: &'a [i16],
     pub scale: i16,
     pub model_index: i16,
@@ -975,11 +1093,13 @@ pub struct TeBubbles<'a> {
 }
 
 // TE_BUBBLETRAIL 114
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeBubbleTrail<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub min_start_positition: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub max_start_position: &'a [i16],
     pub scale: i16,
     pub model_index: i16,
@@ -988,9 +1108,10 @@ pub struct TeBubbleTrail<'a> {
 }
 
 // TE_BLOODSPRITE 115
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeBloodSprite<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     pub model_index: i16,
     pub decal_index: i16,
@@ -999,36 +1120,41 @@ pub struct TeBloodSprite<'a> {
 }
 
 // TE_WORLDDECAL 116
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeWorldDecal<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     pub texture_index: u8,
 }
 
 // TE_WORLDDECALHIGH 117
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeWorldDecalHigh<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     pub texture_index: u8,
 }
 
 // TE_DECALHIGH 118
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeDecalHigh<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     pub decal_index: u8,
     pub entity_index: i16,
 }
 
 // TE_PROJECTILE 119
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeProjectile<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub velocity: &'a [i16],
     pub model_index: i16,
     pub life: u8,
@@ -1036,11 +1162,13 @@ pub struct TeProjectile<'a> {
 }
 
 // TE_SPRAY 120
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeSpray<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub position: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub direction: &'a [i16],
     pub model_index: i16,
     pub count: u8,
@@ -1049,7 +1177,7 @@ pub struct TeSpray<'a> {
 }
 
 // TE_PLAYERSPRITES 121
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TePlayerSprites {
     pub entity_index: i16,
     pub model_index: i16,
@@ -1058,9 +1186,10 @@ pub struct TePlayerSprites {
 }
 
 // TE_PARTICLEBURST 122
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeParticleBurst<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub origin: &'a [i16],
     pub scale: i16,
     pub color: u8,
@@ -1068,9 +1197,10 @@ pub struct TeParticleBurst<'a> {
 }
 
 // TE_FIREFIELD 123
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeFireField<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub origin: &'a [i16],
     pub scale: i16,
     pub model_index: i16,
@@ -1080,7 +1210,7 @@ pub struct TeFireField<'a> {
 }
 
 // TE_PLAYERATTACHMENT 124
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TePlayerAttachment {
     pub entity_index: u8,
     pub scale: i16,
@@ -1089,30 +1219,35 @@ pub struct TePlayerAttachment {
 }
 
 // TE_KILLPLAYERATTACHMENT 125
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeKillPlayerAttachment {
     pub entity_index: u8,
 }
 
 // TE_MULTIGUNSHOT 126
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeMultigunShot<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub origin: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub direction: &'a [i16],
     // [i16; 2]
+    #[serde(deserialize_with = "leak::slice")]
     pub noise: &'a [i16],
     pub count: u8,
     pub decal_index: u8,
 }
 
 // TE_USERTRACER 127
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TeUserTracer<'a> {
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub origin: &'a [i16],
     // [i16; 3]
+    #[serde(deserialize_with = "leak::slice")]
     pub velocity: &'a [i16],
     pub life: u8,
     pub color: u8,
@@ -1120,20 +1255,21 @@ pub struct TeUserTracer<'a> {
 }
 
 /// SVC_SETPAUSE 24
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcSetPause {
     pub is_paused: i8,
 }
 
 /// SVC_SIGNONNUM 25
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcSignOnNum {
     pub sign: i8,
 }
 
 /// SVC_CENTERPRINT 26
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcCenterPrint<'a> {
+    #[serde(with = "c_str")]
     pub message: &'a [u8],
 }
 
@@ -1144,7 +1280,7 @@ pub struct SvcCenterPrint<'a> {
 // #[derive(Clone, Debug)]
 
 /// SVC_SPAWNSTATICSOUND 29
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcSpawnStaticSound {
     // Vec3
     pub origin: Vec<i16>,
@@ -1160,112 +1296,129 @@ pub struct SvcSpawnStaticSound {
 // #[derive(Clone, Debug)]
 
 /// SVC_FINALE 31
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcFinale<'a> {
+    #[serde(with = "c_str")]
     pub text: &'a [u8],
 }
 
 /// SVC_CDTRACK 32
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcCdTrack {
     pub track: i8,
     pub loop_track: i8,
 }
 
 /// SVC_RESTORE 33
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcRestore<'a> {
+    #[serde(with = "c_str")]
     pub save_name: &'a [u8],
     pub map_count: u8,
+    #[serde(with = "c_str::vec")]
     pub map_names: Vec<&'a [u8]>,
 }
 
 /// SVC_CUTSCENE 34
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcCutscene<'a> {
+    #[serde(with = "c_str")]
     pub text: &'a [u8],
 }
 
 /// SVC_WEAPONANIM 35
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcWeaponAnim {
     pub sequence_number: i8,
     pub weapon_model_body_group: i8,
 }
 
 /// SVC_DECALNAME 36
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcDecalName<'a> {
     pub position_index: u8,
+    #[serde(with = "c_str")]
     pub decal_name: &'a [u8],
 }
 
 /// SVC_ROOMTYPE 37
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcRoomType {
     pub room_type: u16,
 }
 
 /// SVC_ADDANGLE 38
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcAddAngle {
     pub angle_to_add: i16,
 }
 
 /// SVC_NEWUSERMSG 39
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SvcNewUserMsg<'a> {
     pub index: u8,
     // weird but it's for consistency
     pub size: i8,
     // [u8; 16]
+    #[serde(
+        serialize_with = "c_str::padded::serialize::<16, _>",
+        deserialize_with = "c_str::padded::deserialize::<16, _>"
+    )]
     pub name: &'a [u8],
 }
 
 /// SVC_PACKETENTITIES 40
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcPacketEntities {
     // [bool; 16]
+    #[serde(with = "bits")]
     pub entity_count: BitType,
     pub entity_states: Vec<EntityState>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EntityState {
     pub entity_index: u16,
     pub increment_entity_number: bool,
     pub is_absolute_entity_index: Option<bool>,
     // [bool; 11]
+    #[serde(with = "bits::option")]
     pub absolute_entity_index: Option<BitType>,
     // [bool; 6]
+    #[serde(with = "bits::option")]
     pub entity_index_difference: Option<BitType>,
     pub has_custom_delta: bool,
     pub has_baseline_index: bool,
     // [bool; 6]
+    #[serde(with = "bits::option")]
     pub baseline_index: Option<BitType>,
     pub delta: Delta,
 }
 
 /// SVC_DELTAPACKETENTITIES 41
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcDeltaPacketEntities {
     // [bool; 16]
+    #[serde(with = "bits")]
     pub entity_count: BitType,
     // [bool; 8]
+    #[serde(with = "bits")]
     pub delta_sequence: BitType,
     pub entity_states: Vec<EntityStateDelta>,
 }
 
 /// These infos are not like THE docs mention.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EntityStateDelta {
     // [bool; 11] but do u16 because arithmetic.
     pub entity_index: u16,
     pub remove_entity: bool,
     pub is_absolute_entity_index: bool,
     // [bool; 11]
+    #[serde(with = "bits::option")]
     pub absolute_entity_index: Option<BitType>,
     // [bool; 6]
+    #[serde(with = "bits::option")]
     pub entity_index_difference: Option<BitType>,
     // Need to be optional because if remove is true then it won't have delta.
     pub has_custom_delta: Option<bool>,
@@ -1275,45 +1428,55 @@ pub struct EntityStateDelta {
 /// SVC_CHOKE 42
 
 /// SVC_RESOURCELIST 43
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcResourceList {
     // [bool; 12]
+    #[serde(with = "bits")]
     pub resource_count: BitType,
     pub resources: Vec<Resource>,
     pub consistencies: Vec<Consistency>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Resource {
     // [bool; 4]
+    #[serde(with = "bits")]
     pub type_: BitType,
     // &'[u8]
+    #[serde(with = "bits::c_str")]
     pub name: BitType,
     // [bool; 12]
+    #[serde(with = "bits")]
     pub index: BitType,
     // [bool; 24]
+    #[serde(with = "bits")]
     pub size: BitType,
     // [bool; 3]
+    #[serde(with = "bits")]
     pub flags: BitType,
     // [bool; 128]
+    #[serde(with = "bits::option")]
     pub md5_hash: Option<BitType>,
     pub has_extra_info: bool,
     // [bool; 256]
+    #[serde(with = "bits::option")]
     pub extra_info: Option<BitType>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Consistency {
     pub has_check_file_flag: bool,
     pub is_short_index: Option<bool>,
     // [bool; 5]
+    #[serde(with = "bits::option")]
     pub short_index: Option<BitType>,
     // [bool; 10]
+    #[serde(with = "bits::option")]
     pub long_index: Option<BitType>,
 }
 
 /// SVC_NEWMOVEVARS 44
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcNewMoveVars<'a> {
     pub gravity: f32,
     pub stop_speed: f32,
@@ -1338,38 +1501,41 @@ pub struct SvcNewMoveVars<'a> {
     pub sky_color: Vec<f32>,
     // Vec3
     pub sky_vec: Vec<f32>,
+    #[serde(with = "c_str")]
     pub sky_name: &'a [u8],
 }
 
 /// SVC_RESOURCEREQUEST 45
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcResourceRequest {
     pub spawn_count: i32,
     pub unknown: Vec<u8>,
 }
 
 /// SVC_CUSTOMIZATION 46
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcCustomization<'a> {
     pub player_index: u8,
     pub type_: u8,
+    #[serde(with = "c_str")]
     pub name: &'a [u8],
     pub index: u16,
     pub download_size: u32,
     pub flags: u8,
     // [u8; 16]
+    #[serde(deserialize_with = "leak::option")]
     pub md5_hash: Option<&'a [u8]>,
 }
 
 /// SVC_CROSSHAIRANGLE 47
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcCrosshairAngle {
     pub pitch: i16,
     pub yaw: i16,
 }
 
 /// SVC_SOUNDFADE 48
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcSoundFade {
     pub initial_percent: u8,
     pub hold_time: u8,
@@ -1378,75 +1544,85 @@ pub struct SvcSoundFade {
 }
 
 /// SVC_FILETXFERFAILED 49
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcFileTxferFailed<'a> {
+    #[serde(with = "c_str")]
     pub file_name: &'a [u8],
 }
 
 /// SVC_HLTV 50
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcHltv {
     pub mode: u8,
 }
 
 /// SVC_DIRECTOR 51
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcDirector<'a> {
     pub length: u8,
     pub flag: u8,
+    #[serde(with = "c_str")]
     pub message: &'a [u8],
 }
 
 /// SVC_VOINCEINIT 52
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcVoiceInit<'a> {
+    #[serde(with = "c_str")]
     pub codec_name: &'a [u8],
     pub quality: i8,
 }
 
 /// SVC_VOICEDATA 53
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcVoiceData<'a> {
     pub player_index: u8,
     pub size: u16,
+    #[serde(deserialize_with = "leak::slice")]
     pub data: &'a [u8],
 }
 
 /// SVC_SENDEXTRAINFO 54
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcSendExtraInfo<'a> {
+    #[serde(with = "c_str")]
     pub fallback_dir: &'a [u8],
     pub can_cheat: u8,
 }
 
 /// SVC_TIMESCALE 55
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcTimeScale {
     pub time_scale: f32,
 }
 
 /// SVC_RESOURCELOCATION 56
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcResourceLocation<'a> {
+    #[serde(with = "c_str")]
     pub download_url: &'a [u8],
 }
 
 /// SVC_SENDCVARVALUE 57
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcSendCvarValue<'a> {
+    #[serde(with = "c_str")]
     pub name: &'a [u8],
 }
 
 /// SVC_SENDCVARVALUE2 58
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SvcSendCvarValue2<'a> {
     pub request_id: u32,
+    #[serde(with = "c_str")]
     pub name: &'a [u8],
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Message<'a> {
+    #[serde(borrow)]
     UserMessage(NetMsgUserMessage<'a>),
+    #[serde(borrow)]
     EngineMessage(EngineMessage<'a>),
 }
 
@@ -1456,66 +1632,92 @@ pub enum MessageType {
 }
 
 #[repr(u8)]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum EngineMessage<'a> {
     SvcBad = 0,
     SvcNop = 1,
+    #[serde(borrow)]
     SvcDisconnect(SvcDisconnect<'a>) = 2,
     SvcEvent(SvcEvent) = 3,
     SvcVersion(SvcVersion) = 4,
     SvcSetView(SvcSetView) = 5,
     SvcSound(SvcSound) = 6,
     SvcTime(SvcTime) = 7,
+    #[serde(borrow)]
     SvcPrint(SvcPrint<'a>) = 8,
+    #[serde(borrow)]
     SvcStuffText(SvcStuffText<'a>) = 9,
     SvcSetAngle(SvcSetAngle) = 10,
+    #[serde(borrow)]
     SvcServerInfo(SvcServerInfo<'a>) = 11,
+    #[serde(borrow)]
     SvcLightStyle(SvcLightStyle<'a>) = 12,
+    #[serde(borrow)]
     SvcUpdateUserInfo(SvcUpdateUserInfo<'a>) = 13,
+    #[serde(borrow)]
     SvcDeltaDescription(SvcDeltaDescription<'a>) = 14,
     SvcClientData(SvcClientData) = 15,
     SvcStopSound(SvcStopSound) = 16,
     SvcPings(SvcPings) = 17,
+    #[serde(borrow)]
     SvcParticle(SvcParticle<'a>) = 18,
     SvcDamage = 19,
+    #[serde(borrow)]
     SvcSpawnStatic(SvcSpawnStatic<'a>) = 20,
     SvcEventReliable(SvcEventReliable) = 21,
     SvcSpawnBaseline(SvcSpawnBaseline) = 22,
+    #[serde(borrow)]
     SvcTempEntity(SvcTempEntity<'a>) = 23,
     SvcSetPause(SvcSetPause) = 24,
     SvcSignOnNum(SvcSignOnNum) = 25,
+    #[serde(borrow)]
     SvcCenterPrint(SvcCenterPrint<'a>) = 26,
     SvcKilledMonster = 27,
     SvcFoundSecret = 28,
     SvcSpawnStaticSound(SvcSpawnStaticSound) = 29,
     SvcIntermission = 30,
+    #[serde(borrow)]
     SvcFinale(SvcFinale<'a>) = 31,
     SvcCdTrack(SvcCdTrack) = 32,
+    #[serde(borrow)]
     SvcRestore(SvcRestore<'a>) = 33,
+    #[serde(borrow)]
     SvcCutscene(SvcCutscene<'a>) = 34,
     SvcWeaponAnim(SvcWeaponAnim) = 35,
+    #[serde(borrow)]
     SvcDecalName(SvcDecalName<'a>) = 36,
     SvcRoomType(SvcRoomType) = 37,
     SvcAddAngle(SvcAddAngle) = 38,
+    #[serde(borrow)]
     SvcNewUserMsg(SvcNewUserMsg<'a>) = 39,
     SvcPacketEntities(SvcPacketEntities) = 40,
     SvcDeltaPacketEntities(SvcDeltaPacketEntities) = 41,
     SvcChoke = 42,
     SvcResourceList(SvcResourceList) = 43,
+    #[serde(borrow)]
     SvcNewMovevars(SvcNewMoveVars<'a>) = 44,
     SvcResourceRequest(SvcResourceRequest) = 45,
+    #[serde(borrow)]
     SvcCustomization(SvcCustomization<'a>) = 46,
     SvcCrosshairAngle(SvcCrosshairAngle) = 47,
     SvcSoundFade(SvcSoundFade) = 48,
+    #[serde(borrow)]
     SvcFileTxferFailed(SvcFileTxferFailed<'a>) = 49,
     SvcHltv(SvcHltv) = 50,
+    #[serde(borrow)]
     SvcDirector(SvcDirector<'a>) = 51,
+    #[serde(borrow)]
     SvcVoiceInit(SvcVoiceInit<'a>) = 52,
+    #[serde(borrow)]
     SvcVoiceData(SvcVoiceData<'a>) = 53,
+    #[serde(borrow)]
     SvcSendExtraInfo(SvcSendExtraInfo<'a>) = 54,
     SvcTimeScale(SvcTimeScale) = 55,
+    #[serde(borrow)]
     SvcResourceLocation(SvcResourceLocation<'a>) = 56,
+    #[serde(borrow)]
     SvcSendCvarValue(SvcSendCvarValue<'a>) = 57,
+    #[serde(borrow)]
     SvcSendCvarValue2(SvcSendCvarValue2<'a>) = 58,
 }
 
@@ -1654,3 +1856,252 @@ impl From<u8> for MessageType {
         }
     }
 }
+
+/// Deserializers for borrowed slices.
+///
+/// Demo bytes can be borrowed from but JSON can't, so these leak instead.
+pub mod leak {
+    use serde::{Deserialize, Deserializer};
+
+    pub fn slice<'de, D, T>(deserializer: D) -> Result<&'static [T], D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de> + 'static,
+    {
+        let res: Vec<T> = Vec::deserialize(deserializer)?;
+        Ok(res.leak())
+    }
+
+    pub fn option<'de, D, T>(deserializer: D) -> Result<Option<&'static [T]>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de> + 'static,
+    {
+        let res: Option<Vec<T>> = Option::deserialize(deserializer)?;
+        Ok(res.map(|res| &*res.leak()))
+    }
+
+    pub fn vec<'de, D, T>(deserializer: D) -> Result<Vec<&'static [T]>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de> + 'static,
+    {
+        let res: Vec<Vec<T>> = Vec::deserialize(deserializer)?;
+        Ok(res.into_iter().map(|res| &*res.leak()).collect())
+    }
+}
+
+/// `BitType` as a string of its bits.
+///
+/// Last bit comes first so the string reads like the number it holds.
+pub mod bits {
+    use bitvec::prelude::*;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use super::BitType;
+
+    pub fn serialize<S: Serializer>(bits: &BitType, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&to_string(bits))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BitType, D::Error> {
+        from_str(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+
+    fn to_string(bits: &BitSlice<u8>) -> String {
+        bits.iter()
+            .rev()
+            .map(|bit| if *bit { '1' } else { '0' })
+            .collect()
+    }
+
+    fn from_str(text: &str) -> Result<BitType, String> {
+        text.chars()
+            .rev()
+            .map(|bit| match bit {
+                '0' => Ok(false),
+                '1' => Ok(true),
+                _ => Err(format!("cannot parse bits {}, expected 0 and 1", text)),
+            })
+            .collect()
+    }
+
+    pub mod option {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(
+            bits: &Option<BitType>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match bits {
+                Some(bits) => serializer.serialize_some(&to_string(bits)),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<BitType>, D::Error> {
+            Option::<String>::deserialize(deserializer)?
+                .map(|text| from_str(&text).map_err(D::Error::custom))
+                .transpose()
+        }
+    }
+
+    /// Bits holding a null terminated string, see [`super::c_str`].
+    pub mod c_str {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(bits: &BitType, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut bits = bits.to_owned();
+            bits.force_align();
+
+            super::super::c_str::serialize(&bits.into_vec(), serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<BitType, D::Error> {
+            super::super::c_str::owned(deserializer).map(BitType::from_vec)
+        }
+    }
+}
+
+/// Null terminated strings as JSON strings.
+///
+/// Anything that isn't text with a null at the end stays as bytes so nothing is lost.
+/// Deserializing leaks like [`leak`].
+pub mod c_str {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum CStr {
+        Text(String),
+        Bytes(Vec<u8>),
+    }
+
+    impl CStr {
+        fn into_bytes(self) -> Vec<u8> {
+            match self {
+                CStr::Text(text) => {
+                    let mut bytes = text.into_bytes();
+                    bytes.push(0);
+                    bytes
+                }
+                CStr::Bytes(bytes) => bytes,
+            }
+        }
+    }
+
+    struct CStrRef<'a>(&'a [u8]);
+
+    impl Serialize for CStrRef<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serialize(&self.0, serializer)
+        }
+    }
+
+    fn text(bytes: &[u8]) -> Option<&str> {
+        let text = bytes.strip_suffix(&[0])?;
+
+        if text.contains(&0) {
+            return None;
+        }
+
+        std::str::from_utf8(text).ok()
+    }
+
+    pub fn serialize<S: Serializer, T: AsRef<[u8]>>(
+        bytes: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let bytes = bytes.as_ref();
+
+        match text(bytes) {
+            Some(text) => serializer.serialize_str(text),
+            None => serializer.collect_seq(bytes),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<&'static [u8], D::Error> {
+        owned(deserializer).map(|bytes| &*bytes.leak())
+    }
+
+    pub fn owned<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        CStr::deserialize(deserializer).map(CStr::into_bytes)
+    }
+
+    pub mod vec {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(
+            strings: &[&[u8]],
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(strings.iter().map(|bytes| CStrRef(bytes)))
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Vec<&'static [u8]>, D::Error> {
+            let strings = Vec::<CStr>::deserialize(deserializer)?;
+
+            Ok(strings
+                .into_iter()
+                .map(|string| &*string.into_bytes().leak())
+                .collect())
+        }
+    }
+
+    /// Fixed size buffers of `N` bytes, the text is padded with nulls.
+    pub mod padded {
+        use serde::de::Error;
+
+        use super::*;
+
+        fn text<const N: usize>(bytes: &[u8]) -> Option<&str> {
+            let end = bytes.iter().position(|byte| *byte == 0)?;
+
+            if bytes.len() != N || bytes[end..].iter().any(|byte| *byte != 0) {
+                return None;
+            }
+
+            std::str::from_utf8(&bytes[..end]).ok()
+        }
+
+        pub fn serialize<const N: usize, S: Serializer>(
+            bytes: &&[u8],
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match text::<N>(bytes) {
+                Some(text) => serializer.serialize_str(text),
+                None => serializer.collect_seq(bytes.iter()),
+            }
+        }
+
+        pub fn deserialize<'de, const N: usize, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<&'static [u8], D::Error> {
+            let bytes = match CStr::deserialize(deserializer)? {
+                CStr::Text(text) if text.len() >= N => {
+                    return Err(D::Error::custom(format!(
+                        "{} is longer than {} bytes",
+                        text,
+                        N - 1
+                    )))
+                }
+                CStr::Text(text) => {
+                    let mut bytes = text.into_bytes();
+                    bytes.resize(N, 0);
+                    bytes
+                }
+                CStr::Bytes(bytes) => bytes,
+            };
+
+            Ok(bytes.leak())
+        }
+    }
+}
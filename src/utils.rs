@@ -3,15 +3,15 @@ use hldemo::{MoveVars, NetMsgData, NetMsgInfo, RefParams, UserCmd};
 #[macro_export]
 macro_rules! write_demo {
     ($demo_name:literal, $demo:ident) => {{
-        use demosuperimpose_goldsrc::writer::DemoWriter;
+        use $crate::writer::DemoWriter;
         let mut out = DemoWriter::new(String::from($demo_name));
-        out.write_file($demo);
+        out.write_file($demo).unwrap();
     }};
 
     ($demo_name:ident, $demo:ident) => {{
-        use demosuperimpose_goldsrc::writer::DemoWriter;
+        use $crate::writer::DemoWriter;
         let mut out = DemoWriter::new(String::from($demo_name));
-        out.write_file($demo);
+        out.write_file($demo).unwrap();
     }};
 }
 
@@ -46,7 +46,7 @@ macro_rules! open_demo {
 #[macro_export]
 macro_rules! nbit_num {
     ($num:expr, $bit:expr) => {{
-        use crate::writer::BitWriter;
+        use $crate::writer::BitWriter;
         let mut writer = BitWriter::new();
         writer.append_u32_range($num as u32, $bit);
         writer.data
@@ -56,7 +56,7 @@ macro_rules! nbit_num {
 #[macro_export]
 macro_rules! nbit_str {
     ($name:expr) => {{
-        use crate::writer::BitWriter;
+        use $crate::writer::BitWriter;
         let mut writer = BitWriter::new();
         $name.as_bytes().iter().for_each(|s| writer.append_u8(*s));
         writer.data
@@ -66,9 +66,10 @@ macro_rules! nbit_str {
 #[macro_export]
 macro_rules! init_parse {
     ($demo:ident) => {{
-        use crate::demo_doer::{
-            get_initial_delta, parse_netmsg, FrameData, HashMap, SvcNewUserMsg,
-        };
+        use hldemo::FrameData;
+        use std::collections::HashMap;
+        use $crate::netmsg_doer::{parse_netmsg, utils::get_initial_delta};
+        use $crate::types::SvcNewUserMsg;
 
         let mut delta_decoders = get_initial_delta();
         let mut custom_messages = HashMap::<u8, SvcNewUserMsg>::new();
@@ -147,7 +148,7 @@ macro_rules! insert_packet_entity_state_with_index {
         // due to entity index difference mechanism.
         // Not really inserting now per se, moreso finding good info
         // to populate our struct.
-        use demosuperimpose_goldsrc::types::BitType;
+        use $crate::types::BitType;
 
         let before_entity = &$entity_states[insert_index - 1];
         let mut is_absolute_entity_index = false;
@@ -217,7 +218,7 @@ macro_rules! insert_packet_entity_state_delta_with_index {
         // due to entity index difference mechanism.
         // Not really inserting now per se, moreso finding good info
         // to populate our struct.
-        use demosuperimpose_goldsrc::types::BitType;
+        use $crate::types::BitType;
 
         let before_entity = &$entity_states[insert_index - 1];
         let mut is_absolute_entity_index = false;
@@ -547,6 +548,63 @@ pub enum ResourceType {
     World = 6,
 }
 
+/// Delta integer of 1 to 4 little endian bytes, shorter ones sign extended like `i8` and `i16`.
+pub fn delta_int(bytes: &[u8]) -> i32 {
+    match *bytes {
+        [] => 0,
+        [a] => a as i8 as i32,
+        [a, b] => i16::from_le_bytes([a, b]) as i32,
+        [a, b, c] => i32::from_le_bytes([0, a, b, c]) >> 8,
+        [a, b, c, d, ..] => i32::from_le_bytes([a, b, c, d]),
+    }
+}
+
+/// Linear interpolation from `a` to `b`, `t` is 0 to 1.
+pub fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// [`lerp`] for angles in degrees, going the short way so 179 to -179 crosses 180 instead of 0.
+///
+/// Result is in (-180, 180].
+pub fn lerp_angle(a: f32, b: f32, t: f32) -> f32 {
+    let mut diff = (b - a) % 360.;
+
+    if diff > 180. {
+        diff -= 360.;
+    } else if diff < -180. {
+        diff += 360.;
+    }
+
+    let mut angle = (a + diff * t) % 360.;
+
+    if angle > 180. {
+        angle -= 360.;
+    } else if angle <= -180. {
+        angle += 360.;
+    }
+
+    angle
+}
+
+/// [`lerp`] for every axis.
+pub fn lerp_vec(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    [
+        lerp(a[0], b[0], t),
+        lerp(a[1], b[1], t),
+        lerp(a[2], b[2], t),
+    ]
+}
+
+/// [`lerp_angle`] for every axis.
+pub fn lerp_angles(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    [
+        lerp_angle(a[0], b[0], t),
+        lerp_angle(a[1], b[1], t),
+        lerp_angle(a[2], b[2], t),
+    ]
+}
+
 pub trait NetMsgDataMethods {
     /// Creates semi-default net message data for CS 1.6
     ///
@@ -1024,7 +1082,7 @@ macro_rules! get_cs_delta_msg {
 #[macro_export]
 macro_rules! get_cs_delta_decoder_table {
     () => {{
-        use demosuperimpose_goldsrc::types::DeltaDecoderS;
+        use $crate::types::DeltaDecoderS;
 
         let mut dt = DeltaDecoderTable::new();
 
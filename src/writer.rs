@@ -2,7 +2,6 @@ use bitvec::slice::BitSlice;
 use bitvec::vec::BitVec;
 use hldemo::{Demo, Directory, DirectoryEntry, Frame, FrameData, Header, NetMsgFrameType};
 use std::fs;
-use std::io::Write;
 
 use bitvec::prelude::Lsb0;
 
@@ -195,17 +194,10 @@ impl DemoWriter {
         }
     }
 
-    pub fn write_file(&mut self, demo: Demo) {
-        let mut file = fs::OpenOptions::new()
-            .write(true)
-            .truncate(true)
-            .create(true)
-            .open(&self.filename)
-            .unwrap();
-
+    pub fn write_file(&mut self, demo: Demo) -> std::io::Result<()> {
         self.write_demo(demo);
 
-        let _ = file.write_all(&self.writer.data);
+        fs::write(&self.filename, &self.writer.data)
     }
 
     fn write_demo(&mut self, demo: Demo) {
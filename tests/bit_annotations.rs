@@ -0,0 +1,104 @@
+//! Reads of a message are recorded by name and cover its bits back to back.
+
+mod common;
+
+use std::collections::HashMap;
+
+use demosuperimpose_goldsrc::netmsg_doer::{
+    parse_single_netmsg,
+    utils::{get_initial_delta, parse_delta, record_bit_annotations, BitReader},
+    write_single_netmsg,
+};
+use demosuperimpose_goldsrc::types::{EngineMessage, Message, SvcDeltaDescription};
+use demosuperimpose_goldsrc::writer::BitWriter;
+
+use common::{hldm, parsed_netmsgs};
+
+const DELTA_DESCRIPTION: &str = "delta_description_t\0";
+
+/// Delta descriptions only need the table the engine starts with, so they parse on their own.
+fn first_delta_description() -> SvcDeltaDescription<'static> {
+    parsed_netmsgs(&hldm())
+        .into_iter()
+        .flat_map(|(_, _, messages)| messages)
+        .find_map(|message| match message {
+            Message::EngineMessage(EngineMessage::SvcDeltaDescription(description)) => {
+                Some(description)
+            }
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[test]
+fn delta_description_annotations() {
+    let message =
+        Message::EngineMessage(EngineMessage::SvcDeltaDescription(first_delta_description()));
+    let bytes: &'static [u8] =
+        write_single_netmsg(message, &get_initial_delta(), &HashMap::new()).leak();
+
+    let (res, annotations) = record_bit_annotations(|| {
+        parse_single_netmsg(bytes, &mut get_initial_delta(), &mut HashMap::new())
+    });
+
+    let description = match res.unwrap().1 {
+        Message::EngineMessage(EngineMessage::SvcDeltaDescription(description)) => description,
+        message => panic!("{:?}", message),
+    };
+
+    let field_names: Vec<String> = get_initial_delta()[DELTA_DESCRIPTION]
+        .iter()
+        .map(|field| String::from_utf8_lossy(&field.name).into_owned())
+        .collect();
+
+    // Every field is a delta of the delta description, each starting with its mask.
+    let masks = annotations
+        .iter()
+        .filter(|annotation| annotation.name == "delta mask")
+        .count();
+    assert_eq!(masks, description.total_fields as usize);
+    assert_eq!(annotations[0].name, "delta mask");
+
+    let mut bit_offset = 0;
+    let mut last_field: Option<usize> = None;
+
+    for annotation in &annotations {
+        // Name and field count are read before the bits.
+        assert_eq!(annotation.start, description.clone.as_ptr() as usize);
+        assert_eq!(annotation.bit_offset, bit_offset, "{:?}", annotation);
+        bit_offset += annotation.bit_len;
+
+        if annotation.name == "delta mask" {
+            assert_eq!(annotation.bit_len % 8, 3, "{:?}", annotation);
+            last_field = None;
+            continue;
+        }
+
+        // Fields come in the order of the description.
+        let field = field_names
+            .iter()
+            .position(|name| *name == annotation.name)
+            .unwrap_or_else(|| panic!("{:?}", annotation));
+        assert!(
+            last_field.map_or(true, |last| last < field),
+            "{:?}",
+            annotation
+        );
+        last_field = Some(field);
+    }
+
+    assert_eq!((bit_offset + 7) / 8, description.clone.len());
+}
+
+#[test]
+fn mask_past_the_description_is_an_error() {
+    let decoder = &get_initial_delta()[DELTA_DESCRIPTION];
+
+    // One mask byte with the bit right after the last field.
+    let mut bw = BitWriter::new();
+    bw.append_u32_range(1, 3);
+    bw.append_u8(1 << decoder.len());
+    let bytes = bw.get_u8_vec();
+
+    assert!(parse_delta(&bytes, decoder, &mut BitReader::new(&bytes)).is_err());
+}
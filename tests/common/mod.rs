@@ -0,0 +1,61 @@
+//! Shared by the integration tests.
+
+// Every test file gets all of this and none uses all of it.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use hldemo::{Demo, FrameData};
+use serde::Serialize;
+use serde_json::Value;
+
+use demosuperimpose_goldsrc::demo_doer::open_demo_file;
+use demosuperimpose_goldsrc::netmsg_doer::{parse_netmsg, utils::get_initial_delta};
+use demosuperimpose_goldsrc::types::{Message, SvcNewUserMsg};
+
+/// JSON to compare parsed things with.
+///
+/// `BitType` is a string of its bits and deltas are sorted objects, so equal messages give equal
+/// JSON no matter where the bits sat in memory or what order a hash map is in.
+pub fn comparable(value: &impl Serialize) -> Value {
+    serde_json::to_value(value).unwrap()
+}
+
+/// Close enough for angles and positions that went through a file or a lerp.
+pub fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-3
+}
+
+/// `name` in a temp folder of the running test file so test files don't step on each other.
+pub fn temp_path(name: &str) -> PathBuf {
+    let folder = std::env::temp_dir().join(concat!("demosuperimpose-", env!("CARGO_CRATE_NAME")));
+    fs::create_dir_all(&folder).unwrap();
+
+    folder.join(name)
+}
+
+/// The example demo, one LOADING entry and one playback entry.
+pub fn hldm<'a>() -> Demo<'a> {
+    open_demo_file(Path::new(env!("CARGO_MANIFEST_DIR")).join("example/hldm.dem")).unwrap()
+}
+
+/// Every netmsg parsed in order, with its entry and frame index.
+pub fn parsed_netmsgs<'a>(demo: &Demo<'a>) -> Vec<(usize, usize, Vec<Message<'a>>)> {
+    let mut delta_decoders = get_initial_delta();
+    let mut custom_messages = HashMap::<u8, SvcNewUserMsg>::new();
+    let mut res = vec![];
+
+    for (entry_idx, entry) in demo.directory.entries.iter().enumerate() {
+        for (frame_idx, frame) in entry.frames.iter().enumerate() {
+            if let FrameData::NetMsg((_, data)) = &frame.data {
+                let (_, messages) =
+                    parse_netmsg(data.msg, &mut delta_decoders, &mut custom_messages).unwrap();
+                res.push((entry_idx, frame_idx, messages));
+            }
+        }
+    }
+
+    res
+}
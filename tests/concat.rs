@@ -0,0 +1,111 @@
+//! Concatenated demos keep going from where the previous one stopped.
+
+mod common;
+
+use hldemo::{Demo, FrameData};
+
+use demosuperimpose_goldsrc::demo_doer::concat::concat;
+
+use common::hldm;
+
+fn client_times(demo: &Demo) -> Vec<f32> {
+    demo.directory
+        .entries
+        .iter()
+        .flat_map(|entry| entry.frames.iter())
+        .filter_map(|frame| match &frame.data {
+            FrameData::NetMsg((_, data)) => Some(data.info.ref_params.time),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Frametime of the last netmsg that has one.
+fn last_frametime(demo: &Demo) -> f32 {
+    demo.directory
+        .entries
+        .iter()
+        .flat_map(|entry| entry.frames.iter())
+        .filter_map(|frame| match &frame.data {
+            FrameData::NetMsg((_, data)) => Some(data.info.ref_params.frametime),
+            _ => None,
+        })
+        .filter(|frametime| *frametime > 0.)
+        .last()
+        .unwrap()
+}
+
+#[test]
+fn client_time_goes_on() {
+    let one_time = client_times(&hldm());
+    let first = one_time[0];
+    let last = one_time.iter().cloned().fold(f32::MIN, f32::max);
+    let frametime = last_frametime(&hldm());
+
+    let demo = concat(vec![hldm(), hldm()]).unwrap();
+    let times = client_times(&demo);
+
+    assert_eq!(times.len(), one_time.len() * 2);
+
+    // Second demo starts one frame after the first one ended.
+    let seam = one_time.len();
+    assert!(times[seam] > last);
+    assert!((times[seam] - (last + frametime)).abs() < 1e-3);
+    assert!((times[seam + 1] - (one_time[1] - first + last + frametime)).abs() < 1e-3);
+}
+
+#[test]
+fn frame_time_keeps_increasing() {
+    let one = hldm();
+    let last = one
+        .directory
+        .entries
+        .iter()
+        .flat_map(|entry| entry.frames.iter())
+        .map(|frame| frame.time)
+        .fold(f32::MIN, f32::max);
+
+    let demo = concat(vec![hldm(), hldm()]).unwrap();
+    let appended = demo
+        .directory
+        .entries
+        .iter()
+        .flat_map(|entry| entry.frames.iter())
+        .filter(|frame| frame.time > last)
+        .count();
+
+    // Every frame of the second demo is after the last of the first one.
+    let frame_count: usize = one
+        .directory
+        .entries
+        .iter()
+        .flat_map(|entry| entry.frames.iter())
+        .filter(|frame| !matches!(frame.data, FrameData::DemoStart | FrameData::NextSection))
+        .count();
+    assert_eq!(appended, frame_count);
+}
+
+#[test]
+fn same_map_shares_playback() {
+    let demo = concat(vec![hldm(), hldm()]).unwrap();
+
+    assert_eq!(demo.directory.entries.len(), hldm().directory.entries.len());
+}
+
+#[test]
+fn other_map_chains_entries() {
+    let mut other = hldm();
+    other.header.map_crc = other.header.map_crc.wrapping_add(1);
+    let entry_count = other.directory.entries.len();
+
+    let demo = concat(vec![hldm(), other]).unwrap();
+    let entries = &demo.directory.entries;
+
+    assert_eq!(entries.len(), entry_count * 2);
+    // LOADING of the second demo is an entry of its own and plays as a normal one.
+    assert_eq!(entries[entry_count].entry_type, 1);
+    assert!(entries[entry_count]
+        .frames
+        .iter()
+        .any(|frame| matches!(frame.data, FrameData::NetMsg(_))));
+}
@@ -0,0 +1,97 @@
+//! Diff says nothing for the same demo and one line for one change.
+
+mod common;
+
+use std::collections::HashMap;
+
+use hldemo::FrameData;
+
+use demosuperimpose_goldsrc::demo_doer::diff::diff;
+use demosuperimpose_goldsrc::netmsg_doer::{utils::get_initial_delta, write_netmsg};
+use demosuperimpose_goldsrc::types::{EngineMessage, Message, SvcPrint};
+
+use common::hldm;
+
+/// Entry and frame index of the last netmsg in playback.
+fn last_netmsg(demo: &hldemo::Demo) -> (usize, usize) {
+    let frame_idx = demo.directory.entries[1]
+        .frames
+        .iter()
+        .rposition(|frame| matches!(frame.data, FrameData::NetMsg(_)))
+        .unwrap();
+
+    (1, frame_idx)
+}
+
+#[test]
+fn same_demo_has_no_differences() {
+    assert_eq!(diff(&hldm(), &hldm()), Vec::<String>::new());
+}
+
+#[test]
+fn changed_frame_time() {
+    let left = hldm();
+    let mut right = hldm();
+
+    right.directory.entries[1].frames[5].time += 1.;
+
+    let res = diff(&left, &right);
+
+    assert_eq!(res.len(), 1, "{:?}", res);
+    assert!(res[0].starts_with("entry 1 frame 5: time "), "{}", res[0]);
+}
+
+#[test]
+fn added_message() {
+    let left = hldm();
+    let mut right = hldm();
+
+    let (entry_idx, frame_idx) = last_netmsg(&right);
+    let print = Message::EngineMessage(EngineMessage::SvcPrint(SvcPrint {
+        message: b"hello\0",
+    }));
+
+    if let FrameData::NetMsg((_, data)) =
+        &mut right.directory.entries[entry_idx].frames[frame_idx].data
+    {
+        // Messages are back to back, so one more goes at the end.
+        let mut msg = data.msg.to_vec();
+        msg.extend(write_netmsg(
+            vec![print],
+            &get_initial_delta(),
+            &HashMap::new(),
+        ));
+        data.msg = msg.leak();
+    }
+
+    assert_eq!(
+        diff(&left, &right),
+        vec![format!(
+            "entry {} frame {}: added message SvcPrint",
+            entry_idx, frame_idx
+        )]
+    );
+}
+
+#[test]
+fn unparseable_netmsg_is_a_difference() {
+    let left = hldm();
+    let mut right = hldm();
+
+    let (entry_idx, frame_idx) = last_netmsg(&right);
+
+    if let FrameData::NetMsg((_, data)) =
+        &mut right.directory.entries[entry_idx].frames[frame_idx].data
+    {
+        // SvcPrint without the end of its string.
+        data.msg = &[8, b'h', b'i'];
+    }
+
+    assert_eq!(
+        diff(&left, &right),
+        vec![format!(
+            "entry {} frame {}: parsed netmsg -> unparseable",
+            entry_idx, frame_idx
+        )]
+    );
+}
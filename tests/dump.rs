@@ -0,0 +1,141 @@
+//! Dump keeps only the messages, entities and times asked for.
+
+mod common;
+
+use hldemo::Demo;
+use serde_json::Value;
+
+use demosuperimpose_goldsrc::demo_doer::dump::{dump, DumpOptions};
+use demosuperimpose_goldsrc::demo_doer::message_name;
+
+use common::{hldm, parsed_netmsgs};
+
+/// Every line of an NDJSON dump.
+fn ndjson(demo: &Demo, options: &mut DumpOptions) -> Vec<Value> {
+    let mut out = vec![];
+    dump(demo, options.ndjson(), &mut out).unwrap();
+
+    String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+fn messages(line: &Value) -> &Vec<Value> {
+    line["messages"].as_array().unwrap()
+}
+
+#[test]
+fn no_filter_has_every_frame() {
+    let demo = hldm();
+    let lines = ndjson(&demo, &mut DumpOptions::new());
+
+    let frame_count: usize = demo
+        .directory
+        .entries
+        .iter()
+        .map(|entry| entry.frames.len())
+        .sum();
+    let message_count: usize = parsed_netmsgs(&demo)
+        .iter()
+        .map(|(_, _, messages)| messages.len())
+        .sum();
+
+    assert_eq!(lines.len(), frame_count);
+    assert_eq!(
+        lines.iter().map(|line| messages(line).len()).sum::<usize>(),
+        message_count
+    );
+}
+
+#[test]
+fn message_filter() {
+    let demo = hldm();
+    let lines = ndjson(&demo, DumpOptions::new().add_message("svctime"));
+
+    let time_count = parsed_netmsgs(&demo)
+        .iter()
+        .flat_map(|(_, _, messages)| messages)
+        .filter(|message| message_name(message) == "SvcTime")
+        .count();
+
+    assert!(time_count > 0);
+    // Frames without one are left out.
+    assert!(lines.iter().all(|line| !messages(line).is_empty()));
+    assert_eq!(
+        lines
+            .iter()
+            .flat_map(messages)
+            .filter(|message| message["name"] == "SvcTime")
+            .count(),
+        time_count
+    );
+    assert_eq!(
+        lines.iter().map(|line| messages(line).len()).sum::<usize>(),
+        time_count
+    );
+}
+
+#[test]
+fn entity_filter() {
+    let lines = ndjson(&hldm(), DumpOptions::new().add_entity(1));
+
+    assert!(!lines.is_empty());
+
+    for message in lines.iter().flat_map(messages) {
+        let data = &message["data"];
+        let entities = data
+            .get("entity_states")
+            .or_else(|| data.get("entities"))
+            .and_then(|entities| entities.as_array())
+            .unwrap_or_else(|| panic!("{} is not an entity message", message["name"]));
+
+        assert!(!entities.is_empty());
+        assert!(entities.iter().all(|entity| entity["entity_index"] == 1));
+    }
+}
+
+#[test]
+fn time_filter() {
+    let demo = hldm();
+    let (start, end) = (1., 2.);
+    let lines = ndjson(&demo, DumpOptions::new().time_range(Some(start), Some(end)));
+
+    let in_time = demo
+        .directory
+        .entries
+        .iter()
+        .flat_map(|entry| &entry.frames)
+        .filter(|frame| frame.time >= start && frame.time <= end)
+        .count();
+
+    assert!(in_time > 0);
+    assert_eq!(lines.len(), in_time);
+
+    for line in &lines {
+        let time = line["time"].as_f64().unwrap() as f32;
+        assert!(time >= start && time <= end, "{}", time);
+    }
+}
+
+#[test]
+fn text_has_a_line_per_frame_and_message() {
+    let demo = hldm();
+    let mut out = vec![];
+    dump(
+        &demo,
+        DumpOptions::new().time_range(Some(1.), Some(2.)),
+        &mut out,
+    )
+    .unwrap();
+
+    let lines = ndjson(&demo, DumpOptions::new().time_range(Some(1.), Some(2.)));
+    let text = String::from_utf8(out).unwrap();
+
+    let line_count = lines.len() + lines.iter().map(|line| messages(line).len()).sum::<usize>();
+    assert_eq!(text.lines().count(), line_count);
+    assert!(text
+        .lines()
+        .all(|line| line.starts_with("entry ") || line.starts_with("    ")));
+}
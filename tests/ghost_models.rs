@@ -0,0 +1,54 @@
+//! Ghost models are found in or added to the resource list.
+
+use demosuperimpose_goldsrc::demo_doer::superimpose::model::{model_path, precache_models};
+use demosuperimpose_goldsrc::netmsg_doer::utils::BitSliceCast;
+use demosuperimpose_goldsrc::types::{Resource, SvcResourceList};
+use demosuperimpose_goldsrc::utils::ResourceType;
+use demosuperimpose_goldsrc::{nbit_num, nbit_str};
+
+fn resource(type_: ResourceType, name: &str, index: u32) -> Resource {
+    Resource {
+        type_: nbit_num!(type_, 4),
+        name: nbit_str!(format!("{}\0", name)),
+        index: nbit_num!(index, 12),
+        size: nbit_num!(0, 3 * 8),
+        flags: nbit_num!(0, 3),
+        md5_hash: None,
+        has_extra_info: false,
+        extra_info: None,
+    }
+}
+
+fn resource_list() -> SvcResourceList {
+    let resources = vec![
+        resource(ResourceType::Model, "maps/kz_longjumps2.bsp", 1),
+        resource(ResourceType::Model, "models/player/GIGN/gign.mdl", 2),
+        resource(ResourceType::Model, "models/player.mdl", 3),
+        resource(ResourceType::Sound, "player/pl_step1.wav", 40),
+    ];
+
+    SvcResourceList {
+        resource_count: nbit_num!(resources.len(), 12),
+        resources,
+        consistencies: vec![],
+    }
+}
+
+#[test]
+fn model_names() {
+    assert_eq!(model_path("arctic"), "models/player/arctic/arctic.mdl");
+    assert_eq!(model_path("models/ghost.mdl"), "models/ghost.mdl");
+}
+
+#[test]
+fn precached_or_added() {
+    let mut resource_list = resource_list();
+
+    let models = ["gign", "arctic", "models/ghost.mdl", "arctic"].map(str::to_owned);
+    let indices = precache_models(&mut resource_list, &models).unwrap();
+
+    // Sounds have their own indices.
+    assert_eq!(indices, vec![2, 4, 5, 4]);
+    assert_eq!(resource_list.resources.len(), 6);
+    assert_eq!(resource_list.resource_count.to_u16(), 6);
+}
@@ -0,0 +1,81 @@
+//! Nameplates are placed over the ghosts on the main demo screen.
+
+mod common;
+
+use demosuperimpose_goldsrc::demo_doer::superimpose::nameplate::{
+    nameplate_messages, parse_color, Nameplate, View, NAMEPLATE_CHANNELS,
+};
+use demosuperimpose_goldsrc::types::TempEntityEntity;
+
+use common::close;
+
+/// Looking down +x from the origin with a 4:3 screen.
+fn view() -> View {
+    View {
+        origin: [0.; 3],
+        forward: [1., 0., 0.],
+        right: [0., -1., 0.],
+        up: [0., 0., 1.],
+        fov: 90.,
+        width: 640.,
+        height: 480.,
+    }
+}
+
+fn nameplate(text: &str) -> Nameplate {
+    Nameplate {
+        text: text.to_owned(),
+        color: [255, 255, 255],
+    }
+}
+
+#[test]
+fn projects_onto_screen() {
+    let view = view();
+
+    let center = view.project([100., 0., 0.]).unwrap();
+    assert!(close(center[0], 0.5) && close(center[1], 0.5));
+
+    // Half way to the right edge at 90 fov.
+    let right = view.project([100., -50., 0.]).unwrap();
+    assert!(close(right[0], 0.75));
+
+    // Up is scaled by the aspect ratio.
+    let up = view.project([100., 0., 30.]).unwrap();
+    assert!(close(up[1], 0.5 - 0.5 * 0.3 * 640. / 480.));
+
+    assert_eq!(view.project([-100., 0., 0.]), None);
+    assert_eq!(view.project([100., 200., 0.]), None);
+}
+
+#[test]
+fn closest_ghosts_get_channels() {
+    let view = view();
+    let nameplates: Vec<_> = (0..6).map(|idx| nameplate(&idx.to_string())).collect();
+
+    // Further away the later it is, the last one is behind.
+    let ghosts = nameplates.iter().enumerate().map(|(idx, nameplate)| {
+        let distance = if idx == 0 { -100. } else { 100. * idx as f32 };
+        (nameplate, [distance, 0., 0.], false)
+    });
+
+    let messages = nameplate_messages(&view, ghosts.rev());
+    assert_eq!(messages.len(), NAMEPLATE_CHANNELS.len());
+
+    for (message, expected) in messages.iter().zip(["1\0", "2\0", "3\0", "4\0"]) {
+        match &message.entity {
+            TempEntityEntity::TeTextMessage(text) => {
+                assert_eq!(text.message, expected.as_bytes())
+            }
+            _ => panic!("not a text message"),
+        }
+    }
+}
+
+#[test]
+fn colors() {
+    assert_eq!(parse_color("ff8800"), Ok([255, 136, 0]));
+    assert_eq!(parse_color("#0a0B0c"), Ok([10, 11, 12]));
+    assert!(parse_color("ff88").is_err());
+    assert!(parse_color("gg8800").is_err());
+}
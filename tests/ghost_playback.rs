@@ -0,0 +1,114 @@
+//! Sampling ghosts at main demo times.
+
+mod common;
+
+use demosuperimpose_goldsrc::demo_doer::get_ghost::types::GhostInfo;
+use demosuperimpose_goldsrc::utils::lerp_angle;
+
+use common::close;
+
+/// Ghost at `frametime` seconds per frame.
+fn ghost(frametime: f32, frames: &[([f32; 3], [f32; 3])]) -> GhostInfo {
+    let mut ghost = GhostInfo::new();
+    ghost.use_frametime = true;
+
+    for (origin, viewangles) in frames {
+        ghost.append_frame_with_time(frametime, *origin, *viewangles, None, None, None, None);
+    }
+
+    ghost
+}
+
+#[test]
+fn playback_follows_time() {
+    let frames: Vec<_> = (0..10).map(|idx| ([idx as f32, 0., 0.], [0.; 3])).collect();
+    // 50 fps ghost.
+    let ghost = ghost(0.02, &frames);
+
+    // Sampled by a 100 fps main demo, every frame shows twice.
+    let shown: Vec<usize> = (0..20)
+        .map(|step| {
+            ghost
+                .get_frame_index_at(step as f32 * 0.01 + 0.001)
+                .unwrap()
+        })
+        .collect();
+    let expected: Vec<usize> = (0..20).map(|step| step / 2).collect();
+    assert_eq!(shown, expected);
+}
+
+#[test]
+fn origin_is_interpolated() {
+    let ghost = ghost(0.1, &[([0., 0., 0.], [0.; 3]), ([10., -20., 4.], [0.; 3])]);
+
+    let frame = ghost.get_frame_at(0.025).unwrap();
+    assert!(close(frame.origin[0], 2.5));
+    assert!(close(frame.origin[1], -5.));
+    assert!(close(frame.origin[2], 1.));
+
+    // Before the start and during the last frame hold still.
+    assert_eq!(ghost.get_frame_at(-1.).unwrap().origin, [0., 0., 0.]);
+    assert_eq!(ghost.get_frame_at(0.15).unwrap().origin, [10., -20., 4.]);
+    assert!(ghost.get_frame_at(0.5).is_none());
+}
+
+#[test]
+fn yaw_wraps_the_short_way() {
+    assert!(close(lerp_angle(170., -170., 0.5), 180.));
+    assert!(close(lerp_angle(170., -170., 0.75), -175.));
+    assert!(close(lerp_angle(-170., 170., 0.25), -175.));
+    assert!(close(lerp_angle(10., -10., 0.5), 0.));
+    assert!(close(lerp_angle(350., 10., 0.5), 0.));
+
+    let ghost = ghost(
+        0.1,
+        &[([0.; 3], [0., 179., 0.]), ([0.; 3], [0., -179., 0.])],
+    );
+    let yaw = ghost.get_frame_at(0.05).unwrap().viewangles[1];
+    assert!(close(yaw.abs(), 180.));
+}
+
+/// 50 fps ghost that switches from sequence 1 to 2 on frame 5, only saying so on the change.
+fn animated_ghost() -> GhostInfo {
+    let mut ghost = GhostInfo::new();
+    ghost.use_frametime = true;
+
+    for idx in 0..10 {
+        let sequence = match idx {
+            0 => Some(vec![1, 0, 0, 0]),
+            5 => Some(vec![2, 0, 0, 0]),
+            _ => None,
+        };
+        ghost.append_frame_with_time(0.02, [0.; 3], [0.; 3], sequence, None, None, None);
+    }
+
+    ghost
+}
+
+#[test]
+fn sequence_is_sent_once_per_change() {
+    let mut ghost = animated_ghost();
+
+    // 200 fps main demo sees every ghost frame several times.
+    let sent: Vec<Vec<u8>> = (0..40)
+        .filter_map(|step| ghost.animate(step as f32 * 0.005).0)
+        .collect();
+    assert_eq!(sent, vec![vec![1, 0, 0, 0], vec![2, 0, 0, 0]]);
+
+    // Frame counts from the change, not from how often it is asked.
+    let (sequence, frame) = animated_ghost().animate(0.13);
+    assert_eq!(sequence, Some(vec![2, 0, 0, 0]));
+    assert!(close(frame.unwrap(), 3.));
+}
+
+#[test]
+fn sequence_change_between_samples_is_kept() {
+    let mut ghost = animated_ghost();
+
+    // 10 fps main demo lands between the changes.
+    assert_eq!(ghost.animate(0.).0, Some(vec![1, 0, 0, 0]));
+    let (sequence, frame) = ghost.animate(0.15);
+    assert_eq!(sequence, Some(vec![2, 0, 0, 0]));
+    assert!(close(frame.unwrap(), 5.));
+    assert_eq!(ghost.animate(0.19).0, None);
+}
@@ -0,0 +1,189 @@
+//! Ghost format detection and forcing through [`GhostSources`].
+
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use demosuperimpose_goldsrc::demo_doer::get_ghost::{
+    demo_players::GhostPlayer,
+    source::{GhostSource, GhostSources},
+    types::GhostInfo,
+    GhostFile,
+};
+
+const ROMANIAN_JUMPERS: &str = r#"{"frames":[
+    {"position":[1,2,3],"orientation":[4,5],"length":0.01,"time":0.01,"buttons":0},
+    {"position":[2,3,4],"orientation":[5,6],"length":0.01,"time":0.02,"buttons":0}
+]}"#;
+
+const SURF_GATEWAY: &str = r#"{"name":"a","authid":"STEAM_0:0:1","time":1.0,"startvel":[0,0,0],
+"frames":[{"origin":[1,2,3],"viewangles":[4,5,6],"moves":[0,0,0],"buttons":0,"impulses":0,"frametime":0}]}"#;
+
+fn temp_file(name: &str, text: &str) -> PathBuf {
+    let path = common::temp_path(name);
+    fs::write(&path, text).unwrap();
+    path
+}
+
+fn detected(sources: &GhostSources, path: &Path) -> String {
+    sources.detect(path).unwrap().name().to_owned()
+}
+
+#[test]
+fn detects_by_extension() {
+    let sources = GhostSources::new();
+
+    let rj = temp_file("run.rj.json", ROMANIAN_JUMPERS);
+    let sg = temp_file("run.sg.json", SURF_GATEWAY);
+
+    assert_eq!(detected(&sources, &rj), "romanian-jumpers");
+    assert_eq!(detected(&sources, &sg), "surf-gateway");
+}
+
+#[test]
+fn extension_wins_over_content() {
+    let sources = GhostSources::new();
+
+    // Surf Gateway's key, but named Romanian-Jumpers.
+    let rj = temp_file("authid.rj.json", SURF_GATEWAY);
+
+    assert_eq!(detected(&sources, &rj), "romanian-jumpers");
+}
+
+#[test]
+fn detects_by_content() {
+    let sources = GhostSources::new();
+
+    let rj = temp_file("renamed-rj.json", ROMANIAN_JUMPERS);
+    let sg = temp_file("renamed-sg.json", SURF_GATEWAY);
+
+    assert_eq!(detected(&sources, &rj), "romanian-jumpers");
+    assert_eq!(detected(&sources, &sg), "surf-gateway");
+
+    let ghost = sources.get_ghost(&rj, 0., None).unwrap();
+    assert_eq!(ghost.frames.len(), 2);
+    assert_eq!(ghost.frames[1].origin, [2., 3., 4.]);
+}
+
+#[test]
+fn unknown_file_is_an_error() {
+    let sources = GhostSources::new();
+    let path = temp_file("mystery.txt", "nothing to see");
+
+    assert!(sources.get_ghost(&path, 0., None).is_err());
+}
+
+#[test]
+fn forced_format() {
+    let sources = GhostSources::new();
+    let path = temp_file("forced.txt", ROMANIAN_JUMPERS);
+
+    let ghost = sources
+        .get_ghost(&path, 0., Some("romanian-jumpers"))
+        .unwrap();
+    assert_eq!(ghost.frames.len(), 2);
+
+    // Wrong format reports instead of panicking.
+    assert!(sources.get_ghost(&path, 0., Some("surf-gateway")).is_err());
+
+    let err = sources.get_ghost(&path, 0., Some("nope")).unwrap_err();
+    assert!(err.contains("romanian-jumpers"));
+}
+
+struct OneFrame;
+
+impl GhostSource for OneFrame {
+    fn name(&self) -> &str {
+        "one-frame"
+    }
+
+    fn detect(&self, _path: &Path, start: &[u8]) -> bool {
+        start.starts_with(b"ONE FRAME")
+    }
+
+    fn parse(&self, path: &Path, _offset: f32) -> Result<GhostInfo, String> {
+        let mut ghost = GhostInfo::new();
+        ghost.set_name(path.display().to_string());
+        ghost.append_frame([1., 1., 1.], [0.; 3], None, None, None, None);
+        Ok(ghost)
+    }
+}
+
+#[test]
+fn downstream_source() {
+    let mut sources = GhostSources::new();
+    sources.add(OneFrame);
+
+    let path = temp_file("custom.ghost", "ONE FRAME");
+
+    assert_eq!(detected(&sources, &path), "one-frame");
+    assert_eq!(sources.get_ghost(&path, 0., None).unwrap().frames.len(), 1);
+    assert!(sources.names().contains(&"one-frame"));
+}
+
+#[test]
+fn offset() {
+    let sources = GhostSources::new();
+    let path = temp_file("offset.rj.json", ROMANIAN_JUMPERS);
+
+    let origin_at = |ghost: &GhostInfo, time: f32| {
+        ghost
+            .get_frame_index_at(time)
+            .map(|idx| ghost.get_frame(idx).origin)
+    };
+
+    // Holds the first frame until it starts.
+    let later = sources.get_ghost(&path, 0.05, None).unwrap();
+    assert_eq!(origin_at(&later, 0.), Some([1., 2., 3.]));
+    assert_eq!(origin_at(&later, 0.055), Some([1., 2., 3.]));
+    assert_eq!(origin_at(&later, 0.065), Some([2., 3., 4.]));
+    assert_eq!(origin_at(&later, 1.), None);
+
+    let earlier = sources.get_ghost(&path, -0.005, None).unwrap();
+    assert_eq!(origin_at(&earlier, 0.), Some([1., 2., 3.]));
+    assert_eq!(origin_at(&earlier, 0.006), Some([2., 3., 4.]));
+
+    // Halfway between the two frames is the start.
+    let unmoved = sources.get_ghost(&path, 0., None).unwrap();
+    let (first, second) = (unmoved.get_frame(0).time, unmoved.get_frame(1).time);
+    let halfway = sources
+        .get_ghost(&path, -(first + second) / 2., None)
+        .unwrap();
+    assert!(common::close(
+        halfway.get_frame(0).time,
+        (first - second) / 2.
+    ));
+    let origin = halfway.get_frame_at(0.).unwrap().origin;
+    assert!(origin
+        .iter()
+        .zip([1.5, 2.5, 3.5])
+        .all(|(a, b)| common::close(*a, b)));
+
+    let gone = sources.get_ghost(&path, -1., None).unwrap();
+    assert_eq!(origin_at(&gone, 0.), None);
+}
+
+#[test]
+fn player_from_text() {
+    assert_eq!("recorder".parse(), Ok(GhostPlayer::Recorder));
+    assert_eq!("all".parse(), Ok(GhostPlayer::All));
+    assert_eq!("3".parse(), Ok(GhostPlayer::Entity(3)));
+    assert_eq!("jeb".parse(), Ok(GhostPlayer::Name("jeb".to_owned())));
+    assert!("0".parse::<GhostPlayer>().is_err());
+    assert!("".parse::<GhostPlayer>().is_err());
+}
+
+#[test]
+fn player_needs_demo() {
+    let sources = GhostSources::new();
+    let path = temp_file("player.rj.json", ROMANIAN_JUMPERS);
+
+    let recorder = sources
+        .get_ghosts(&[GhostFile::new(&path, 0.)], None)
+        .unwrap();
+    assert_eq!(recorder.len(), 1);
+
+    let all = GhostFile::with_player(&path, 0., GhostPlayer::All);
+    assert!(sources.get_ghosts(&[all], None).is_err());
+}
@@ -0,0 +1,92 @@
+//! Ghost styles end up in the entity delta.
+
+use std::path::Path;
+
+use demosuperimpose_goldsrc::demo_doer::job::Job;
+use demosuperimpose_goldsrc::demo_doer::superimpose::style::{GhostStyle, RENDER_FX_GLOW_SHELL};
+use demosuperimpose_goldsrc::types::Delta;
+
+#[test]
+fn style_from_text() {
+    let style: GhostStyle = "rendermode=5,renderamt=120,rendercolor=ff8800,renderfx=19,scale=1.5"
+        .parse()
+        .unwrap();
+
+    assert_eq!(
+        style,
+        GhostStyle {
+            rendermode: Some(5),
+            renderamt: Some(120),
+            rendercolor: Some([255, 136, 0]),
+            renderfx: Some(RENDER_FX_GLOW_SHELL),
+            scale: Some(1.5),
+        }
+    );
+
+    assert_eq!("".parse::<GhostStyle>(), Ok(GhostStyle::default()));
+    assert!("rendermode".parse::<GhostStyle>().is_err());
+    assert!("alpha=3".parse::<GhostStyle>().is_err());
+    assert!("renderamt=lots".parse::<GhostStyle>().is_err());
+}
+
+#[test]
+fn style_out_of_range() {
+    for text in [
+        "rendermode=6",
+        "rendermode=-1",
+        "renderamt=256",
+        "renderfx=21",
+        "scale=0",
+        "scale=-1",
+        "scale=inf",
+        "scale=NaN",
+    ] {
+        assert!(text.parse::<GhostStyle>().is_err(), "{}", text);
+    }
+
+    assert!("rendermode=0,renderamt=255,renderfx=20,scale=0.01"
+        .parse::<GhostStyle>()
+        .is_ok());
+}
+
+#[test]
+fn style_into_delta() {
+    let mut delta = Delta::new();
+    delta.insert("rendermode\0".to_owned(), 0i32.to_le_bytes().to_vec());
+    delta.insert("renderfx\0".to_owned(), 0i32.to_le_bytes().to_vec());
+
+    GhostStyle {
+        rendermode: Some(2),
+        rendercolor: Some([1, 2, 3]),
+        scale: Some(0.5),
+        ..Default::default()
+    }
+    .apply(&mut delta);
+
+    assert_eq!(delta["rendermode\0"], 2i32.to_le_bytes());
+    assert_eq!(delta["rendercolor.g\0"], 2i32.to_le_bytes());
+    assert_eq!(delta["scale\0"], 0.5f32.to_le_bytes());
+    // Not given so the player's stays.
+    assert_eq!(delta["renderfx\0"], 0i32.to_le_bytes());
+    assert!(!delta.contains_key("renderamt\0"));
+}
+
+#[test]
+fn job_style_out_of_range() {
+    let text = r#"
+        demo = "example/hldm.dem"
+        output = "out.dem"
+
+        [[ghosts]]
+        path = "example/hldm.dem"
+
+        [ghosts.style]
+        renderamt = 300
+    "#;
+
+    let mut job = Job::from_toml(text).unwrap();
+    job.relative_to(Path::new(env!("CARGO_MANIFEST_DIR")));
+
+    let err = job.check().unwrap_err();
+    assert!(err.contains("renderamt 300"), "{}", err);
+}
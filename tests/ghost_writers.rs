@@ -0,0 +1,226 @@
+//! Every writable ghost format reads back what was written.
+
+mod common;
+
+use std::fs;
+
+use demosuperimpose_goldsrc::demo_doer::get_ghost::{
+    source::GhostSources,
+    types::{GhostInfo, GhostMeta, GhostMovement},
+};
+use demosuperimpose_goldsrc::utils::delta_int;
+
+use common::{close, temp_path};
+
+/// 100 fps like server frames so formats without time still line up.
+fn ghost() -> GhostInfo {
+    let mut ghost = GhostInfo::new();
+    ghost.set_name("writer".to_owned());
+    ghost.use_frametime = true;
+
+    for idx in 0..5 {
+        let idx = idx as f32;
+        ghost.append_frame_with_time(
+            0.01,
+            [100. + idx, -200. - idx * 2., 36.5],
+            [-12.5 + idx, 170. + idx * 3., 0.],
+            None,
+            None,
+            None,
+            Some(idx as u32),
+        );
+    }
+
+    ghost
+}
+
+fn check(name: &str) {
+    let sources = GhostSources::new();
+    let ghost = ghost();
+    let path = temp_path(name);
+
+    sources.write_ghost(&ghost, &path, None).unwrap();
+    let back = sources.get_ghost(&path, 0., None).unwrap();
+
+    assert_eq!(back.get_size(), ghost.get_size(), "{}", name);
+
+    for (a, b) in ghost.frames.iter().zip(&back.frames) {
+        assert!(
+            close(a.time, b.time),
+            "{}: time {} {}",
+            name,
+            a.time,
+            b.time
+        );
+
+        for i in 0..3 {
+            assert!(close(a.origin[i], b.origin[i]), "{}: origin", name);
+        }
+
+        // Romanian-Jumpers has no roll.
+        for i in 0..2 {
+            assert!(
+                close(a.viewangles[i], b.viewangles[i]),
+                "{}: viewangles",
+                name
+            );
+        }
+
+        assert_eq!(a.buttons, b.buttons, "{}: buttons", name);
+    }
+}
+
+#[test]
+fn native() {
+    check("writer.ghost.json");
+}
+
+#[test]
+fn native_binary() {
+    check("writer.ghost");
+}
+
+/// [`ghost`] with everything our own format keeps.
+fn full_ghost() -> GhostInfo {
+    let mut ghost = ghost();
+    ghost.meta = GhostMeta {
+        map_name: Some("kz_longjumps2".to_owned()),
+        map_crc: Some(0xdeadbeef),
+        player_name: Some("jumper".to_owned()),
+        steam_id: None,
+        player_entity: Some(3),
+    };
+
+    for (idx, frame) in ghost.frames.iter_mut().enumerate() {
+        frame.sequence = Some((idx as i32).to_le_bytes().to_vec());
+        frame.frame = (idx % 2 == 0).then(|| (idx as f32 * 25.).to_le_bytes().to_vec());
+        frame.movement = GhostMovement {
+            velocity: Some([250., -idx as f32, 0.]),
+            movetype: Some(3),
+            flags: if idx == 0 { None } else { Some(1 << 9) },
+            onground: Some(idx != 2),
+            ducking: Some(idx == 4),
+            fov: Some(90.),
+            weapon: Some(29),
+            health: Some(100. - idx as f32),
+        };
+    }
+
+    ghost
+}
+
+#[test]
+fn native_keeps_everything() {
+    let sources = GhostSources::new();
+    let ghost = full_ghost();
+
+    for name in ["full.ghost.json", "full.ghost"] {
+        let path = temp_path(name);
+
+        sources.write_ghost(&ghost, &path, None).unwrap();
+        let back = sources.get_ghost(&path, 0., None).unwrap();
+
+        assert_eq!(back.meta, ghost.meta, "{}", name);
+        assert_eq!(back.get_name(), ghost.get_name(), "{}", name);
+
+        for (a, b) in ghost.frames.iter().zip(&back.frames) {
+            assert_eq!(a.movement, b.movement, "{}", name);
+            assert_eq!(a.sequence, b.sequence, "{}", name);
+            assert_eq!(a.frame, b.frame, "{}", name);
+            assert_eq!(a.animtime, b.animtime, "{}", name);
+        }
+    }
+
+    // Binary is the smaller one.
+    let json = fs::metadata(temp_path("full.ghost.json")).unwrap().len();
+    let binary = fs::metadata(temp_path("full.ghost")).unwrap().len();
+    assert!(binary < json);
+}
+
+#[test]
+fn native_rejects_other_versions() {
+    let path = temp_path("future.ghost.json");
+    fs::write(
+        &path,
+        r#"{"format":"demosuperimpose-ghost","version":99,"name":"","frames":[]}"#,
+    )
+    .unwrap();
+
+    let err = GhostSources::new().get_ghost(&path, 0., None).unwrap_err();
+    assert!(err.contains("version 99"));
+}
+
+#[test]
+fn native_reads_version_1() {
+    let path = temp_path("old.ghost.json");
+    fs::write(
+        &path,
+        r#"{"format":"demosuperimpose-ghost","version":1,"name":"old","frames":[
+            {"time":0.0,"frametime":0.01,"origin":[1,2,3],"viewangles":[4,5,6],"sequence":[7,0,0,0],"buttons":2},
+            {"time":0.01,"frametime":0.01,"origin":[2,3,4],"viewangles":[5,6,7]}
+        ]}"#,
+    )
+    .unwrap();
+
+    let ghost = GhostSources::new().get_ghost(&path, 0., None).unwrap();
+
+    assert_eq!(ghost.get_name(), "old");
+    assert_eq!(ghost.frames.len(), 2);
+    assert_eq!(ghost.frames[0].origin, [1., 2., 3.]);
+    assert_eq!(ghost.frames[0].sequence, Some(vec![7, 0, 0, 0]));
+    assert_eq!(ghost.frames[0].buttons, Some(2));
+    assert_eq!(ghost.frames[1].sequence, None);
+}
+
+#[test]
+fn short_delta_ints_are_signed() {
+    assert_eq!(delta_int(&[]), 0);
+    assert_eq!(delta_int(&[7]), 7);
+    assert_eq!(delta_int(&[0xff]), -1);
+    assert_eq!(delta_int(&[0x80]), -128);
+    assert_eq!(delta_int(&[0x34, 0x12]), 0x1234);
+    assert_eq!(delta_int(&[0xfe, 0xff]), -2);
+    assert_eq!(delta_int(&[0xff, 0xff, 0xff]), -1);
+    assert_eq!(delta_int(&[0xff, 0xff, 0xff, 0x7f]), i32::MAX);
+
+    // Version 1 kept the delta bytes as they were.
+    let path = temp_path("short.ghost.json");
+    fs::write(
+        &path,
+        r#"{"format":"demosuperimpose-ghost","version":1,"name":"short","frames":[
+            {"time":0.0,"frametime":0.01,"origin":[0,0,0],"viewangles":[0,0,0],"sequence":[255]}
+        ]}"#,
+    )
+    .unwrap();
+
+    let ghost = GhostSources::new().get_ghost(&path, 0., None).unwrap();
+    assert_eq!(
+        ghost.frames[0].sequence,
+        Some((-1i32).to_le_bytes().to_vec())
+    );
+}
+
+#[test]
+fn simen() {
+    check("writer.simen.txt");
+}
+
+#[test]
+fn surf_gateway() {
+    check("writer.sg.json");
+}
+
+#[test]
+fn romanian_jumpers() {
+    check("writer.rj.json");
+}
+
+#[test]
+fn unknown_output() {
+    let sources = GhostSources::new();
+    let path = temp_path("writer.txt");
+
+    assert!(sources.write_ghost(&ghost(), &path, None).is_err());
+    // Demos can be read but not written.
+    assert!(sources.write_ghost(&ghost(), &path, Some("demo")).is_err());
+}
@@ -0,0 +1,64 @@
+//! Info JSON has the header, every entry and a count of every message.
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+
+use demosuperimpose_goldsrc::demo_doer::{bytes_to_string, info::info, message_name};
+
+use common::{hldm, parsed_netmsgs};
+
+#[test]
+fn json_has_header_and_entries() {
+    let demo = hldm();
+    let json: Value = serde_json::from_str(&info(&demo).to_json()).unwrap();
+
+    assert_eq!(json["demo_protocol"], json!(demo.header.demo_protocol));
+    assert_eq!(json["net_protocol"], json!(demo.header.net_protocol));
+    assert_eq!(
+        json["map_name"],
+        json!(bytes_to_string(demo.header.map_name))
+    );
+    assert_eq!(json["map_crc"], json!(demo.header.map_crc));
+    assert_eq!(
+        json["game_dir"],
+        json!(bytes_to_string(demo.header.game_dir))
+    );
+
+    let entries = json["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), demo.directory.entries.len());
+
+    for (entry, json) in demo.directory.entries.iter().zip(entries) {
+        assert_eq!(json["entry_type"], json!(entry.entry_type));
+        assert_eq!(
+            json["description"],
+            json!(bytes_to_string(entry.description))
+        );
+        assert_eq!(json["frame_count"], json!(entry.frame_count));
+        assert_eq!(json["frames"], json!(entry.frames.len()));
+    }
+
+    // A recorded server always says who it is.
+    assert!(json["hostname"].is_string());
+    assert!(json["max_players"].is_u64());
+    assert!(json["average_fps"].as_f64().unwrap() > 0.);
+    assert!(json["resource_count"].as_u64().unwrap() > 0);
+    assert!(!json["players"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn json_counts_every_message() {
+    let demo = hldm();
+    let json: Value = serde_json::from_str(&info(&demo).to_json()).unwrap();
+
+    let mut messages = BTreeMap::<String, usize>::new();
+    for (_, _, netmsg) in parsed_netmsgs(&demo) {
+        for message in &netmsg {
+            *messages.entry(message_name(message)).or_default() += 1;
+        }
+    }
+
+    assert_eq!(json["messages"], json!(messages));
+}
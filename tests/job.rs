@@ -0,0 +1,110 @@
+//! The same job as TOML and as JSON reads the same and makes the same demo.
+
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use demosuperimpose_goldsrc::demo_doer::job::Job;
+use demosuperimpose_goldsrc::demo_doer::{message_name, open_demo_file};
+
+use common::{hldm, parsed_netmsgs, temp_path};
+
+fn example() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("example/hldm.dem")
+}
+
+fn toml_job(output: &Path) -> String {
+    format!(
+        r#"
+        demo = {demo:?}
+        output = {output:?}
+        remove_entities = [2]
+
+        [[ghosts]]
+        path = {demo:?}
+        offset = 0.5
+        label = "again"
+
+        [kz_stats]
+        keys = true
+        "#,
+        demo = example(),
+        output = output,
+    )
+}
+
+fn json_job(output: &Path) -> String {
+    format!(
+        r#"{{
+            "demo": {demo:?},
+            "output": {output:?},
+            "remove_entities": [2],
+            "ghosts": [{{ "path": {demo:?}, "offset": 0.5, "label": "again" }}],
+            "kz_stats": {{ "keys": true }}
+        }}"#,
+        demo = example(),
+        output = output,
+    )
+}
+
+fn temp_entity_count(path: &Path) -> usize {
+    parsed_netmsgs(&open_demo_file(path).unwrap())
+        .iter()
+        .flat_map(|(_, _, messages)| messages)
+        .filter(|message| message_name(message) == "SvcTempEntity")
+        .count()
+}
+
+#[test]
+fn toml_and_json_read_the_same() {
+    let output = temp_path("parsed.dem");
+    let toml = Job::from_toml(&toml_job(&output)).unwrap();
+    let json = Job::from_json(&json_job(&output)).unwrap();
+
+    assert_eq!(format!("{:?}", toml), format!("{:?}", json));
+    assert_eq!(toml.ghosts[0].offset, 0.5);
+    assert_eq!(toml.ghosts[0].label.as_deref(), Some("again"));
+    assert!(toml.kz_stats.keys && !toml.kz_stats.speedometer);
+}
+
+#[test]
+fn toml_and_json_run_the_same() {
+    let toml_output = temp_path("toml.dem");
+    let json_output = temp_path("json.dem");
+
+    let toml_path = temp_path("job.toml");
+    let json_path = temp_path("job.json");
+    fs::write(&toml_path, toml_job(&toml_output)).unwrap();
+    fs::write(&json_path, json_job(&json_output)).unwrap();
+
+    Job::open(&toml_path).unwrap().run(|_| ()).unwrap();
+    Job::open(&json_path).unwrap().run(|_| ()).unwrap();
+
+    assert!(fs::read(&toml_output).unwrap() == fs::read(&json_output).unwrap());
+
+    let output = open_demo_file(&toml_output).unwrap();
+    assert_eq!(
+        output.directory.entries.len(),
+        hldm().directory.entries.len()
+    );
+
+    // Keys are drawn with temp entities.
+    assert!(temp_entity_count(&toml_output) > temp_entity_count(&example()));
+}
+
+#[test]
+fn missing_demo_is_an_error() {
+    let job_path = temp_path("missing.toml");
+    fs::write(
+        &job_path,
+        r#"
+        demo = "nowhere.dem"
+        output = "out.dem"
+        "#,
+    )
+    .unwrap();
+
+    let err = Job::open(&job_path).unwrap_err();
+    assert!(err.contains("nowhere.dem"), "{}", err);
+}
@@ -0,0 +1,79 @@
+//! Demos go to JSON and back without losing anything, and the JSON is readable.
+
+use std::path::Path;
+
+use hldemo::{Demo, FrameData};
+use serde_json::Value;
+
+use demosuperimpose_goldsrc::demo_doer::{
+    json::{export, import},
+    open_demo_file,
+};
+
+fn hldm<'a>() -> Demo<'a> {
+    open_demo_file(Path::new(env!("CARGO_MANIFEST_DIR")).join("example/hldm.dem")).unwrap()
+}
+
+fn netmsgs<'a>(demo: &Demo<'a>) -> Vec<&'a [u8]> {
+    demo.directory
+        .entries
+        .iter()
+        .flat_map(|entry| entry.frames.iter())
+        .filter_map(|frame| match &frame.data {
+            FrameData::NetMsg((_, data)) => Some(data.msg),
+            _ => None,
+        })
+        .collect()
+}
+
+/// First value under `key` anywhere in `value`.
+fn find<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    match value {
+        Value::Object(object) => object
+            .get(key)
+            .or_else(|| object.values().find_map(|value| find(value, key))),
+        Value::Array(values) => values.iter().find_map(|value| find(value, key)),
+        _ => None,
+    }
+}
+
+#[test]
+fn export_import_keeps_netmsg() {
+    let demo = hldm();
+    let json = export(&demo).unwrap();
+    let imported = import(&json).unwrap();
+
+    assert_eq!(demo.header.map_name, imported.header.map_name);
+    assert_eq!(netmsgs(&demo), netmsgs(&imported));
+
+    // Same JSON every time, deltas don't come out in hash map order.
+    assert_eq!(json, export(&imported).unwrap());
+}
+
+#[test]
+fn export_is_readable() {
+    let json: Value = serde_json::from_str(&export(&hldm()).unwrap()).unwrap();
+
+    assert!(json["header"]["map_name"].is_string());
+
+    let baseline = &find(&json, "SvcSpawnBaseline").unwrap()["entities"][0];
+
+    // [bool; 11] as bits, last bit first.
+    let index = baseline["index"].as_str().unwrap();
+    assert_eq!(index.len(), 11);
+    assert_eq!(
+        u32::from_str_radix(index, 2).unwrap(),
+        baseline["entity_index"].as_u64().unwrap() as u32
+    );
+
+    // Delta fields are decoded and sorted.
+    let delta = baseline["delta"].as_object().unwrap();
+    assert!(delta.values().all(|value| !value.is_array()));
+    assert!(delta.keys().zip(delta.keys().skip(1)).all(|(a, b)| a < b));
+}
+
+#[test]
+fn bad_json_is_an_error() {
+    assert!(import("{").is_err());
+    assert!(import(r#"{"header": 1, "entries": []}"#).is_err());
+}
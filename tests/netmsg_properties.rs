@@ -0,0 +1,1430 @@
+//! Every message has to parse back to what was written, `parse(write(x)) == x`.
+//!
+//! Messages are generated field by field within what their types can carry. Deltas are generated
+//! as raw bits within the widths of `DeltaDecoderS` and parsed, so they are always deltas a demo
+//! could have.
+
+mod common;
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::strategy::BoxedStrategy;
+
+use demosuperimpose_goldsrc::get_cs_delta_decoder_table;
+use demosuperimpose_goldsrc::netmsg_doer::{
+    parse_single_netmsg,
+    utils::{get_initial_delta, parse_delta, BitReader},
+    write_single_netmsg,
+};
+use demosuperimpose_goldsrc::types::*;
+use demosuperimpose_goldsrc::writer::BitWriter;
+
+/// Entities from 1 to this are players.
+const MAX_CLIENT: u8 = 32;
+
+// Max client is global so cases can't run at the same time.
+static LOCK: Mutex<()> = Mutex::new(());
+
+type CustomMessages = HashMap<u8, SvcNewUserMsg<'static>>;
+
+fn delta_decoders() -> &'static DeltaDecoderTable {
+    static TABLE: OnceLock<DeltaDecoderTable> = OnceLock::new();
+
+    TABLE.get_or_init(|| {
+        let mut table = get_cs_delta_decoder_table!();
+        // Delta descriptions are read with the one the engine starts with.
+        table.extend(get_initial_delta());
+        table
+    })
+}
+
+/// Max client is only set by parsing a server info.
+fn set_max_client(max_client: u8) {
+    let mut bytes = vec![EngineMessageType::SvcServerInfo as u8];
+    bytes.extend([0u8; 12]);
+    bytes.extend([0u8; 16]);
+    bytes.extend([max_client, 0, 0]);
+    bytes.extend([0u8; 4]);
+    bytes.push(0);
+
+    let bytes: &'static [u8] = bytes.leak();
+    parse_single_netmsg(
+        bytes,
+        &mut DeltaDecoderTable::new(),
+        &mut CustomMessages::new(),
+    )
+    .unwrap();
+}
+
+/// Writes the message, parses it back and compares.
+fn round_trip(
+    message: Message<'static>,
+    custom_messages: &CustomMessages,
+) -> Result<(), TestCaseError> {
+    let _lock = LOCK.lock().unwrap_or_else(|err| err.into_inner());
+    set_max_client(MAX_CLIENT);
+
+    let mut delta_decoders = delta_decoders().clone();
+    let mut parse_custom_messages = custom_messages.clone();
+
+    let bytes: &'static [u8] =
+        write_single_netmsg(message.clone(), &delta_decoders, custom_messages).leak();
+    let (rest, parsed) =
+        parse_single_netmsg(bytes, &mut delta_decoders, &mut parse_custom_messages)
+            .map_err(|err| TestCaseError::fail(format!("{:?} from {:?}", err, bytes)))?;
+
+    prop_assert!(rest.is_empty(), "{} bytes left of {:?}", rest.len(), bytes);
+    prop_assert_eq!(common::comparable(&message), common::comparable(&parsed));
+
+    Ok(())
+}
+
+fn engine(message: EngineMessage<'static>) -> Message<'static> {
+    Message::EngineMessage(message)
+}
+
+fn to_bits(value: u64, width: usize) -> BitType {
+    (0..width).map(|bit| value >> bit & 1 == 1).collect()
+}
+
+fn bytes_to_bits(bytes: &[u8]) -> BitType {
+    bytes
+        .iter()
+        .flat_map(|byte| (0..8).map(move |bit| byte >> bit & 1 == 1))
+        .collect()
+}
+
+fn bits(width: usize) -> impl Strategy<Value = BitType> {
+    vec(any::<bool>(), width).prop_map(|bits| bits.into_iter().collect())
+}
+
+fn bytes(len: usize) -> impl Strategy<Value = &'static [u8]> {
+    vec(any::<u8>(), len).prop_map(|bytes| &*bytes.leak())
+}
+
+/// Like `null_string` reads, nul terminated without a nul before.
+fn string_vec() -> impl Strategy<Value = Vec<u8>> {
+    vec(1u8..=255, 0..24).prop_map(|mut string| {
+        string.push(0);
+        string
+    })
+}
+
+fn string() -> impl Strategy<Value = &'static [u8]> {
+    string_vec().prop_map(|string| &*string.leak())
+}
+
+/// Raw bits of one delta field.
+fn delta_field(description: &DeltaDecoderS) -> BoxedStrategy<BitType> {
+    let has = |flag: DeltaType| description.flags & flag as u32 != 0;
+    let is_float =
+        has(DeltaType::Float) || has(DeltaType::TimeWindow8) || has(DeltaType::TimeWindowBig);
+    let is_signed = has(DeltaType::Signed) && !has(DeltaType::Angle);
+
+    if has(DeltaType::String) {
+        return string_vec()
+            .prop_map(|string| bytes_to_bits(&string))
+            .boxed();
+    }
+
+    let width = description.bits as usize - is_signed as usize;
+    // Floats are divided into f32, past 20 bits they don't make it back to the same integer.
+    let value_width = if is_float { width.min(20) } else { width };
+
+    (any::<bool>(), 0..1u64 << value_width)
+        .prop_map(move |(is_negative, value)| {
+            let mut res = BitType::new();
+            if is_signed {
+                res.push(is_negative);
+            }
+            res.extend_from_bitslice(&to_bits(value, width));
+            res
+        })
+        .boxed()
+}
+
+/// Delta as `parse_delta` reads it off the wire.
+fn delta(decoder_name: &'static str) -> impl Strategy<Value = Delta> {
+    let decoder = &delta_decoders()[decoder_name];
+    // Mask byte count is 3 bits.
+    let fields: Vec<_> = decoder
+        .iter()
+        .take(7 * 8)
+        .map(|description| proptest::option::of(delta_field(description)))
+        .collect();
+
+    fields.prop_map(move |fields| parse_raw_delta(decoder, &fields))
+}
+
+fn parse_raw_delta(decoder: &DeltaDecoder, fields: &[Option<BitType>]) -> Delta {
+    let mut bw = BitWriter::new();
+    write_raw_delta(fields, &mut bw);
+
+    let bytes = bw.get_u8_vec();
+    parse_delta(&bytes, decoder, &mut BitReader::new(&bytes)).unwrap()
+}
+
+fn write_raw_delta(fields: &[Option<BitType>], bw: &mut BitWriter) {
+    let mask_byte_count = fields
+        .iter()
+        .rposition(|field| field.is_some())
+        .map_or(0, |index| index / 8 + 1);
+
+    bw.append_u32_range(mask_byte_count as u32, 3);
+    for mask_byte in 0..mask_byte_count {
+        let mask = (0..8)
+            .filter(|bit| matches!(fields.get(mask_byte * 8 + bit), Some(Some(_))))
+            .fold(0u8, |mask, bit| mask | 1 << bit);
+        bw.append_u8(mask);
+    }
+
+    for field in fields.iter().flatten() {
+        bw.append_slice(field);
+    }
+}
+
+fn entity_decoder(entity_index: u16, has_custom_delta: bool) -> &'static str {
+    if entity_index > 0 && entity_index <= MAX_CLIENT as u16 {
+        "entity_state_player_t\0"
+    } else if has_custom_delta {
+        "custom_entity_state_t\0"
+    } else {
+        "entity_state_t\0"
+    }
+}
+
+/// How the next entity index is found.
+#[derive(Clone, Copy, Debug)]
+enum EntityStep {
+    Increment,
+    Absolute(u16),
+    // Never 0, 16 bits of 0 ends the list.
+    Difference(u16),
+}
+
+fn entity_step() -> impl Strategy<Value = EntityStep> {
+    prop_oneof![
+        Just(EntityStep::Increment),
+        (0u16..2048).prop_map(EntityStep::Absolute),
+        (1u16..64).prop_map(EntityStep::Difference),
+    ]
+}
+
+fn origin_coord() -> impl Strategy<Value = OriginCoord> {
+    (
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+        bits(12),
+        bits(3),
+    )
+        .prop_map(
+            |(int_flag, fraction_flag, is_negative, int_value, fraction_value)| OriginCoord {
+                int_flag,
+                fraction_flag,
+                is_negative: (int_flag || fraction_flag).then_some(is_negative),
+                int_value: int_flag.then_some(int_value),
+                fraction_value: fraction_flag.then_some(fraction_value),
+            },
+        )
+}
+
+fn svc_event() -> impl Strategy<Value = Message<'static>> {
+    let event = (
+        bits(10),
+        any::<bool>(),
+        bits(11),
+        proptest::option::of(delta("event_t\0")),
+        proptest::option::of(bits(16)),
+    )
+        .prop_map(
+            |(event_index, has_packet_index, packet_index, delta, fire_time)| EventS {
+                event_index,
+                has_packet_index,
+                packet_index: has_packet_index.then_some(packet_index),
+                has_delta: has_packet_index.then_some(delta.is_some()),
+                delta: if has_packet_index { delta } else { None },
+                has_fire_time: fire_time.is_some(),
+                fire_time,
+            },
+        );
+
+    vec(event, 0..8).prop_map(|events| {
+        engine(EngineMessage::SvcEvent(SvcEvent {
+            event_count: to_bits(events.len() as u64, 5),
+            events,
+        }))
+    })
+}
+
+fn svc_sound() -> impl Strategy<Value = Message<'static>> {
+    (
+        (bits(9), bits(8), bits(8), bits(3), bits(11)),
+        (bits(16), bits(8), bits(8)),
+        (
+            proptest::option::of(origin_coord()),
+            proptest::option::of(origin_coord()),
+            proptest::option::of(origin_coord()),
+        ),
+    )
+        .prop_map(
+            |(
+                (flags, volume, attenuation, channel, entity_index),
+                (sound_index_long, sound_index_short, pitch),
+                (origin_x, origin_y, origin_z),
+            )| {
+                let flag_u = flags
+                    .iter()
+                    .rev()
+                    .fold(0, |res, bit| res << 1 | *bit as u16);
+                let is_long = flag_u & 4 != 0;
+
+                engine(EngineMessage::SvcSound(SvcSound {
+                    volume: (flag_u & 1 != 0).then_some(volume),
+                    attenuation: (flag_u & 2 != 0).then_some(attenuation),
+                    channel,
+                    entity_index,
+                    sound_index_long: is_long.then_some(sound_index_long),
+                    sound_index_short: (!is_long).then_some(sound_index_short),
+                    has_x: origin_x.is_some(),
+                    has_y: origin_y.is_some(),
+                    has_z: origin_z.is_some(),
+                    origin_x,
+                    origin_y,
+                    origin_z,
+                    // What parsing fills in without the pitch flag.
+                    pitch: if flag_u & 8 != 0 {
+                        pitch
+                    } else {
+                        to_bits(1, 8)
+                    },
+                    flags,
+                }))
+            },
+        )
+}
+
+fn svc_server_info() -> impl Strategy<Value = Message<'static>> {
+    (
+        (any::<i32>(), any::<i32>(), any::<i32>(), bytes(16)),
+        (any::<u8>(), any::<u8>(), any::<u8>()),
+        (string(), string(), string(), string(), any::<u8>()),
+    )
+        .prop_map(
+            |(
+                (protocol, spawn_count, map_checksum, client_dll_hash),
+                (max_players, player_index, is_deathmatch),
+                (game_dir, hostname, map_file_name, map_cycle, unknown),
+            )| {
+                engine(EngineMessage::SvcServerInfo(SvcServerInfo {
+                    protocol,
+                    spawn_count,
+                    map_checksum,
+                    client_dll_hash,
+                    max_players,
+                    player_index,
+                    is_deathmatch,
+                    game_dir,
+                    hostname,
+                    map_file_name,
+                    map_cycle,
+                    unknown,
+                }))
+            },
+        )
+}
+
+/// The message keeps its own bytes, so it is made by parsing generated bytes.
+fn svc_delta_description() -> impl Strategy<Value = Message<'static>> {
+    let decoder = &delta_decoders()["delta_description_t\0"];
+    // Parsing a description needs these.
+    let required = ["name", "bits", "divisor", "flags"];
+
+    let entry: Vec<_> = decoder
+        .iter()
+        .map(|description| {
+            if required.contains(&&*String::from_utf8_lossy(&description.name)) {
+                delta_field(description).prop_map(Some).boxed()
+            } else {
+                proptest::option::of(delta_field(description)).boxed()
+            }
+        })
+        .collect();
+
+    (string_vec(), vec(entry, 0..6)).prop_map(|(name, entries)| {
+        let mut bw = BitWriter::new();
+        for fields in &entries {
+            write_raw_delta(fields, &mut bw);
+        }
+
+        let mut bytes = vec![EngineMessageType::SvcDeltaDescription as u8];
+        bytes.extend(name);
+        bytes.extend((entries.len() as u16).to_le_bytes());
+        bytes.extend(bw.get_u8_vec());
+
+        let bytes: &'static [u8] = bytes.leak();
+        let (_, message) = parse_single_netmsg(
+            bytes,
+            &mut delta_decoders().clone(),
+            &mut CustomMessages::new(),
+        )
+        .unwrap();
+
+        message
+    })
+}
+
+fn svc_client_data() -> impl Strategy<Value = Message<'static>> {
+    let weapon_data =
+        (bits(6), delta("weapon_data_t\0")).prop_map(|(weapon_index, weapon_data)| {
+            ClientDataWeaponData {
+                weapon_index,
+                weapon_data,
+            }
+        });
+
+    (
+        proptest::option::of(bits(8)),
+        delta("clientdata_t\0"),
+        vec(weapon_data, 0..4),
+    )
+        .prop_map(|(delta_update_mask, client_data, weapon_data)| {
+            engine(EngineMessage::SvcClientData(SvcClientData {
+                has_delta_update_mask: delta_update_mask.is_some(),
+                delta_update_mask,
+                client_data,
+                // Parsing has no empty list.
+                weapon_data: (!weapon_data.is_empty()).then_some(weapon_data),
+            }))
+        })
+}
+
+fn svc_pings() -> impl Strategy<Value = Message<'static>> {
+    vec((any::<u8>(), any::<u8>(), any::<u8>()), 0..8).prop_map(|pings| {
+        let mut pings: Vec<PingS> = pings
+            .into_iter()
+            .map(|(player_id, ping, loss)| PingS {
+                has_ping_data: true,
+                player_id: Some(player_id),
+                ping: Some(ping),
+                loss: Some(loss),
+            })
+            .collect();
+
+        // The end of the list is a ping too.
+        pings.push(PingS {
+            has_ping_data: false,
+            player_id: None,
+            ping: None,
+            loss: None,
+        });
+
+        engine(EngineMessage::SvcPings(SvcPings { pings }))
+    })
+}
+
+fn svc_spawn_static() -> impl Strategy<Value = Message<'static>> {
+    (
+        (
+            any::<i16>(),
+            any::<i8>(),
+            any::<i8>(),
+            any::<i16>(),
+            any::<i8>(),
+        ),
+        (any::<i16>(), any::<i8>(), any::<i16>(), any::<i8>()),
+        (any::<i16>(), any::<i8>(), any::<i8>(), bytes(3)),
+    )
+        .prop_map(
+            |(
+                (model_index, sequence, frame, color_map, skin),
+                (origin_x, rotation_x, origin_y, rotation_y),
+                (origin_z, rotation_z, has_render_mode, render_color),
+            )| {
+                engine(EngineMessage::SvcSpawnStatic(SvcSpawnStatic {
+                    model_index,
+                    sequence,
+                    frame,
+                    color_map,
+                    skin,
+                    origin_x,
+                    rotation_x,
+                    origin_y,
+                    rotation_y,
+                    origin_z,
+                    rotation_z,
+                    has_render_mode,
+                    render_color: (has_render_mode != 0).then_some(render_color),
+                }))
+            },
+        )
+}
+
+fn svc_event_reliable() -> impl Strategy<Value = Message<'static>> {
+    (bits(10), delta("event_t\0"), proptest::option::of(bits(16))).prop_map(
+        |(event_index, event_args, fire_time)| {
+            engine(EngineMessage::SvcEventReliable(SvcEventReliable {
+                event_index,
+                event_args,
+                has_fire_time: fire_time.is_some(),
+                fire_time,
+            }))
+        },
+    )
+}
+
+fn svc_spawn_baseline() -> impl Strategy<Value = Message<'static>> {
+    // 2047 with every bit after it set looks like the end of the list.
+    let entity = (0u16..2047, 0u8..4).prop_flat_map(|(entity_index, type_)| {
+        let decoder = if type_ & 1 != 0 {
+            entity_decoder(entity_index, false)
+        } else {
+            "custom_entity_state_t\0"
+        };
+
+        delta(decoder).prop_map(move |delta| EntityS {
+            entity_index,
+            index: to_bits(entity_index as u64, 11),
+            type_: to_bits(type_ as u64, 2),
+            delta,
+        })
+    });
+
+    (vec(entity, 0..6), vec(delta("entity_state_t\0"), 0..4)).prop_map(|(entities, extra_data)| {
+        engine(EngineMessage::SvcSpawnBaseline(SvcSpawnBaseline {
+            entities,
+            total_extra_data: to_bits(extra_data.len() as u64, 6),
+            extra_data,
+        }))
+    })
+}
+
+fn temp_entity_slice(entity_type: u8, data: &'static [u8]) -> TempEntityEntity<'static> {
+    match entity_type {
+        1 => TempEntityEntity::TeBeamEntPoint(data),
+        2 => TempEntityEntity::TeGunshot(data),
+        3 => TempEntityEntity::TeExplosion(data),
+        4 => TempEntityEntity::TeTarExplosion(data),
+        5 => TempEntityEntity::TeSmoke(data),
+        6 => TempEntityEntity::TeTracer(data),
+        7 => TempEntityEntity::TeLightning(data),
+        8 => TempEntityEntity::TeBeamEnts(data),
+        9 => TempEntityEntity::TeSparks(data),
+        10 => TempEntityEntity::TeLavaSplash(data),
+        11 => TempEntityEntity::TeTeleport(data),
+        12 => TempEntityEntity::TeExplosion2(data),
+        14 => TempEntityEntity::TeImplosion(data),
+        15 => TempEntityEntity::TeSpriteTrail(data),
+        16 => TempEntityEntity::TeSprite(data),
+        18 => TempEntityEntity::TeBeamSprite(data),
+        19 => TempEntityEntity::TeBeamTorus(data),
+        20 => TempEntityEntity::TeBeamDisk(data),
+        21 => TempEntityEntity::TeBeamCylinder(data),
+        22 => TempEntityEntity::TeBeamFollow(data),
+        23 => TempEntityEntity::TeGlowSprite(data),
+        24 => TempEntityEntity::TeBeamRing(data),
+        25 => TempEntityEntity::TeStreakSplash(data),
+        27 => TempEntityEntity::TeDLight(data),
+        28 => TempEntityEntity::TeELight(data),
+        30 => TempEntityEntity::TeLine(data),
+        31 => TempEntityEntity::TeBox(data),
+        99 => TempEntityEntity::TeKillBeam(data),
+        100 => TempEntityEntity::TeLargeFunnel(data),
+        101 => TempEntityEntity::TeBloodStream(data),
+        102 => TempEntityEntity::TeShowLine(data),
+        103 => TempEntityEntity::TeBlood(data),
+        104 => TempEntityEntity::TeDecal(data),
+        105 => TempEntityEntity::TeFizz(data),
+        106 => TempEntityEntity::TeModel(data),
+        107 => TempEntityEntity::TeExplodeModel(data),
+        108 => TempEntityEntity::TeBreakModel(data),
+        109 => TempEntityEntity::TeGunshotDecal(data),
+        110 => TempEntityEntity::TeSpriteSpray(data),
+        111 => TempEntityEntity::TeArmorRicochet(data),
+        112 => TempEntityEntity::TePlayerDecal(data),
+        113 => TempEntityEntity::TeBubbles(data),
+        114 => TempEntityEntity::TeBubbleTrail(data),
+        115 => TempEntityEntity::TeBloodSprite(data),
+        116 => TempEntityEntity::TeWorldDecal(data),
+        117 => TempEntityEntity::TeWorldDecalHigh(data),
+        118 => TempEntityEntity::TeDecalHigh(data),
+        119 => TempEntityEntity::TeProjectile(data),
+        120 => TempEntityEntity::TeSpray(data),
+        121 => TempEntityEntity::TePlayerSprites(data),
+        122 => TempEntityEntity::TeParticleBurst(data),
+        123 => TempEntityEntity::TeFireField(data),
+        124 => TempEntityEntity::TePlayerAttachment(data),
+        125 => TempEntityEntity::TeKillPlayerAttachment(data),
+        126 => TempEntityEntity::TeMultigunShot(data),
+        127 => TempEntityEntity::TeUserTracer(data),
+        _ => unreachable!(),
+    }
+}
+
+/// Type and length of every temp entity that is just bytes.
+const TEMP_ENTITY_SLICES: &[(u8, usize)] = &[
+    (1, 20),
+    (2, 6),
+    (3, 11),
+    (4, 6),
+    (5, 10),
+    (6, 12),
+    (7, 17),
+    (8, 16),
+    (9, 6),
+    (10, 6),
+    (11, 6),
+    (12, 8),
+    (14, 9),
+    (15, 19),
+    (16, 10),
+    (18, 16),
+    (19, 24),
+    (20, 24),
+    (21, 24),
+    (22, 10),
+    (23, 11),
+    (24, 16),
+    (25, 19),
+    (27, 12),
+    (28, 16),
+    (30, 17),
+    (31, 17),
+    (99, 2),
+    (100, 10),
+    (101, 14),
+    (102, 12),
+    (103, 14),
+    (104, 9),
+    (105, 5),
+    (106, 17),
+    (107, 13),
+    (108, 24),
+    (109, 9),
+    (110, 17),
+    (111, 7),
+    (112, 10),
+    (113, 10),
+    (114, 19),
+    (115, 12),
+    (116, 7),
+    (117, 7),
+    (118, 9),
+    (119, 16),
+    (120, 18),
+    (121, 5),
+    (122, 10),
+    (123, 9),
+    (124, 7),
+    (125, 1),
+    (126, 18),
+    (127, 15),
+];
+
+fn svc_temp_entity() -> impl Strategy<Value = Message<'static>> {
+    let beam_points = (
+        (vec(any::<i16>(), 3), vec(any::<i16>(), 3), any::<i16>()),
+        (
+            any::<u8>(),
+            any::<u8>(),
+            any::<u8>(),
+            any::<u8>(),
+            any::<u8>(),
+        ),
+        (bytes(4), any::<u8>()),
+    )
+        .prop_map(
+            |(
+                (start_position, end_position, sprite_index),
+                (start_frame, frame_rate, life, width, noise),
+                (color, speed),
+            )| {
+                (
+                    0u8,
+                    TempEntityEntity::TeBeamPoints(TeBeamPoints {
+                        start_position,
+                        end_position,
+                        sprite_index,
+                        start_frame,
+                        frame_rate,
+                        life,
+                        width,
+                        noise,
+                        color,
+                        speed,
+                    }),
+                )
+            },
+        );
+
+    let bsp_decal =
+        (bytes(8), any::<i16>(), bytes(2)).prop_map(|(unknown1, entity_index, unknown2)| {
+            (
+                13u8,
+                TempEntityEntity::TeBspDecal(TeBspDecal {
+                    unknown1,
+                    entity_index,
+                    unknown2: (entity_index != 0).then_some(unknown2),
+                }),
+            )
+        });
+
+    let text_message = (
+        (any::<i8>(), any::<i16>(), any::<i16>(), any::<i8>()),
+        (bytes(4), bytes(4), any::<i16>(), any::<i16>(), any::<i16>()),
+        (any::<i16>(), string()),
+    )
+        .prop_map(
+            |(
+                (channel, x, y, effect),
+                (text_color, effect_color, fade_in_time, fade_out_time, hold_time),
+                (effect_time, message),
+            )| {
+                (
+                    29u8,
+                    TempEntityEntity::TeTextMessage(TeTextMessage {
+                        channel,
+                        x,
+                        y,
+                        effect,
+                        text_color,
+                        effect_color,
+                        fade_in_time,
+                        fade_out_time,
+                        hold_time,
+                        effect_time: (effect != 0).then_some(effect_time),
+                        message,
+                    }),
+                )
+            },
+        );
+
+    let slice = proptest::sample::select(TEMP_ENTITY_SLICES).prop_flat_map(|(entity_type, len)| {
+        bytes(len).prop_map(move |data| (entity_type, temp_entity_slice(entity_type, data)))
+    });
+
+    prop_oneof![beam_points, bsp_decal, text_message, slice].prop_map(|(entity_type, entity)| {
+        engine(EngineMessage::SvcTempEntity(SvcTempEntity {
+            entity_type,
+            entity,
+        }))
+    })
+}
+
+fn svc_restore() -> impl Strategy<Value = Message<'static>> {
+    (string(), vec(string(), 0..4)).prop_map(|(save_name, map_names)| {
+        engine(EngineMessage::SvcRestore(SvcRestore {
+            save_name,
+            map_count: map_names.len() as u8,
+            map_names,
+        }))
+    })
+}
+
+fn svc_packet_entities() -> impl Strategy<Value = Message<'static>> {
+    let state = (entity_step(), any::<bool>(), proptest::option::of(bits(6)));
+
+    (bits(16), vec(state, 0..6))
+        .prop_flat_map(|(entity_count, states)| {
+            let mut entity_index = 0u16;
+
+            let entity_states: Vec<_> = states
+                .into_iter()
+                .map(|(step, has_custom_delta, baseline_index)| {
+                    let (is_absolute_entity_index, absolute_entity_index, entity_index_difference) =
+                        match step {
+                            EntityStep::Increment => {
+                                entity_index += 1;
+                                (None, None, None)
+                            }
+                            EntityStep::Absolute(index) => {
+                                entity_index = index;
+                                (Some(true), Some(to_bits(index as u64, 11)), None)
+                            }
+                            EntityStep::Difference(difference) => {
+                                entity_index += difference;
+                                (Some(false), None, Some(to_bits(difference as u64, 6)))
+                            }
+                        };
+
+                    let entity_index = entity_index;
+                    delta(entity_decoder(entity_index, has_custom_delta)).prop_map(move |delta| {
+                        EntityState {
+                            entity_index,
+                            increment_entity_number: is_absolute_entity_index.is_none(),
+                            is_absolute_entity_index,
+                            absolute_entity_index: absolute_entity_index.clone(),
+                            entity_index_difference: entity_index_difference.clone(),
+                            has_custom_delta,
+                            has_baseline_index: baseline_index.is_some(),
+                            baseline_index: baseline_index.clone(),
+                            delta,
+                        }
+                    })
+                })
+                .collect();
+
+            (Just(entity_count), entity_states)
+        })
+        .prop_map(|(entity_count, entity_states)| {
+            engine(EngineMessage::SvcPacketEntities(SvcPacketEntities {
+                entity_count,
+                entity_states,
+            }))
+        })
+}
+
+fn svc_delta_packet_entities() -> impl Strategy<Value = Message<'static>> {
+    let state = (
+        any::<bool>(),
+        prop_oneof![
+            (0u16..2048).prop_map(EntityStep::Absolute),
+            (1u16..64).prop_map(EntityStep::Difference),
+        ],
+        any::<bool>(),
+    );
+
+    (bits(16), bits(8), vec(state, 0..6))
+        .prop_flat_map(|(entity_count, delta_sequence, states)| {
+            let mut entity_index = 0u16;
+
+            let entity_states: Vec<BoxedStrategy<EntityStateDelta>> = states
+                .into_iter()
+                .map(|(remove_entity, step, has_custom_delta)| {
+                    let (absolute_entity_index, entity_index_difference) = match step {
+                        EntityStep::Absolute(index) => {
+                            entity_index = index;
+                            (Some(to_bits(index as u64, 11)), None)
+                        }
+                        EntityStep::Difference(difference) => {
+                            entity_index += difference;
+                            (None, Some(to_bits(difference as u64, 6)))
+                        }
+                        EntityStep::Increment => unreachable!(),
+                    };
+
+                    let state = EntityStateDelta {
+                        entity_index,
+                        remove_entity,
+                        is_absolute_entity_index: absolute_entity_index.is_some(),
+                        absolute_entity_index,
+                        entity_index_difference,
+                        has_custom_delta: None,
+                        delta: None,
+                    };
+
+                    if remove_entity {
+                        return Just(state).boxed();
+                    }
+
+                    delta(entity_decoder(entity_index, has_custom_delta))
+                        .prop_map(move |delta| EntityStateDelta {
+                            has_custom_delta: Some(has_custom_delta),
+                            delta: Some(delta),
+                            ..state.clone()
+                        })
+                        .boxed()
+                })
+                .collect();
+
+            (Just(entity_count), Just(delta_sequence), entity_states)
+        })
+        .prop_map(|(entity_count, delta_sequence, entity_states)| {
+            engine(EngineMessage::SvcDeltaPacketEntities(
+                SvcDeltaPacketEntities {
+                    entity_count,
+                    delta_sequence,
+                    entity_states,
+                },
+            ))
+        })
+}
+
+fn svc_resource_list() -> impl Strategy<Value = Message<'static>> {
+    let resource = (
+        (bits(4), string_vec(), bits(12), bits(24), bits(3)),
+        (bits(128), proptest::option::of(bits(256))),
+    )
+        .prop_map(
+            |((type_, name, index, size, flags), (md5_hash, extra_info))| {
+                let has_md5_hash = flags[2];
+
+                Resource {
+                    type_,
+                    name: bytes_to_bits(&name),
+                    index,
+                    size,
+                    flags,
+                    md5_hash: has_md5_hash.then_some(md5_hash),
+                    has_extra_info: extra_info.is_some(),
+                    extra_info,
+                }
+            },
+        );
+
+    // Consistencies without a check file flag end the list.
+    let consistency =
+        (any::<bool>(), bits(5), bits(10)).prop_map(|(is_short_index, short_index, long_index)| {
+            Consistency {
+                has_check_file_flag: true,
+                is_short_index: Some(is_short_index),
+                short_index: is_short_index.then_some(short_index),
+                long_index: (!is_short_index).then_some(long_index),
+            }
+        });
+
+    (vec(resource, 0..4), vec(consistency, 0..4)).prop_map(|(resources, consistencies)| {
+        engine(EngineMessage::SvcResourceList(SvcResourceList {
+            resource_count: to_bits(resources.len() as u64, 12),
+            resources,
+            consistencies,
+        }))
+    })
+}
+
+fn svc_new_movevars() -> impl Strategy<Value = Message<'static>> {
+    (
+        vec(any::<f32>(), 16),
+        any::<i32>(),
+        (any::<f32>(), any::<f32>()),
+        (vec(any::<f32>(), 3), vec(any::<f32>(), 3), string()),
+    )
+        .prop_map(
+            |(floats, footsteps, (roll_angle, roll_speed), (sky_color, sky_vec, sky_name))| {
+                engine(EngineMessage::SvcNewMovevars(SvcNewMoveVars {
+                    gravity: floats[0],
+                    stop_speed: floats[1],
+                    max_speed: floats[2],
+                    spectator_max_speed: floats[3],
+                    accelerate: floats[4],
+                    airaccelerate: floats[5],
+                    water_accelerate: floats[6],
+                    friction: floats[7],
+                    edge_friction: floats[8],
+                    water_friction: floats[9],
+                    ent_garvity: floats[10],
+                    bounce: floats[11],
+                    step_size: floats[12],
+                    max_velocity: floats[13],
+                    z_max: floats[14],
+                    wave_height: floats[15],
+                    footsteps,
+                    roll_angle,
+                    roll_speed,
+                    sky_color,
+                    sky_vec,
+                    sky_name,
+                }))
+            },
+        )
+}
+
+fn svc_customization() -> impl Strategy<Value = Message<'static>> {
+    (
+        (any::<u8>(), any::<u8>(), string()),
+        (any::<u16>(), any::<u32>(), any::<u8>(), bytes(16)),
+    )
+        .prop_map(
+            |((player_index, type_, name), (index, download_size, flags, md5_hash))| {
+                engine(EngineMessage::SvcCustomization(SvcCustomization {
+                    player_index,
+                    type_,
+                    name,
+                    index,
+                    download_size,
+                    flags,
+                    md5_hash: (flags & 4 != 0).then_some(md5_hash),
+                }))
+            },
+        )
+}
+
+/// Message with a user message registered for it or not.
+fn user_message() -> impl Strategy<Value = (Message<'static>, CustomMessages)> {
+    (64u8..=255, vec(any::<u8>(), 0..64), 0..3, bytes(16)).prop_map(
+        |(id, data, registration, name)| {
+            let mut custom_messages = CustomMessages::new();
+
+            let size = match registration {
+                0 => None,
+                1 => Some(-1),
+                _ => Some(data.len() as i8),
+            };
+
+            if let Some(size) = size {
+                custom_messages.insert(
+                    id,
+                    SvcNewUserMsg {
+                        index: id,
+                        size,
+                        name,
+                    },
+                );
+            }
+
+            let message = Message::UserMessage(NetMsgUserMessage {
+                id,
+                name: if size.is_some() { name } else { &b"\0"[..] },
+                data: data.leak(),
+            });
+
+            (message, custom_messages)
+        },
+    )
+}
+
+proptest! {
+    #[test]
+    fn no_payload(message in prop_oneof![
+        Just(EngineMessage::SvcBad),
+        Just(EngineMessage::SvcNop),
+        Just(EngineMessage::SvcDamage),
+        Just(EngineMessage::SvcKilledMonster),
+        Just(EngineMessage::SvcFoundSecret),
+        Just(EngineMessage::SvcIntermission),
+        Just(EngineMessage::SvcChoke),
+    ]) {
+        round_trip(engine(message), &CustomMessages::new())?;
+    }
+
+    #[test]
+    fn disconnect(reason in string()) {
+        round_trip(
+            engine(EngineMessage::SvcDisconnect(SvcDisconnect { reason })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn event(message in svc_event()) {
+        round_trip(message, &CustomMessages::new())?;
+    }
+
+    #[test]
+    fn version(protocol_version in any::<u32>()) {
+        round_trip(
+            engine(EngineMessage::SvcVersion(SvcVersion { protocol_version })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn set_view(entity_index in any::<i16>()) {
+        round_trip(
+            engine(EngineMessage::SvcSetView(SvcSetView { entity_index })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn sound(message in svc_sound()) {
+        round_trip(message, &CustomMessages::new())?;
+    }
+
+    #[test]
+    fn time(time in any::<f32>()) {
+        round_trip(
+            engine(EngineMessage::SvcTime(SvcTime { time })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn print(message in string()) {
+        round_trip(
+            engine(EngineMessage::SvcPrint(SvcPrint { message })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn stuff_text(command in string()) {
+        round_trip(
+            engine(EngineMessage::SvcStuffText(SvcStuffText { command })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn set_angle(pitch in any::<i16>(), yaw in any::<i16>(), roll in any::<i16>()) {
+        round_trip(
+            engine(EngineMessage::SvcSetAngle(SvcSetAngle { pitch, yaw, roll })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn server_info(message in svc_server_info()) {
+        round_trip(message, &CustomMessages::new())?;
+    }
+
+    #[test]
+    fn light_style(index in any::<u8>(), light_info in string()) {
+        round_trip(
+            engine(EngineMessage::SvcLightStyle(SvcLightStyle { index, light_info })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn update_user_info(
+        index in any::<u8>(),
+        id in any::<u32>(),
+        user_info in string(),
+        cd_key_hash in bytes(16),
+    ) {
+        round_trip(
+            engine(EngineMessage::SvcUpdateUserInfo(SvcUpdateUserInfo {
+                index,
+                id,
+                user_info,
+                cd_key_hash,
+            })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn delta_description(message in svc_delta_description()) {
+        round_trip(message, &CustomMessages::new())?;
+    }
+
+    #[test]
+    fn client_data(message in svc_client_data()) {
+        round_trip(message, &CustomMessages::new())?;
+    }
+
+    #[test]
+    fn stop_sound(entity_index in any::<i16>()) {
+        round_trip(
+            engine(EngineMessage::SvcStopSound(SvcStopSound { entity_index })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn pings(message in svc_pings()) {
+        round_trip(message, &CustomMessages::new())?;
+    }
+
+    #[test]
+    fn particle(
+        origin in vec(any::<i16>(), 3),
+        direction in bytes(3),
+        count in any::<u8>(),
+        color in any::<u8>(),
+    ) {
+        round_trip(
+            engine(EngineMessage::SvcParticle(SvcParticle {
+                origin,
+                direction,
+                count,
+                color,
+            })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn spawn_static(message in svc_spawn_static()) {
+        round_trip(message, &CustomMessages::new())?;
+    }
+
+    #[test]
+    fn event_reliable(message in svc_event_reliable()) {
+        round_trip(message, &CustomMessages::new())?;
+    }
+
+    #[test]
+    fn spawn_baseline(message in svc_spawn_baseline()) {
+        round_trip(message, &CustomMessages::new())?;
+    }
+
+    #[test]
+    fn temp_entity(message in svc_temp_entity()) {
+        round_trip(message, &CustomMessages::new())?;
+    }
+
+    #[test]
+    fn set_pause(is_paused in any::<i8>()) {
+        round_trip(
+            engine(EngineMessage::SvcSetPause(SvcSetPause { is_paused })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn sign_on_num(sign in any::<i8>()) {
+        round_trip(
+            engine(EngineMessage::SvcSignOnNum(SvcSignOnNum { sign })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn center_print(message in string()) {
+        round_trip(
+            engine(EngineMessage::SvcCenterPrint(SvcCenterPrint { message })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn spawn_static_sound(
+        origin in vec(any::<i16>(), 3),
+        sound_index in any::<u16>(),
+        (volume, attenuation) in (any::<u8>(), any::<u8>()),
+        entity_index in any::<u16>(),
+        (pitch, flags) in (any::<u8>(), any::<u8>()),
+    ) {
+        round_trip(
+            engine(EngineMessage::SvcSpawnStaticSound(SvcSpawnStaticSound {
+                origin,
+                sound_index,
+                volume,
+                attenuation,
+                entity_index,
+                pitch,
+                flags,
+            })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn finale(text in string()) {
+        round_trip(
+            engine(EngineMessage::SvcFinale(SvcFinale { text })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn cd_track(track in any::<i8>(), loop_track in any::<i8>()) {
+        round_trip(
+            engine(EngineMessage::SvcCdTrack(SvcCdTrack { track, loop_track })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn restore(message in svc_restore()) {
+        round_trip(message, &CustomMessages::new())?;
+    }
+
+    #[test]
+    fn cutscene(text in string()) {
+        round_trip(
+            engine(EngineMessage::SvcCutscene(SvcCutscene { text })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn weapon_anim(sequence_number in any::<i8>(), weapon_model_body_group in any::<i8>()) {
+        round_trip(
+            engine(EngineMessage::SvcWeaponAnim(SvcWeaponAnim {
+                sequence_number,
+                weapon_model_body_group,
+            })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn decal_name(position_index in any::<u8>(), decal_name in string()) {
+        round_trip(
+            engine(EngineMessage::SvcDecalName(SvcDecalName {
+                position_index,
+                decal_name,
+            })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn room_type(room_type in any::<u16>()) {
+        round_trip(
+            engine(EngineMessage::SvcRoomType(SvcRoomType { room_type })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn add_angle(angle_to_add in any::<i16>()) {
+        round_trip(
+            engine(EngineMessage::SvcAddAngle(SvcAddAngle { angle_to_add })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn new_user_msg(index in any::<u8>(), size in any::<i8>(), name in bytes(16)) {
+        round_trip(
+            engine(EngineMessage::SvcNewUserMsg(SvcNewUserMsg { index, size, name })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn packet_entities(message in svc_packet_entities()) {
+        round_trip(message, &CustomMessages::new())?;
+    }
+
+    #[test]
+    fn delta_packet_entities(message in svc_delta_packet_entities()) {
+        round_trip(message, &CustomMessages::new())?;
+    }
+
+    #[test]
+    fn resource_list(message in svc_resource_list()) {
+        round_trip(message, &CustomMessages::new())?;
+    }
+
+    #[test]
+    fn new_movevars(message in svc_new_movevars()) {
+        round_trip(message, &CustomMessages::new())?;
+    }
+
+    #[test]
+    fn resource_request(spawn_count in any::<i32>(), unknown in vec(any::<u8>(), 4)) {
+        round_trip(
+            engine(EngineMessage::SvcResourceRequest(SvcResourceRequest {
+                spawn_count,
+                unknown,
+            })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn customization(message in svc_customization()) {
+        round_trip(message, &CustomMessages::new())?;
+    }
+
+    #[test]
+    fn crosshair_angle(pitch in any::<i16>(), yaw in any::<i16>()) {
+        round_trip(
+            engine(EngineMessage::SvcCrosshairAngle(SvcCrosshairAngle { pitch, yaw })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn sound_fade(
+        initial_percent in any::<u8>(),
+        hold_time in any::<u8>(),
+        fade_out_time in any::<u8>(),
+        fade_in_time in any::<u8>(),
+    ) {
+        round_trip(
+            engine(EngineMessage::SvcSoundFade(SvcSoundFade {
+                initial_percent,
+                hold_time,
+                fade_out_time,
+                fade_in_time,
+            })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn file_txfer_failed(file_name in string()) {
+        round_trip(
+            engine(EngineMessage::SvcFileTxferFailed(SvcFileTxferFailed { file_name })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn hltv(mode in any::<u8>()) {
+        round_trip(
+            engine(EngineMessage::SvcHltv(SvcHltv { mode })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn director(flag in any::<u8>(), message in vec(any::<u8>(), 0..64)) {
+        round_trip(
+            engine(EngineMessage::SvcDirector(SvcDirector {
+                // The flag counts too.
+                length: message.len() as u8 + 1,
+                flag,
+                message: message.leak(),
+            })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn voice_init(codec_name in string(), quality in any::<i8>()) {
+        round_trip(
+            engine(EngineMessage::SvcVoiceInit(SvcVoiceInit { codec_name, quality })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn voice_data(player_index in any::<u8>(), data in vec(any::<u8>(), 0..64)) {
+        round_trip(
+            engine(EngineMessage::SvcVoiceData(SvcVoiceData {
+                player_index,
+                size: data.len() as u16,
+                data: data.leak(),
+            })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn send_extra_info(fallback_dir in string(), can_cheat in any::<u8>()) {
+        round_trip(
+            engine(EngineMessage::SvcSendExtraInfo(SvcSendExtraInfo {
+                fallback_dir,
+                can_cheat,
+            })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn time_scale(time_scale in any::<f32>()) {
+        round_trip(
+            engine(EngineMessage::SvcTimeScale(SvcTimeScale { time_scale })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn resource_location(download_url in string()) {
+        round_trip(
+            engine(EngineMessage::SvcResourceLocation(SvcResourceLocation { download_url })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn send_cvar_value(name in string()) {
+        round_trip(
+            engine(EngineMessage::SvcSendCvarValue(SvcSendCvarValue { name })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn send_cvar_value_2(request_id in any::<u32>(), name in string()) {
+        round_trip(
+            engine(EngineMessage::SvcSendCvarValue2(SvcSendCvarValue2 { request_id, name })),
+            &CustomMessages::new(),
+        )?;
+    }
+
+    #[test]
+    fn user_message((message, custom_messages) in user_message()) {
+        round_trip(message, &custom_messages)?;
+    }
+}
@@ -0,0 +1,145 @@
+//! Passes of one pipeline see each other's edits and no pass changes nothing.
+
+mod common;
+
+use std::cell::Cell;
+use std::fs;
+
+use hldemo::{Frame, FrameData, NetMsgData};
+
+use demosuperimpose_goldsrc::demo_doer::pipeline::{DemoPass, PassContext, Pipeline};
+use demosuperimpose_goldsrc::demo_doer::write_demo_file;
+use demosuperimpose_goldsrc::types::{EngineMessage, Message, SvcPrint};
+
+use common::{hldm, parsed_netmsgs, temp_path};
+
+const FRAMETIME: f32 = 0.125;
+const TEXT: &[u8] = b"pipeline\0";
+
+fn is_print(message: Option<&Message>) -> bool {
+    matches!(
+        message,
+        Some(Message::EngineMessage(EngineMessage::SvcPrint(print))) if print.message == TEXT
+    )
+}
+
+/// Adds a print and counts netmsgs with the frametime of [`SetFrametime`].
+struct AddPrint<'s> {
+    saw_frametime: &'s Cell<usize>,
+}
+
+impl<'a> DemoPass<'a> for AddPrint<'_> {
+    fn messages(
+        &mut self,
+        _ctx: &PassContext<'_, 'a>,
+        data: &mut NetMsgData<'a>,
+        messages: &mut Vec<Message<'a>>,
+    ) -> Result<bool, String> {
+        if data.info.ref_params.frametime == FRAMETIME {
+            self.saw_frametime.set(self.saw_frametime.get() + 1);
+        }
+
+        messages.push(Message::EngineMessage(EngineMessage::SvcPrint(SvcPrint {
+            message: TEXT,
+        })));
+
+        Ok(true)
+    }
+}
+
+/// Sets every frametime and counts netmsgs with the print of [`AddPrint`].
+struct SetFrametime<'s> {
+    saw_print: &'s Cell<usize>,
+}
+
+impl<'a> DemoPass<'a> for SetFrametime<'_> {
+    fn frame(&mut self, _ctx: &PassContext<'_, 'a>, frame: &mut Frame<'a>) -> Result<(), String> {
+        if let FrameData::NetMsg((_, data)) = &mut frame.data {
+            data.info.ref_params.frametime = FRAMETIME;
+        }
+
+        Ok(())
+    }
+
+    fn messages(
+        &mut self,
+        _ctx: &PassContext<'_, 'a>,
+        _data: &mut NetMsgData<'a>,
+        messages: &mut Vec<Message<'a>>,
+    ) -> Result<bool, String> {
+        if is_print(messages.last()) {
+            self.saw_print.set(self.saw_print.get() + 1);
+        }
+
+        Ok(false)
+    }
+}
+
+#[test]
+fn passes_see_each_others_edits() {
+    let mut demo = hldm();
+    let netmsg_count = parsed_netmsgs(&demo).len();
+
+    let saw_frametime = Cell::new(0);
+    let saw_print = Cell::new(0);
+
+    Pipeline::new()
+        .add_pass(AddPrint {
+            saw_frametime: &saw_frametime,
+        })
+        .add_pass(SetFrametime {
+            saw_print: &saw_print,
+        })
+        .run(&mut demo)
+        .unwrap();
+
+    assert_eq!(saw_frametime.get(), netmsg_count);
+    assert_eq!(saw_print.get(), netmsg_count);
+
+    // And the print is written.
+    for (entry_idx, frame_idx, messages) in parsed_netmsgs(&demo) {
+        assert!(
+            is_print(messages.last()),
+            "entry {} frame {}",
+            entry_idx,
+            frame_idx
+        );
+    }
+}
+
+#[test]
+fn no_passes_is_byte_identical() {
+    let mut demo = hldm();
+    Pipeline::new().run(&mut demo).unwrap();
+
+    let original_path = temp_path("original.dem");
+    let run_path = temp_path("run.dem");
+    write_demo_file(hldm(), &original_path).unwrap();
+    write_demo_file(demo, &run_path).unwrap();
+
+    assert!(fs::read(original_path).unwrap() == fs::read(run_path).unwrap());
+}
+
+#[test]
+fn unparseable_netmsg_is_an_error() {
+    let mut demo = hldm();
+
+    let frame_idx = demo.directory.entries[1]
+        .frames
+        .iter()
+        .position(|frame| matches!(frame.data, FrameData::NetMsg(_)))
+        .unwrap();
+
+    if let FrameData::NetMsg((_, data)) = &mut demo.directory.entries[1].frames[frame_idx].data {
+        // SvcPrint without the end of its string.
+        data.msg = &[8, b'h', b'i'];
+    }
+
+    let err = Pipeline::new().run(&mut demo).unwrap_err();
+
+    assert!(
+        err.contains(&format!("entry 1 frame {}", frame_idx)),
+        "{}",
+        err
+    );
+}
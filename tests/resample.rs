@@ -0,0 +1,154 @@
+//! Resampling keeps frames on an even grid and refuses framerates it can't step by.
+
+mod common;
+
+use hldemo::{Demo, FrameData};
+
+use demosuperimpose_goldsrc::demo_doer::{message_name, resample::resample};
+use demosuperimpose_goldsrc::utils::{lerp_angles, lerp_vec};
+
+use common::{close, hldm, parsed_netmsgs};
+
+const FPS: f32 = 20.;
+const STEP: f32 = 1. / FPS;
+
+fn resampled<'a>() -> Demo<'a> {
+    let mut demo = hldm();
+    resample(&mut demo, FPS).unwrap();
+    demo
+}
+
+/// Time, origin and viewangles of every `ClientData` frame in playback.
+fn client_data(demo: &Demo) -> Vec<(f32, [f32; 3], [f32; 3])> {
+    demo.directory.entries[1]
+        .frames
+        .iter()
+        .filter_map(|frame| match &frame.data {
+            FrameData::ClientData(data) => Some((frame.time, data.origin, data.viewangles)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn bad_fps_is_refused() {
+    for fps in [0., -30., f32::NAN, f32::INFINITY] {
+        let mut demo = hldm();
+        assert!(resample(&mut demo, fps).is_err(), "{}", fps);
+    }
+}
+
+#[test]
+fn frames_are_evenly_spaced() {
+    let demo = resampled();
+
+    let mut times: Vec<f32> = demo.directory.entries[1]
+        .frames
+        .iter()
+        // Kept where it was.
+        .filter(|frame| !matches!(frame.data, FrameData::DemoStart))
+        .map(|frame| frame.time)
+        .collect();
+    times.dedup();
+
+    for pair in times.windows(2) {
+        let steps = (pair[1] - pair[0]) / STEP;
+        assert!((steps - steps.round()).abs() < 1e-2, "{:?}", pair);
+    }
+}
+
+#[test]
+fn client_data_is_interpolated() {
+    let source = client_data(&hldm());
+    let resampled = client_data(&resampled());
+
+    let mut between = 0;
+
+    // First one has nothing before it.
+    for &(time, origin, viewangles) in resampled.iter().skip(1) {
+        // Kept frame is the first at or after the slot, it is blended with the one before.
+        let next = source.iter().position(|(t, _, _)| *t >= time).unwrap();
+        let (prev_time, prev_origin, prev_viewangles) = source[next - 1];
+        let (next_time, next_origin, next_viewangles) = source[next];
+
+        let t = if next_time > prev_time {
+            (time - prev_time) / (next_time - prev_time)
+        } else {
+            1.
+        };
+        if t > 0. && t < 1. {
+            between += 1;
+        }
+
+        let expected_origin = lerp_vec(prev_origin, next_origin, t);
+        let expected_viewangles = lerp_angles(prev_viewangles, next_viewangles, t);
+
+        for i in 0..3 {
+            assert!(close(origin[i], expected_origin[i]), "{} origin", time);
+            assert!(
+                close(viewangles[i], expected_viewangles[i]),
+                "{} viewangles",
+                time
+            );
+        }
+    }
+
+    assert!(between > 0, "no slot fell between two source frames");
+}
+
+/// Messages that are not redone every frame, with the time of the frame they are in.
+fn reliable_messages(demo: &Demo) -> Vec<(f32, String)> {
+    parsed_netmsgs(demo)
+        .into_iter()
+        .filter(|(entry_idx, _, _)| *entry_idx == 1)
+        .flat_map(|(entry_idx, frame_idx, messages)| {
+            let time = demo.directory.entries[entry_idx].frames[frame_idx].time;
+
+            messages
+                .iter()
+                .map(message_name)
+                .filter(|name| {
+                    ![
+                        "SvcPacketEntities",
+                        "SvcDeltaPacketEntities",
+                        "SvcNop",
+                        "SvcTime",
+                        "SvcClientData",
+                    ]
+                    .contains(&name.as_str())
+                })
+                .map(|name| (time, name))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[test]
+fn dropped_messages_move_to_next_kept_frame() {
+    let source = reliable_messages(&hldm());
+    let resampled = reliable_messages(&resampled());
+
+    // Nothing lost and still in order.
+    let names = |messages: &[(f32, String)]| -> Vec<String> {
+        messages.iter().map(|(_, name)| name.clone()).collect()
+    };
+    assert_eq!(names(&source), names(&resampled));
+
+    let mut moved = 0;
+
+    for ((source_time, name), (time, _)) in source.iter().zip(&resampled) {
+        // Kept frames move back onto their slot, dropped ones go to the next slot.
+        if time > source_time {
+            assert!(
+                time - source_time < STEP + 1e-3,
+                "{} from {} to {}",
+                name,
+                source_time,
+                time
+            );
+            moved += 1;
+        }
+    }
+
+    assert!(moved > 0, "no message came from a dropped frame");
+}
@@ -0,0 +1,201 @@
+//! Every demo in the fixture folders goes through parse -> write -> parse.
+//!
+//! Each netmsg has to be written back byte for byte, parse back to the same messages, and the
+//! whole demo has to survive `DemoWriter`. Drop more demos in `tests/fixtures/` to cover more.
+
+mod common;
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use hldemo::{Demo, FrameData};
+
+use demosuperimpose_goldsrc::demo_doer::{message_name, open_demo_file, write_demo_file};
+use demosuperimpose_goldsrc::netmsg_doer::{
+    parse_netmsg, parse_single_netmsg, utils::get_initial_delta, write_single_netmsg,
+};
+use demosuperimpose_goldsrc::types::SvcNewUserMsg;
+
+const FIXTURE_FOLDERS: &[&str] = &["example", "tests/fixtures"];
+
+#[derive(Default)]
+struct Coverage {
+    count: usize,
+    mismatches: usize,
+}
+
+fn fixtures() -> Vec<PathBuf> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+    let mut res: Vec<PathBuf> = FIXTURE_FOLDERS
+        .iter()
+        .filter_map(|folder| fs::read_dir(root.join(folder)).ok())
+        .flatten()
+        .map(|file| file.unwrap().path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "dem"))
+        .collect();
+
+    res.sort();
+    res
+}
+
+/// Writes every message back on its own and compares with the bytes it was parsed from.
+fn round_trip_netmsg(
+    demo: &Demo,
+    coverage: &mut BTreeMap<String, Coverage>,
+    failures: &mut Vec<String>,
+) {
+    let mut delta_decoders = get_initial_delta();
+    let mut custom_messages = HashMap::<u8, SvcNewUserMsg>::new();
+
+    // What the rewritten netmsg is parsed with.
+    let mut reparse_delta_decoders = get_initial_delta();
+    let mut reparse_custom_messages = HashMap::<u8, SvcNewUserMsg>::new();
+
+    for (entry_idx, entry) in demo.directory.entries.iter().enumerate() {
+        for (frame_idx, frame) in entry.frames.iter().enumerate() {
+            let data = match &frame.data {
+                FrameData::NetMsg((_, data)) => data,
+                _ => continue,
+            };
+
+            let mut rewritten: Vec<u8> = vec![];
+            let mut messages = vec![];
+            let mut i = data.msg;
+
+            while !i.is_empty() {
+                let (rest, message) =
+                    parse_single_netmsg(i, &mut delta_decoders, &mut custom_messages).unwrap();
+                let original = &i[..i.len() - rest.len()];
+                i = rest;
+
+                let name = message_name(&message);
+                let write = write_single_netmsg(message.clone(), &delta_decoders, &custom_messages);
+
+                let message_coverage = coverage.entry(name.clone()).or_default();
+                message_coverage.count += 1;
+
+                if write != original {
+                    message_coverage.mismatches += 1;
+
+                    // One of each is enough to go on.
+                    if message_coverage.mismatches == 1 {
+                        failures.push(format!(
+                            "entry {} frame {} {}: wrote {:?}, expected {:?}",
+                            entry_idx, frame_idx, name, write, original
+                        ));
+                    }
+                }
+
+                rewritten.extend(write);
+                messages.push(message);
+            }
+
+            let rewritten: &'static [u8] = rewritten.leak();
+            let (_, reparsed) = parse_netmsg(
+                rewritten,
+                &mut reparse_delta_decoders,
+                &mut reparse_custom_messages,
+            )
+            .unwrap();
+
+            if common::comparable(&messages) != common::comparable(&reparsed) {
+                failures.push(format!(
+                    "entry {} frame {}: re-parse is different",
+                    entry_idx, frame_idx
+                ));
+            }
+        }
+    }
+}
+
+/// Writes the demo with `DemoWriter` and compares it frame by frame after reading it back.
+fn round_trip_demo(path: &Path, failures: &mut Vec<String>) {
+    let demo = open_demo_file(path).unwrap();
+
+    let out = std::env::temp_dir().join(format!(
+        "round_trip_{}",
+        path.file_name().unwrap().to_str().unwrap()
+    ));
+    write_demo_file(open_demo_file(path).unwrap(), &out).unwrap();
+    let written = open_demo_file(&out).unwrap();
+    let _ = fs::remove_file(&out);
+
+    if format!("{:?}", demo.header) != format!("{:?}", written.header) {
+        failures.push("header is different".to_owned());
+    }
+
+    let (entries, written_entries) = (&demo.directory.entries, &written.directory.entries);
+    if entries.len() != written_entries.len() {
+        failures.push(format!(
+            "entry count {} -> {}",
+            entries.len(),
+            written_entries.len()
+        ));
+        return;
+    }
+
+    for (entry_idx, (entry, written_entry)) in entries.iter().zip(written_entries).enumerate() {
+        if entry.frames.len() != written_entry.frames.len() {
+            failures.push(format!(
+                "entry {} frame count {} -> {}",
+                entry_idx,
+                entry.frames.len(),
+                written_entry.frames.len()
+            ));
+            continue;
+        }
+
+        for (frame_idx, (frame, written_frame)) in
+            entry.frames.iter().zip(&written_entry.frames).enumerate()
+        {
+            if format!("{:?}", frame) != format!("{:?}", written_frame) {
+                failures.push(format!(
+                    "entry {} frame {} is different after DemoWriter",
+                    entry_idx, frame_idx
+                ));
+                break;
+            }
+        }
+    }
+}
+
+// Demos run one after another, parsing leans on a global max client count.
+#[test]
+fn round_trip_fixtures() {
+    let fixtures = fixtures();
+    assert!(!fixtures.is_empty(), "no demos in {:?}", FIXTURE_FOLDERS);
+
+    let mut coverage = BTreeMap::<String, Coverage>::new();
+    let mut failures: Vec<String> = vec![];
+
+    for path in &fixtures {
+        let mut demo_failures = vec![];
+
+        let demo = open_demo_file(path).unwrap();
+        round_trip_netmsg(&demo, &mut coverage, &mut demo_failures);
+        round_trip_demo(path, &mut demo_failures);
+
+        failures.extend(
+            demo_failures
+                .into_iter()
+                .map(|failure| format!("{}: {}", path.display(), failure)),
+        );
+    }
+
+    println!("{:<32} {:>8} {:>10}", "message", "count", "mismatches");
+    for (name, coverage) in &coverage {
+        println!(
+            "{:<32} {:>8} {:>10}",
+            name, coverage.count, coverage.mismatches
+        );
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} round trip failures:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
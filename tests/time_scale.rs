@@ -0,0 +1,68 @@
+//! Time scaling stretches frame times once, either by rewriting them or through the engine.
+
+use std::path::Path;
+
+use hldemo::Demo;
+
+use demosuperimpose_goldsrc::demo_doer::{
+    open_demo_file,
+    time_scale::{time_scale, SpeedCurve, TimeScaleOptions},
+};
+
+fn hldm<'a>() -> Demo<'a> {
+    open_demo_file(Path::new(env!("CARGO_MANIFEST_DIR")).join("example/hldm.dem")).unwrap()
+}
+
+fn last_time(demo: &Demo) -> f32 {
+    demo.directory
+        .entries
+        .iter()
+        .flat_map(|entry| entry.frames.iter())
+        .map(|frame| frame.time)
+        .fold(0., f32::max)
+}
+
+/// Where playback starts, LOADING is left alone.
+fn first_time(demo: &Demo) -> f32 {
+    demo.directory.entries[1].frames[0].time
+}
+
+#[test]
+fn speeds_have_to_be_positive() {
+    assert!(TimeScaleOptions::new(SpeedCurve::Constant(0.)).is_err());
+    assert!(TimeScaleOptions::new(SpeedCurve::Constant(-1.)).is_err());
+    assert!(TimeScaleOptions::new(SpeedCurve::Constant(f32::NAN)).is_err());
+    assert!(TimeScaleOptions::new(SpeedCurve::Linear(1., 0.)).is_err());
+    assert!(TimeScaleOptions::new(SpeedCurve::Ramp {
+        speed: 0.5,
+        ramp: -1.
+    })
+    .is_err());
+
+    assert!(TimeScaleOptions::new(SpeedCurve::Linear(1., 0.25)).is_ok());
+}
+
+#[test]
+fn half_speed_doubles_the_range() {
+    let start = first_time(&hldm());
+    let original = last_time(&hldm());
+
+    let mut demo = hldm();
+    let options = TimeScaleOptions::new(SpeedCurve::Constant(0.5)).unwrap();
+    time_scale(&mut demo, 0., original, options.get());
+
+    let expected = start + (original - start) * 2.;
+    assert!((last_time(&demo) - expected).abs() < 0.1);
+}
+
+#[test]
+fn svc_time_scale_keeps_times() {
+    let original = last_time(&hldm());
+
+    let mut demo = hldm();
+    let mut options = TimeScaleOptions::new(SpeedCurve::Constant(0.5)).unwrap();
+    options.add_svc_time_scale();
+    time_scale(&mut demo, 0., original, options.get());
+
+    assert!((last_time(&demo) - original).abs() < 1e-3);
+}
@@ -0,0 +1,99 @@
+//! Trimming keeps a playable range starting from 0 and leaves LOADING alone.
+
+mod common;
+
+use hldemo::{Demo, FrameData};
+
+use demosuperimpose_goldsrc::demo_doer::trim::trim;
+use demosuperimpose_goldsrc::types::{EngineMessage, Message};
+
+use common::{hldm, parsed_netmsgs};
+
+fn last_time(demo: &Demo) -> f32 {
+    demo.directory.entries[1]
+        .frames
+        .iter()
+        .map(|frame| frame.time)
+        .fold(f32::MIN, f32::max)
+}
+
+/// Middle third of the demo.
+fn trimmed<'a>() -> (Demo<'a>, f32, f32) {
+    let mut demo = hldm();
+    let length = last_time(&demo);
+    let (start, end) = (length / 3., length * 2. / 3.);
+
+    trim(&mut demo, start, end).unwrap();
+
+    (demo, start, end)
+}
+
+#[test]
+fn first_entities_are_a_full_update() {
+    let (demo, _, _) = trimmed();
+
+    let first = parsed_netmsgs(&demo)
+        .into_iter()
+        .filter(|(entry_idx, _, _)| *entry_idx == 1)
+        .flat_map(|(_, _, messages)| messages)
+        .find(|message| {
+            matches!(
+                message,
+                Message::EngineMessage(
+                    EngineMessage::SvcPacketEntities(_) | EngineMessage::SvcDeltaPacketEntities(_)
+                )
+            )
+        })
+        .unwrap();
+
+    match first {
+        Message::EngineMessage(EngineMessage::SvcPacketEntities(packet)) => {
+            assert!(!packet.entity_states.is_empty())
+        }
+        _ => panic!("first entity update is a delta"),
+    }
+}
+
+#[test]
+fn times_start_from_0() {
+    let (demo, start, end) = trimmed();
+
+    let frames: Vec<_> = demo.directory.entries[1]
+        .frames
+        .iter()
+        .filter(|frame| !matches!(frame.data, FrameData::DemoStart))
+        .collect();
+
+    assert_eq!(frames[0].time, 0.);
+    assert_eq!(frames[0].frame, 0);
+    assert!(frames
+        .iter()
+        .all(|frame| frame.time >= 0. && frame.time <= end - start + 1e-3));
+    assert!(frames.windows(2).all(|pair| pair[0].frame <= pair[1].frame));
+}
+
+#[test]
+fn loading_is_untouched() {
+    let before = hldm();
+    let (after, _, _) = trimmed();
+
+    assert_eq!(
+        format!("{:?}", before.directory.entries[0].frames),
+        format!("{:?}", after.directory.entries[0].frames)
+    );
+}
+
+#[test]
+fn bad_range_is_an_error() {
+    let length = last_time(&hldm());
+
+    for (start, end) in [
+        (2., 1.),
+        (1., 1.),
+        (f32::NAN, 1.),
+        (0., length + 10.),
+        (length + 5., length + 10.),
+    ] {
+        assert!(trim(&mut hldm(), start, end).is_err(), "{} {}", start, end);
+    }
+}